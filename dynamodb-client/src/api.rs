@@ -0,0 +1,279 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+use aws_sdk_dynamodb::model::AttributeValue;
+use aws_sdk_dynamodb::output::{
+    CreateTableOutput, DeleteItemOutput, DeleteTableOutput, GetItemOutput, ListTablesOutput,
+    PutItemOutput, TransactWriteItemsOutput, UpdateItemOutput, UpdateTableOutput,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::client::{Client, ExistsTableResultType, ItemStream, ScanPageStream, TableNameStream};
+use crate::error::DynamoDbError;
+use crate::item::{from_item, to_item};
+use crate::query::batch_get::{BatchGetOutput, BatchGetQuery};
+use crate::query::batch_write::{BatchWriteOutput, BatchWriteQuery};
+use crate::query::create_table::CreateTableQuery;
+use crate::query::delete_item::DeleteItemQuery;
+use crate::query::delete_table::DeleteTableQuery;
+use crate::query::describe_table::{DescribeTableQuery, TableDescription};
+use crate::query::dynamodb_query::Keys;
+use crate::query::expression::{Condition, UpdateExpression};
+use crate::query::get_item::GetItemQuery;
+use crate::query::list_tables::ListTablesQuery;
+use crate::query::parallel_scan::ParallelScanQuery;
+use crate::query::put_item::{Items, PutItemQuery};
+use crate::query::query::QueryQuery;
+use crate::query::scan::ScanQuery;
+use crate::query::transact_write::TransactWriteQuery;
+use crate::query::update_item::UpdateItemQuery;
+use crate::query::update_table::UpdateTableQuery;
+
+/// Attribute holding the item version for `put_versioned` and `update_versioned`.
+pub const VERSION_ATTRIBUTE: &str = "version";
+
+/// The table and item operations of `Client`, so callers can swap in `InMemoryDynamoDb` in tests.
+#[async_trait]
+pub trait DynamoDbApi: Send + Sync {
+    async fn create_table(
+        &self,
+        table_name: &str,
+        query: &CreateTableQuery,
+    ) -> Result<CreateTableOutput, DynamoDbError>;
+
+    async fn delete_table(&self, query: &DeleteTableQuery) -> Result<DeleteTableOutput, DynamoDbError>;
+
+    async fn update_table(&self, query: &UpdateTableQuery) -> Result<UpdateTableOutput, DynamoDbError>;
+
+    async fn describe_table(&self, query: &DescribeTableQuery) -> Result<TableDescription, DynamoDbError>;
+
+    async fn exists_table(&self, table_name: &str) -> Result<ExistsTableResultType, DynamoDbError>;
+
+    async fn wait_until_active(
+        &self,
+        table_name: &str,
+        timeout: Duration,
+    ) -> Result<TableDescription, DynamoDbError>;
+
+    async fn wait_until_deleted(&self, table_name: &str, timeout: Duration) -> Result<(), DynamoDbError>;
+
+    async fn list_tables(&self, query: &ListTablesQuery) -> Result<ListTablesOutput, DynamoDbError>;
+
+    fn list_all_tables(&self, query: ListTablesQuery) -> TableNameStream;
+
+    async fn get_item(&self, query: &GetItemQuery) -> Result<GetItemOutput, DynamoDbError>;
+
+    async fn put_item(&self, query: PutItemQuery) -> Result<PutItemOutput, DynamoDbError>;
+
+    async fn update_item(&self, query: UpdateItemQuery) -> Result<UpdateItemOutput, DynamoDbError>;
+
+    async fn delete_item(&self, query: DeleteItemQuery) -> Result<DeleteItemOutput, DynamoDbError>;
+
+    async fn transact_write(
+        &self,
+        query: TransactWriteQuery,
+    ) -> Result<TransactWriteItemsOutput, DynamoDbError>;
+
+    async fn batch_write(&self, query: BatchWriteQuery) -> Result<BatchWriteOutput, DynamoDbError>;
+
+    async fn batch_get(&self, query: BatchGetQuery) -> Result<BatchGetOutput, DynamoDbError>;
+
+    fn query(&self, query: QueryQuery) -> ItemStream;
+
+    fn scan(&self, query: ScanQuery) -> ItemStream;
+
+    fn parallel_scan(&self, query: ParallelScanQuery) -> ScanPageStream;
+
+    /// Serializes `value` with `item::to_item` and puts it unconditionally.
+    async fn put_typed<T: Serialize + Sync>(
+        &self,
+        table_name: &str,
+        value: &T,
+    ) -> Result<PutItemOutput, DynamoDbError>
+    where
+        Self: Sized,
+    {
+        let query = PutItemQuery::new(table_name, to_item(value)?, None, None::<String>);
+
+        self.put_item(query).await
+    }
+
+    /// Returns `None` when no item matches the key.
+    async fn get_typed<T: DeserializeOwned>(&self, query: &GetItemQuery) -> Result<Option<T>, DynamoDbError>
+    where
+        Self: Sized,
+    {
+        let output = self.get_item(query).await?;
+
+        Ok(output.item.map(from_item).transpose()?)
+    }
+
+    /// Puts `items` at version `expected_version + 1`, or 1 when `expected_version` is `None` and the
+    /// item must not exist yet. Returns the written version.
+    async fn put_versioned(
+        &self,
+        table_name: &str,
+        mut items: Items,
+        expected_version: Option<u64>,
+    ) -> Result<u64, DynamoDbError> {
+        let version = next_version(expected_version)?;
+        items.insert(VERSION_ATTRIBUTE.to_string(), AttributeValue::N(version.to_string()));
+
+        let query = PutItemQuery::new(table_name, items, None, None::<String>)
+            .with_condition(version_condition(expected_version));
+
+        self.put_item(query)
+            .await
+            .map_err(|error| version_conflict(error, table_name, expected_version))?;
+
+        Ok(version)
+    }
+
+    /// Applies `update` and bumps the version to `expected_version + 1`. Returns the written version.
+    /// `update` must leave `VERSION_ATTRIBUTE` alone, DynamoDB rejects overlapping paths.
+    async fn update_versioned(
+        &self,
+        table_name: &str,
+        keys: Keys,
+        update: UpdateExpression,
+        expected_version: u64,
+    ) -> Result<u64, DynamoDbError> {
+        if update.touches(VERSION_ATTRIBUTE) {
+            return Err(DynamoDbError::ValidationError(format!(
+                "The update must not touch {}, update_versioned sets it.",
+                VERSION_ATTRIBUTE
+            )));
+        }
+
+        let version = next_version(Some(expected_version))?;
+        let update = update.set(VERSION_ATTRIBUTE, AttributeValue::N(version.to_string()));
+
+        let query = UpdateItemQuery::new(table_name, keys, update)
+            .with_condition(version_condition(Some(expected_version)));
+
+        self.update_item(query)
+            .await
+            .map_err(|error| version_conflict(error, table_name, Some(expected_version)))?;
+
+        Ok(version)
+    }
+}
+
+fn next_version(expected_version: Option<u64>) -> Result<u64, DynamoDbError> {
+    match expected_version {
+        Some(expected_version) => expected_version.checked_add(1).ok_or_else(|| {
+            DynamoDbError::ValidationError(format!("Version {} cannot be incremented.", expected_version))
+        }),
+        None => Ok(1),
+    }
+}
+
+fn version_condition(expected_version: Option<u64>) -> Condition {
+    match expected_version {
+        Some(expected_version) => {
+            Condition::eq(VERSION_ATTRIBUTE, AttributeValue::N(expected_version.to_string()))
+        }
+        None => Condition::attribute_not_exists(VERSION_ATTRIBUTE),
+    }
+}
+
+fn version_conflict(error: DynamoDbError, table_name: &str, expected_version: Option<u64>) -> DynamoDbError {
+    match error {
+        DynamoDbError::ConditionalCheckFailed(_) => DynamoDbError::VersionConflict {
+            table_name: table_name.to_string(),
+            expected_version,
+        },
+        error => error,
+    }
+}
+
+#[async_trait]
+impl DynamoDbApi for Client {
+    async fn create_table(
+        &self,
+        table_name: &str,
+        query: &CreateTableQuery,
+    ) -> Result<CreateTableOutput, DynamoDbError> {
+        Client::create_table(self, table_name, query).await
+    }
+
+    async fn delete_table(&self, query: &DeleteTableQuery) -> Result<DeleteTableOutput, DynamoDbError> {
+        Client::delete_table(self, query).await
+    }
+
+    async fn update_table(&self, query: &UpdateTableQuery) -> Result<UpdateTableOutput, DynamoDbError> {
+        Client::update_table(self, query).await
+    }
+
+    async fn describe_table(&self, query: &DescribeTableQuery) -> Result<TableDescription, DynamoDbError> {
+        Client::describe_table(self, query).await
+    }
+
+    async fn exists_table(&self, table_name: &str) -> Result<ExistsTableResultType, DynamoDbError> {
+        Client::exists_table(self, table_name).await
+    }
+
+    async fn wait_until_active(
+        &self,
+        table_name: &str,
+        timeout: Duration,
+    ) -> Result<TableDescription, DynamoDbError> {
+        Client::wait_until_active(self, table_name, timeout).await
+    }
+
+    async fn wait_until_deleted(&self, table_name: &str, timeout: Duration) -> Result<(), DynamoDbError> {
+        Client::wait_until_deleted(self, table_name, timeout).await
+    }
+
+    async fn list_tables(&self, query: &ListTablesQuery) -> Result<ListTablesOutput, DynamoDbError> {
+        Client::list_tables(self, query).await
+    }
+
+    fn list_all_tables(&self, query: ListTablesQuery) -> TableNameStream {
+        Client::list_all_tables(self, query)
+    }
+
+    async fn get_item(&self, query: &GetItemQuery) -> Result<GetItemOutput, DynamoDbError> {
+        Client::get_item(self, query).await
+    }
+
+    async fn put_item(&self, query: PutItemQuery) -> Result<PutItemOutput, DynamoDbError> {
+        Client::put_item(self, query).await
+    }
+
+    async fn update_item(&self, query: UpdateItemQuery) -> Result<UpdateItemOutput, DynamoDbError> {
+        Client::update_item(self, query).await
+    }
+
+    async fn delete_item(&self, query: DeleteItemQuery) -> Result<DeleteItemOutput, DynamoDbError> {
+        Client::delete_item(self, query).await
+    }
+
+    async fn transact_write(
+        &self,
+        query: TransactWriteQuery,
+    ) -> Result<TransactWriteItemsOutput, DynamoDbError> {
+        Client::transact_write(self, query).await
+    }
+
+    async fn batch_write(&self, query: BatchWriteQuery) -> Result<BatchWriteOutput, DynamoDbError> {
+        Client::batch_write(self, query).await
+    }
+
+    async fn batch_get(&self, query: BatchGetQuery) -> Result<BatchGetOutput, DynamoDbError> {
+        Client::batch_get(self, query).await
+    }
+
+    fn query(&self, query: QueryQuery) -> ItemStream {
+        Client::query(self, query)
+    }
+
+    fn scan(&self, query: ScanQuery) -> ItemStream {
+        Client::scan(self, query)
+    }
+
+    fn parallel_scan(&self, query: ParallelScanQuery) -> ScanPageStream {
+        Client::parallel_scan(self, query)
+    }
+}
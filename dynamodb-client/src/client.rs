@@ -1,22 +1,57 @@
-use anyhow::{anyhow, Context};
-use aws_sdk_dynamodb::error::DescribeTableError;
-use aws_sdk_dynamodb::error::DescribeTableErrorKind::ResourceNotFoundException;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_stream::try_stream;
+use aws_sdk_dynamodb::error::TransactWriteItemsError;
+use aws_sdk_dynamodb::error::TransactWriteItemsErrorKind::TransactionCanceledException;
 use aws_sdk_dynamodb::model::{
-    AttributeDefinition, KeySchemaElement, ProvisionedThroughput, StreamSpecification,
+    AttributeDefinition, BatchStatementRequest, ConditionCheck as AwsConditionCheck, CreateGlobalSecondaryIndexAction,
+    Delete, DeleteGlobalSecondaryIndexAction, GlobalSecondaryIndex,
+    GlobalSecondaryIndexUpdate as AwsGlobalSecondaryIndexUpdate, KeySchemaElement,
+    KeysAndAttributes, LocalSecondaryIndex, Projection, ProvisionedThroughput, Put,
+    ReturnConsumedCapacity, ReturnValuesOnConditionCheckFailure, SseSpecification, StreamSpecification, Tag, TimeToLiveSpecification,
+    TransactWriteItem as AwsTransactWriteItem, Update, UpdateGlobalSecondaryIndexAction,
+    WriteRequest,
 };
 use aws_sdk_dynamodb::output::{
-    CreateTableOutput, DeleteTableOutput, GetItemOutput, ListTablesOutput,
-    PutItemOutput,
+    CreateTableOutput, DeleteItemOutput, DeleteTableOutput, ExecuteStatementOutput, GetItemOutput,
+    ListTablesOutput, PutItemOutput, ScanOutput, TransactWriteItemsOutput, UpdateItemOutput, UpdateTableOutput,
+    UpdateTimeToLiveOutput,
 };
 use aws_sdk_dynamodb::types::SdkError::ServiceError;
-use aws_sdk_dynamodb::{Credentials, Endpoint, Region};
+use futures_util::stream;
+use futures_util::stream::BoxStream;
 use http::Uri;
 
+use crate::config::ClientConfig;
+use crate::error::DynamoDbError;
+use crate::query::batch_execute_statement::{
+    BatchExecuteStatementOutput, BatchExecuteStatementQuery, BatchStatementError,
+    BATCH_EXECUTE_STATEMENT_CHUNK_SIZE,
+};
+use crate::query::batch_get::{BatchGetOutput, BatchGetQuery, BATCH_GET_CHUNK_SIZE};
+use crate::query::batch_write::{BatchWriteOutput, BatchWriteQuery, BATCH_WRITE_CHUNK_SIZE};
 use crate::query::create_table::CreateTableQuery;
+use crate::query::delete_item::DeleteItemQuery;
 use crate::query::delete_table::DeleteTableQuery;
+use crate::query::describe_table::{DescribeTableQuery, TableDescription};
+use crate::query::dynamodb_query;
+use crate::query::dynamodb_query::{KeySchema, Keys};
+use crate::query::execute_statement::ExecuteStatementQuery;
 use crate::query::get_item::GetItemQuery;
 use crate::query::list_tables::ListTablesQuery;
-use crate::query::put_item::PutItemQuery;
+use crate::query::parallel_scan::{ParallelScanQuery, ScanPage, SegmentCheckpoint};
+use crate::query::put_item::{Items, PutItemQuery};
+use crate::query::query::QueryQuery;
+use crate::query::scan::ScanQuery;
+use crate::query::transact_write::{CancellationReason, TransactWriteItem, TransactWriteQuery};
+use crate::query::update_item::UpdateItemQuery;
+use crate::query::update_table::{GlobalSecondaryIndexUpdate, UpdateTableQuery};
+use crate::query::update_time_to_live::UpdateTimeToLiveQuery;
+use crate::metrics::{ConsumedCapacityUnits, MetricsHook, Operation};
+use crate::rate_limiter::CapacityRateLimiter;
+use crate::retry::{with_retry, RetryPolicy};
 
 #[derive(Debug, PartialEq)]
 pub enum ExistsTableResultType {
@@ -24,23 +59,52 @@ pub enum ExistsTableResultType {
     NotFound,
 }
 
+const WAIT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+
+pub type ItemStream = BoxStream<'static, Result<Items, DynamoDbError>>;
+
+pub type ScanPageStream = BoxStream<'static, Result<ScanPage, DynamoDbError>>;
+
+pub type TableNameStream = BoxStream<'static, Result<String, DynamoDbError>>;
+
 #[derive(Debug, Clone)]
 pub struct Client {
     client: aws_sdk_dynamodb::Client,
+    retry_policy: Arc<dyn RetryPolicy>,
+    metrics: Arc<dyn MetricsHook>,
 }
 
 impl Client {
+    /// Shortcut for DynamoDB Local / LocalStack listening on `uri`.
     pub fn new(uri: Uri) -> Client {
+        Client::from_config(&ClientConfig::local(uri))
+    }
+
+    pub fn from_config(config: &ClientConfig) -> Client {
         Self {
-            client: Client::factory(uri),
+            client: aws_sdk_dynamodb::Client::from_conf(config.to_sdk_config()),
+            retry_policy: config.retry_policy(),
+            metrics: config.metrics_hook(),
         }
     }
 
+    fn operation(&self, name: &'static str, table_name: impl Into<String>) -> Operation<'_> {
+        Operation::new(name, table_name, self.retry_policy.as_ref(), self.metrics.as_ref())
+    }
+
+    /// Unprocessed requests are DynamoDB shedding load, so they are retried like throttling.
+    fn unprocessed_delay(&self, attempt: u32, unprocessed_count: usize) -> Option<Duration> {
+        let error = DynamoDbError::Throttled(format!("{} requests were left unprocessed", unprocessed_count));
+
+        self.retry_policy.next_delay(attempt, &error)
+    }
+
     pub async fn create_table(
         &self,
         table_name: &str,
         query: &CreateTableQuery,
-    ) -> anyhow::Result<CreateTableOutput> {
+    ) -> Result<CreateTableOutput, DynamoDbError> {
         let vec_attribute_definitions = query
             .attribute_definitions()
             .to_vec()
@@ -48,144 +112,966 @@ impl Client {
             .map(|attribute_definition| {
                 (AttributeDefinition::builder()
                     .attribute_name(attribute_definition.attribute_name()))
-                .attribute_type(attribute_definition.attribute_type())
+                .attribute_type(attribute_definition.attribute_type().into())
                 .build()
             })
             .collect::<Vec<_>>();
 
-        let vec_key_schemas = query
-            .key_schemas()
-            .to_vec()
+        let global_secondary_indexes = query
+            .global_secondary_indexes()
             .iter()
-            .map(|key_schema| {
-                (KeySchemaElement::builder().attribute_name(key_schema.attribute_name()))
-                    .key_type(key_schema.key_type())
+            .map(global_secondary_index)
+            .collect::<Vec<_>>();
+
+        let local_secondary_indexes = query
+            .local_secondary_indexes()
+            .iter()
+            .map(|index| {
+                LocalSecondaryIndex::builder()
+                    .index_name(index.index_name())
+                    .set_key_schema(Some(key_schema_elements(index.key_schemas())))
+                    .projection(projection(index.projection()))
                     .build()
             })
             .collect::<Vec<_>>();
 
-        let input_provisioned_throughput = query.provisioned_throughput();
-
-        let provisioned_throughput = ProvisionedThroughput::builder()
-            .read_capacity_units(*input_provisioned_throughput.read_capacity_units())
-            .write_capacity_units(*input_provisioned_throughput.write_capacity_units())
-            .build();
-
-        let stream_specification = StreamSpecification::builder()
-            .stream_enabled(query.stream_specification().stream_enabled())
-            .set_stream_view_type(query.stream_specification().stream_view_type())
-            .build();
+        let tags = query
+            .tags()
+            .iter()
+            .map(|tag| Tag::builder().key(tag.key()).value(tag.value()).build())
+            .collect::<Vec<_>>();
 
-        let create_table_response = self
+        let request = self
             .client
             .create_table()
             .table_name(table_name)
             .set_attribute_definitions(Some(vec_attribute_definitions))
-            .set_key_schema(Some(vec_key_schemas))
-            .provisioned_throughput(provisioned_throughput)
-            .stream_specification(stream_specification)
-            .send()
-            .await;
+            .set_key_schema(Some(key_schema_elements(query.key_schemas())))
+            .set_provisioned_throughput(query.provisioned_throughput().as_ref().map(provisioned_throughput))
+            .stream_specification(stream_specification(query.stream_specification()))
+            .set_global_secondary_indexes(non_empty_vec(global_secondary_indexes))
+            .set_local_secondary_indexes(non_empty_vec(local_secondary_indexes))
+            .set_billing_mode(query.billing_mode().map(Into::into))
+            .set_sse_specification(query.sse_specification().as_ref().map(sse_specification))
+            .set_tags(non_empty_vec(tags));
+        let request = &request;
 
-        Ok(create_table_response?)
+        with_retry(&self.operation("create_table", table_name), move || async move {
+            request.clone().send().await.map_err(DynamoDbError::from)
+        })
+        .await
     }
 
-    pub async fn delete_table(&self, query: &DeleteTableQuery) -> anyhow::Result<DeleteTableOutput> {
-        let delete_table_response = self
-            .client
-            .delete_table()
-            .table_name(query.table_name())
-            .send()
-            .await;
+    pub async fn delete_table(&self, query: &DeleteTableQuery) -> Result<DeleteTableOutput, DynamoDbError> {
+        let client = &self.client;
 
-        delete_table_response.context(format!(
-            "Failed delete_table. Table name: {:?}",
-            query.table_name()
-        ))
+        with_retry(&self.operation("delete_table", query.table_name()), move || async move {
+            client
+                .delete_table()
+                .table_name(query.table_name())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
     }
 
-    pub async fn get_item(&self, query: &GetItemQuery) -> anyhow::Result<GetItemOutput> {
-        let query_response = self
-            .client
-            .get_item()
-            .table_name(query.table_name())
-            .key(query.key().name(), query.key().value().clone())
-            .consistent_read(*query.consistent_read())
-            .send()
-            .await;
+    pub async fn describe_table(&self, query: &DescribeTableQuery) -> Result<TableDescription, DynamoDbError> {
+        let client = &self.client;
+
+        let output = with_retry(&self.operation("describe_table", query.table_name()), move || async move {
+            client
+                .describe_table()
+                .table_name(query.table_name())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await?;
+
+        let description = output.table.ok_or_else(|| {
+            DynamoDbError::UnexpectedResponse("DescribeTable returned no table.".to_string())
+        })?;
 
-        query_response.context(format!(
-            "Failed get_item. Table name: {}",
-            query.table_name()
-        ))
+        TableDescription::try_from(&description).map_err(DynamoDbError::UnexpectedResponse)
     }
 
-    pub async fn put_item(&self, query: PutItemQuery) -> anyhow::Result<PutItemOutput> {
-        let condition_expression = query.condition_expression().as_ref();
+    /// Changes throughput, billing mode, global secondary indexes, streams or encryption.
+    /// The table is `UPDATING` afterwards; use `wait_until_active` before relying on the change.
+    pub async fn update_table(&self, query: &UpdateTableQuery) -> Result<UpdateTableOutput, DynamoDbError> {
+        let attribute_definitions = query
+            .attribute_definitions()
+            .iter()
+            .map(|attribute_definition| {
+                AttributeDefinition::builder()
+                    .attribute_name(attribute_definition.attribute_name())
+                    .attribute_type(attribute_definition.attribute_type().into())
+                    .build()
+            })
+            .collect::<Vec<_>>();
+
+        let global_secondary_index_updates = query
+            .global_secondary_index_updates()
+            .iter()
+            .map(global_secondary_index_update)
+            .collect::<Vec<_>>();
 
-        let mut put_item = self
+        let request = self
             .client
-            .put_item()
+            .update_table()
             .table_name(query.table_name())
-            .set_item(Some(query.items()))
-            .return_values(query.return_values());
+            .set_attribute_definitions(non_empty_vec(attribute_definitions))
+            .set_billing_mode(query.billing_mode().map(Into::into))
+            .set_provisioned_throughput(query.provisioned_throughput().as_ref().map(provisioned_throughput))
+            .set_global_secondary_index_updates(non_empty_vec(global_secondary_index_updates))
+            .set_stream_specification(query.stream_specification().as_ref().map(stream_specification))
+            .set_sse_specification(query.sse_specification().as_ref().map(sse_specification));
+        let request = &request;
 
-        if let Some(expression) = condition_expression {
-            put_item = put_item.condition_expression(expression.to_string());
+        with_retry(&self.operation("update_table", query.table_name()), move || async move {
+            request.clone().send().await.map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    /// Polls DescribeTable until the table and all of its global secondary indexes are `ACTIVE`.
+    pub async fn wait_until_active(
+        &self,
+        table_name: &str,
+        timeout: Duration,
+    ) -> Result<TableDescription, DynamoDbError> {
+        let query = DescribeTableQuery::new(table_name);
+
+        let wait = async {
+            loop {
+                match self.describe_table(&query).await {
+                    Ok(description) if description.is_active() => return Ok(description),
+                    // A table that was just created may not be visible yet.
+                    Ok(_) | Err(DynamoDbError::ResourceNotFound(_)) => {}
+                    Err(error) => return Err(error),
+                }
+
+                tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            DynamoDbError::WaitTimeout(format!(
+                "Table {} did not become ACTIVE within {:?}.",
+                table_name, timeout
+            ))
+        })?
+    }
+
+    /// Polls DescribeTable until the table no longer exists.
+    pub async fn wait_until_deleted(&self, table_name: &str, timeout: Duration) -> Result<(), DynamoDbError> {
+        let query = DescribeTableQuery::new(table_name);
+
+        let wait = async {
+            loop {
+                match self.describe_table(&query).await {
+                    Ok(_) => {}
+                    Err(DynamoDbError::ResourceNotFound(_)) => return Ok(()),
+                    Err(error) => return Err(error),
+                }
+
+                tokio::time::sleep(WAIT_POLL_INTERVAL).await;
+            }
+        };
+
+        tokio::time::timeout(timeout, wait).await.map_err(|_| {
+            DynamoDbError::WaitTimeout(format!(
+                "Table {} was not deleted within {:?}.",
+                table_name, timeout
+            ))
+        })?
+    }
+
+    pub async fn update_time_to_live(
+        &self,
+        query: &UpdateTimeToLiveQuery,
+    ) -> Result<UpdateTimeToLiveOutput, DynamoDbError> {
+        let client = &self.client;
+
+        with_retry(&self.operation("update_time_to_live", query.table_name()), move || async move {
+            let specification = query.time_to_live_specification();
+
+            client
+                .update_time_to_live()
+                .table_name(query.table_name())
+                .time_to_live_specification(
+                    TimeToLiveSpecification::builder()
+                        .attribute_name(specification.attribute_name())
+                        .enabled(specification.enabled())
+                        .build(),
+                )
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    pub async fn get_item(&self, query: &GetItemQuery) -> Result<GetItemOutput, DynamoDbError> {
+        let client = &self.client;
+
+        with_retry(&self.operation("get_item", query.table_name()), move || async move {
+            client
+                .get_item()
+                .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                .table_name(query.table_name())
+                .key(query.key().name(), query.key().value().clone())
+                .consistent_read(*query.consistent_read())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    pub async fn put_item(&self, query: PutItemQuery) -> Result<PutItemOutput, DynamoDbError> {
+        let client = &self.client;
+        let query = &query;
+
+        with_retry(&self.operation("put_item", query.table_name()), move || async move {
+            client
+                .put_item()
+                .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                .table_name(query.table_name())
+                .set_item(Some(query.items()))
+                .set_condition_expression(query.condition_expression().clone())
+                .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                .return_values(query.return_values())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    pub async fn update_item(&self, query: UpdateItemQuery) -> Result<UpdateItemOutput, DynamoDbError> {
+        let client = &self.client;
+        let query = &query;
+
+        with_retry(&self.operation("update_item", query.table_name()), move || async move {
+            client
+                .update_item()
+                .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                .table_name(query.table_name())
+                .set_key(Some(query.keys().clone()))
+                .update_expression(query.update_expression())
+                .set_condition_expression(query.condition_expression().clone())
+                .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                .return_values(query.return_values())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    pub async fn delete_item(&self, query: DeleteItemQuery) -> Result<DeleteItemOutput, DynamoDbError> {
+        let client = &self.client;
+        let query = &query;
+
+        with_retry(&self.operation("delete_item", query.table_name()), move || async move {
+            client
+                .delete_item()
+                .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                .table_name(query.table_name())
+                .set_key(Some(query.keys().clone()))
+                .set_condition_expression(query.condition_expression().clone())
+                .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                .return_values(query.return_values())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    /// All items commit or none do. A canceled transaction fails with
+    /// `DynamoDbError::TransactionCanceled`, listing a reason per item.
+    pub async fn transact_write(
+        &self,
+        query: TransactWriteQuery,
+    ) -> Result<TransactWriteItemsOutput, DynamoDbError> {
+        let transact_items = query
+            .items()
+            .iter()
+            .map(|item| transact_write_item(item, query.return_old_items_on_condition_failure()))
+            .collect::<Vec<_>>();
+
+        let client = &self.client;
+        let transact_items = &transact_items;
+        let query = &query;
+
+        let operation = self.operation(
+            "transact_write",
+            table_names(query.items().iter().map(TransactWriteItem::table_name)),
+        );
+
+        with_retry(&operation, move || async move {
+            let transact_write_response = client
+                .transact_write_items()
+                .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                .set_transact_items(Some(transact_items.clone()))
+                .set_client_request_token(query.client_request_token().clone())
+                .send()
+                .await;
+
+            match transact_write_response {
+                Ok(output) => Ok(output),
+                Err(ServiceError {
+                    err:
+                        TransactWriteItemsError {
+                            kind: TransactionCanceledException(exception),
+                            ..
+                        },
+                    raw: _,
+                }) => {
+                    let reasons = exception
+                        .cancellation_reasons()
+                        .unwrap_or_default()
+                        .iter()
+                        .enumerate()
+                        .map(|(index, reason)| CancellationReason::from_aws(index, reason))
+                        .collect::<Vec<_>>();
+
+                    Err(DynamoDbError::TransactionCanceled(reasons))
+                }
+                Err(error) => Err(error.into()),
+            }
+        })
+        .await
+    }
+
+    /// Writes in chunks of 25 and resubmits UnprocessedItems for as long as the retry policy allows.
+    /// Requests that are still unprocessed after the last attempt are returned, not dropped.
+    pub async fn batch_write(&self, query: BatchWriteQuery) -> Result<BatchWriteOutput, DynamoDbError> {
+        let mut processed_count = 0;
+        let mut unprocessed = vec![];
+
+        for chunk in query.requests().chunks(BATCH_WRITE_CHUNK_SIZE) {
+            let mut pending = chunk.to_vec();
+            let mut attempt = 1;
+
+            loop {
+                let sent_count = pending.len();
+
+                let client = &self.client;
+                let request_items = &group_by_table(pending);
+
+                let operation = self.operation("batch_write", table_names(request_items.keys().map(String::as_str)));
+
+                let output = with_retry(&operation, move || async move {
+                    client
+                        .batch_write_item()
+                        .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                        .set_request_items(Some(request_items.clone()))
+                        .send()
+                        .await
+                        .map_err(DynamoDbError::from)
+                })
+                .await?;
+
+                pending = flatten_by_table(output.unprocessed_items.unwrap_or_default());
+                processed_count += sent_count - pending.len();
+
+                if pending.is_empty() {
+                    break;
+                }
+
+                match self.unprocessed_delay(attempt, pending.len()) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => {
+                        unprocessed.append(&mut pending);
+                        break;
+                    }
+                }
+                attempt += 1;
+            }
         }
 
-        let put_item_response = put_item
-            .send()
-            .await;
+        Ok(BatchWriteOutput::new(processed_count, unprocessed))
+    }
+
+    /// Reads in chunks of 100 and resubmits UnprocessedKeys for as long as the retry policy allows.
+    pub async fn batch_get(&self, query: BatchGetQuery) -> Result<BatchGetOutput, DynamoDbError> {
+        let mut items: HashMap<String, Vec<Items>> = HashMap::new();
+        let mut unprocessed_keys = vec![];
 
-        Ok(put_item_response?)
+        for chunk in query.keys().chunks(BATCH_GET_CHUNK_SIZE) {
+            let mut pending = chunk.to_vec();
+            let mut attempt = 1;
+
+            loop {
+                let client = &self.client;
+                let request_items = &keys_and_attributes_by_table(pending, *query.consistent_read());
+
+                let operation = self.operation("batch_get", table_names(request_items.keys().map(String::as_str)));
+
+                let output = with_retry(&operation, move || async move {
+                    client
+                        .batch_get_item()
+                        .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                        .set_request_items(Some(request_items.clone()))
+                        .send()
+                        .await
+                        .map_err(DynamoDbError::from)
+                })
+                .await?;
+
+                for (table_name, responses) in output.responses.unwrap_or_default() {
+                    items.entry(table_name).or_default().extend(responses);
+                }
+
+                pending = output
+                    .unprocessed_keys
+                    .unwrap_or_default()
+                    .into_iter()
+                    .flat_map(|(table_name, keys_and_attributes)| {
+                        keys_and_attributes
+                            .keys
+                            .unwrap_or_default()
+                            .into_iter()
+                            .map(move |keys| (table_name.clone(), keys))
+                    })
+                    .collect::<Vec<_>>();
+
+                if pending.is_empty() {
+                    break;
+                }
+
+                match self.unprocessed_delay(attempt, pending.len()) {
+                    Some(delay) => tokio::time::sleep(delay).await,
+                    None => {
+                        unprocessed_keys.append(&mut pending);
+                        break;
+                    }
+                }
+                attempt += 1;
+            }
+        }
+
+        Ok(BatchGetOutput::new(items, unprocessed_keys))
     }
 
-    pub async fn list_tables(&self, _query: &ListTablesQuery) -> anyhow::Result<ListTablesOutput> {
-        self.client
-            .list_tables()
-            .send()
-            .await
-            .map_err(|error| {
-                anyhow!(format!("Failed list tables. Error: {}", error))
+    /// Follows LastEvaluatedKey until every page of the query has been read.
+    pub fn query(&self, query: QueryQuery) -> ItemStream {
+        let client = self.client.clone();
+        let retry_policy = self.retry_policy.clone();
+        let metrics = self.metrics.clone();
+
+        Box::pin(try_stream! {
+            let mut exclusive_start_key = None;
+
+            loop {
+                let client = &client;
+                let query = &query;
+                let start_key = &exclusive_start_key;
+
+                let operation = Operation::new("query", query.table_name(), retry_policy.as_ref(), metrics.as_ref());
+
+                let output = with_retry(&operation, move || async move {
+                    client
+                        .query()
+                        .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                        .table_name(query.table_name())
+                        .key_condition_expression(query.key_condition_expression())
+                        .set_filter_expression(query.filter_expression().clone())
+                        .set_index_name(query.index_name().clone())
+                        .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                        .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                        .set_limit(*query.limit())
+                        .consistent_read(*query.consistent_read())
+                        .scan_index_forward(*query.scan_index_forward())
+                        .set_exclusive_start_key(start_key.clone())
+                        .send()
+                        .await
+                        .map_err(DynamoDbError::from)
+                })
+                .await?;
+
+                for item in output.items.unwrap_or_default() {
+                    yield item;
+                }
+
+                match output.last_evaluated_key {
+                    Some(key) if !key.is_empty() => exclusive_start_key = Some(key),
+                    _ => break,
+                }
+            }
+        })
+    }
+
+    /// Follows LastEvaluatedKey until the whole table (or index) has been read.
+    pub fn scan(&self, query: ScanQuery) -> ItemStream {
+        let client = self.clone();
+
+        Box::pin(try_stream! {
+            let mut exclusive_start_key = None;
+
+            loop {
+                let output = client.scan_page(&query, &exclusive_start_key).await?;
+
+                for item in output.items.unwrap_or_default() {
+                    yield item;
+                }
+
+                match output.last_evaluated_key {
+                    Some(key) if !key.is_empty() => exclusive_start_key = Some(key),
+                    _ => break,
+                }
+            }
+        })
+    }
+
+    /// Scans the segments of `query` concurrently and merges their pages into one stream.
+    /// Pages of a segment are in order, pages of different segments interleave.
+    pub fn parallel_scan(&self, query: ParallelScanQuery) -> ScanPageStream {
+        let rate_limiter = query
+            .read_capacity_per_second()
+            .map(|read_capacity_per_second| Arc::new(CapacityRateLimiter::new(read_capacity_per_second)));
+
+        let segments = query
+            .segments()
+            .into_iter()
+            .map(|(scan, exclusive_start_key)| self.scan_segment(scan, exclusive_start_key, rate_limiter.clone()))
+            .collect::<Vec<_>>();
+
+        Box::pin(stream::select_all(segments))
+    }
+
+    fn scan_segment(
+        &self,
+        query: ScanQuery,
+        exclusive_start_key: Option<Keys>,
+        rate_limiter: Option<Arc<CapacityRateLimiter>>,
+    ) -> ScanPageStream {
+        let client = self.clone();
+
+        Box::pin(try_stream! {
+            let segment = query.segment().unwrap_or_default();
+            let mut exclusive_start_key = exclusive_start_key;
+
+            loop {
+                if let Some(rate_limiter) = &rate_limiter {
+                    rate_limiter.acquire().await;
+                }
+
+                let output = client.scan_page(&query, &exclusive_start_key).await?;
+
+                if let (Some(rate_limiter), Some(units)) = (&rate_limiter, output.consumed_capacity_units()) {
+                    rate_limiter.consume(units);
+                }
+
+                let checkpoint = match output.last_evaluated_key {
+                    Some(key) if !key.is_empty() => {
+                        exclusive_start_key = Some(key.clone());
+                        SegmentCheckpoint::InProgress(key)
+                    }
+                    _ => SegmentCheckpoint::Completed,
+                };
+                let completed = checkpoint == SegmentCheckpoint::Completed;
+
+                yield ScanPage::new(segment, output.items.unwrap_or_default(), checkpoint);
+
+                if completed {
+                    break;
+                }
+            }
+        })
+    }
+
+    async fn scan_page(
+        &self,
+        query: &ScanQuery,
+        exclusive_start_key: &Option<Keys>,
+    ) -> Result<ScanOutput, DynamoDbError> {
+        let client = &self.client;
+
+        with_retry(&self.operation("scan", query.table_name()), move || async move {
+            client
+                .scan()
+                .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                .table_name(query.table_name())
+                .set_filter_expression(query.filter_expression().clone())
+                .set_index_name(query.index_name().clone())
+                .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                .set_limit(*query.limit())
+                .consistent_read(*query.consistent_read())
+                .set_segment(*query.segment())
+                .set_total_segments(*query.total_segments())
+                .set_exclusive_start_key(exclusive_start_key.clone())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    /// Runs a PartiQL statement and returns a single page. A SELECT may continue in a next page,
+    /// see `select_all` to read every item.
+    pub async fn execute_statement(
+        &self,
+        query: &ExecuteStatementQuery,
+    ) -> Result<ExecuteStatementOutput, DynamoDbError> {
+        let client = &self.client;
+
+        with_retry(&self.operation("execute_statement", ""), move || async move {
+            client
+                .execute_statement()
+                .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                .statement(query.statement())
+                .set_parameters(non_empty_vec(query.parameters().clone()))
+                .consistent_read(*query.consistent_read())
+                .set_limit(*query.limit())
+                .set_next_token(query.next_token().clone())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    /// Follows NextToken until every item of a PartiQL SELECT has been read.
+    pub fn select_all(&self, query: ExecuteStatementQuery) -> ItemStream {
+        let client = self.clone();
+
+        Box::pin(try_stream! {
+            let mut query = query;
+
+            loop {
+                let output = client.execute_statement(&query).await?;
+
+                for item in output.items.unwrap_or_default() {
+                    yield item;
+                }
+
+                match output.next_token {
+                    Some(next_token) => query = query.with_next_token(next_token),
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// Runs the statements in chunks of 25. Statements that fail, including throttled ones,
+    /// are reported per statement in the output and are not retried.
+    pub async fn batch_execute_statement(
+        &self,
+        query: BatchExecuteStatementQuery,
+    ) -> Result<BatchExecuteStatementOutput, DynamoDbError> {
+        let mut responses = vec![];
+
+        for chunk in query.statements().chunks(BATCH_EXECUTE_STATEMENT_CHUNK_SIZE) {
+            let client = &self.client;
+            let statements = &chunk
+                .iter()
+                .map(|statement| {
+                    BatchStatementRequest::builder()
+                        .statement(statement.statement())
+                        .set_parameters(non_empty_vec(statement.parameters().clone()))
+                        .consistent_read(*statement.consistent_read())
+                        .build()
+                })
+                .collect::<Vec<_>>();
+
+            let output = with_retry(&self.operation("batch_execute_statement", ""), move || async move {
+                client
+                    .batch_execute_statement()
+                    .return_consumed_capacity(ReturnConsumedCapacity::Total)
+                    .set_statements(Some(statements.clone()))
+                    .send()
+                    .await
+                    .map_err(DynamoDbError::from)
             })
+            .await?;
+
+            for response in output.responses.unwrap_or_default() {
+                responses.push(match response.error() {
+                    Some(error) => Err(BatchStatementError::from_aws(error)),
+                    None => Ok(response.item),
+                });
+            }
+        }
+
+        Ok(BatchExecuteStatementOutput::new(responses))
     }
 
-    pub async fn exists_table(&self, table_name: &str) -> anyhow::Result<ExistsTableResultType> {
-        let describe_table_response = self
-            .client
-            .describe_table()
-            .table_name(table_name)
-            .send()
-            .await;
+    /// A single page. See `list_all_tables` to read every table name.
+    pub async fn list_tables(&self, query: &ListTablesQuery) -> Result<ListTablesOutput, DynamoDbError> {
+        let client = &self.client;
+
+        with_retry(&self.operation("list_tables", ""), move || async move {
+            client
+                .list_tables()
+                .set_limit(*query.limit())
+                .set_exclusive_start_table_name(query.exclusive_start_table_name().clone())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    /// Follows LastEvaluatedTableName until every table name has been read.
+    pub fn list_all_tables(&self, query: ListTablesQuery) -> TableNameStream {
+        let client = self.clone();
+
+        Box::pin(try_stream! {
+            let mut query = query;
+
+            loop {
+                let output = client.list_tables(&query).await?;
+
+                for table_name in output.table_names.unwrap_or_default() {
+                    yield table_name;
+                }
+
+                match output.last_evaluated_table_name {
+                    Some(table_name) => query = query.with_exclusive_start_table_name(table_name),
+                    None => break,
+                }
+            }
+        })
+    }
+
+    pub async fn exists_table(&self, table_name: &str) -> Result<ExistsTableResultType, DynamoDbError> {
+        let client = &self.client;
+
+        let describe_table_response = with_retry(&self.operation("describe_table", table_name), move || async move {
+            client
+                .describe_table()
+                .table_name(table_name)
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await;
 
         match describe_table_response {
             Ok(_) => Ok(ExistsTableResultType::Found),
-            Err(ServiceError {
-                err:
-                    DescribeTableError {
-                        kind: ResourceNotFoundException(_),
-                        ..
-                    },
-                raw: _,
-            }) => Ok(ExistsTableResultType::NotFound),
-            Err(error) => Err(anyhow!(error.to_string())),
+            Err(DynamoDbError::ResourceNotFound(_)) => Ok(ExistsTableResultType::NotFound),
+            Err(error) => Err(error),
         }
     }
+}
 
-    fn factory(uri: Uri) -> aws_sdk_dynamodb::Client {
-        let endpoint = Endpoint::immutable(uri);
+/// Label for operations spanning several tables.
+fn table_names<'a>(table_names: impl Iterator<Item = &'a str>) -> String {
+    let mut table_names = table_names.collect::<Vec<_>>();
+    table_names.sort_unstable();
+    table_names.dedup();
 
-        let dynamodb_local_config = aws_sdk_dynamodb::Config::builder()
-            .region(Region::new("ap-northeast-1"))
-            .endpoint_resolver(endpoint)
-            .credentials_provider(Credentials::new("test", "test", None, None, "default"))
-            .build();
+    table_names.join(",")
+}
+
+/// DynamoDB rejects empty expression attribute maps, so they are omitted instead.
+fn non_empty<V: Clone>(map: &HashMap<String, V>) -> Option<HashMap<String, V>> {
+    if map.is_empty() {
+        None
+    } else {
+        Some(map.clone())
+    }
+}
 
-        aws_sdk_dynamodb::Client::from_conf(dynamodb_local_config)
+/// DynamoDB rejects empty index and tag lists, so they are omitted instead.
+fn non_empty_vec<T>(values: Vec<T>) -> Option<Vec<T>> {
+    if values.is_empty() {
+        None
+    } else {
+        Some(values)
     }
 }
 
+fn key_schema_elements(key_schemas: &[KeySchema]) -> Vec<KeySchemaElement> {
+    key_schemas
+        .iter()
+        .map(|key_schema| {
+            KeySchemaElement::builder()
+                .attribute_name(key_schema.attribute_name())
+                .key_type(key_schema.key_type().into())
+                .build()
+        })
+        .collect()
+}
+
+fn provisioned_throughput(
+    provisioned_throughput: &dynamodb_query::ProvisionedThroughput,
+) -> ProvisionedThroughput {
+    ProvisionedThroughput::builder()
+        .read_capacity_units(*provisioned_throughput.read_capacity_units())
+        .write_capacity_units(*provisioned_throughput.write_capacity_units())
+        .build()
+}
+
+fn global_secondary_index(index: &dynamodb_query::GlobalSecondaryIndex) -> GlobalSecondaryIndex {
+    GlobalSecondaryIndex::builder()
+        .index_name(index.index_name())
+        .set_key_schema(Some(key_schema_elements(index.key_schemas())))
+        .projection(projection(index.projection()))
+        .set_provisioned_throughput(index.provisioned_throughput().as_ref().map(provisioned_throughput))
+        .build()
+}
+
+fn global_secondary_index_update(update: &GlobalSecondaryIndexUpdate) -> AwsGlobalSecondaryIndexUpdate {
+    match update {
+        GlobalSecondaryIndexUpdate::Create(index) => {
+            let index = global_secondary_index(index);
+
+            AwsGlobalSecondaryIndexUpdate::builder()
+                .create(
+                    CreateGlobalSecondaryIndexAction::builder()
+                        .set_index_name(index.index_name)
+                        .set_key_schema(index.key_schema)
+                        .set_projection(index.projection)
+                        .set_provisioned_throughput(index.provisioned_throughput)
+                        .build(),
+                )
+                .build()
+        }
+        GlobalSecondaryIndexUpdate::Update {
+            index_name,
+            provisioned_throughput: throughput,
+        } => AwsGlobalSecondaryIndexUpdate::builder()
+            .update(
+                UpdateGlobalSecondaryIndexAction::builder()
+                    .index_name(index_name)
+                    .provisioned_throughput(provisioned_throughput(throughput))
+                    .build(),
+            )
+            .build(),
+        GlobalSecondaryIndexUpdate::Delete { index_name } => AwsGlobalSecondaryIndexUpdate::builder()
+            .delete(
+                DeleteGlobalSecondaryIndexAction::builder()
+                    .index_name(index_name)
+                    .build(),
+            )
+            .build(),
+    }
+}
+
+fn stream_specification(stream_specification: &dynamodb_query::StreamSpecification) -> StreamSpecification {
+    StreamSpecification::builder()
+        .stream_enabled(stream_specification.stream_enabled())
+        .set_stream_view_type(stream_specification.stream_view_type().map(Into::into))
+        .build()
+}
+
+fn sse_specification(sse_specification: &dynamodb_query::SseSpecification) -> SseSpecification {
+    SseSpecification::builder()
+        .enabled(sse_specification.enabled())
+        .set_sse_type(sse_specification.sse_type().map(Into::into))
+        .set_kms_master_key_id(sse_specification.kms_master_key_id().clone())
+        .build()
+}
+
+fn projection(projection: &dynamodb_query::Projection) -> Projection {
+    Projection::builder()
+        .projection_type(projection.projection_type().into())
+        .set_non_key_attributes(projection.non_key_attributes().clone())
+        .build()
+}
+
+fn transact_write_item(item: &TransactWriteItem, return_old_item: bool) -> AwsTransactWriteItem {
+    let return_values = return_old_item.then_some(ReturnValuesOnConditionCheckFailure::AllOld);
+
+    match item {
+        TransactWriteItem::Put(query) => AwsTransactWriteItem::builder()
+            .put(
+                Put::builder()
+                    .table_name(query.table_name())
+                    .set_item(Some(query.items()))
+                    .set_condition_expression(query.condition_expression().clone())
+                    .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                    .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                    .set_return_values_on_condition_check_failure(return_values)
+                    .build(),
+            )
+            .build(),
+        TransactWriteItem::Update(query) => AwsTransactWriteItem::builder()
+            .update(
+                Update::builder()
+                    .table_name(query.table_name())
+                    .set_key(Some(query.keys().clone()))
+                    .update_expression(query.update_expression())
+                    .set_condition_expression(query.condition_expression().clone())
+                    .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                    .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                    .set_return_values_on_condition_check_failure(return_values)
+                    .build(),
+            )
+            .build(),
+        TransactWriteItem::Delete(query) => AwsTransactWriteItem::builder()
+            .delete(
+                Delete::builder()
+                    .table_name(query.table_name())
+                    .set_key(Some(query.keys().clone()))
+                    .set_condition_expression(query.condition_expression().clone())
+                    .set_expression_attribute_names(non_empty(query.expression_attribute_names()))
+                    .set_expression_attribute_values(non_empty(query.expression_attribute_values()))
+                    .set_return_values_on_condition_check_failure(return_values)
+                    .build(),
+            )
+            .build(),
+        TransactWriteItem::ConditionCheck(check) => AwsTransactWriteItem::builder()
+            .condition_check(
+                AwsConditionCheck::builder()
+                    .table_name(check.table_name())
+                    .set_key(Some(check.keys().clone()))
+                    .condition_expression(check.condition_expression())
+                    .set_expression_attribute_names(non_empty(check.expression_attribute_names()))
+                    .set_expression_attribute_values(non_empty(check.expression_attribute_values()))
+                    .set_return_values_on_condition_check_failure(return_values)
+                    .build(),
+            )
+            .build(),
+    }
+}
+
+fn group_by_table<T>(entries: Vec<(String, T)>) -> HashMap<String, Vec<T>> {
+    entries
+        .into_iter()
+        .fold(HashMap::new(), |mut grouped, (table_name, entry)| {
+            grouped.entry(table_name).or_insert_with(Vec::new).push(entry);
+            grouped
+        })
+}
+
+fn flatten_by_table(grouped: HashMap<String, Vec<WriteRequest>>) -> Vec<(String, WriteRequest)> {
+    grouped
+        .into_iter()
+        .flat_map(|(table_name, requests)| {
+            requests
+                .into_iter()
+                .map(move |request| (table_name.clone(), request))
+        })
+        .collect()
+}
+
+fn keys_and_attributes_by_table(
+    keys: Vec<(String, Keys)>,
+    consistent_read: bool,
+) -> HashMap<String, KeysAndAttributes> {
+    group_by_table(keys)
+        .into_iter()
+        .map(|(table_name, keys)| {
+            let keys_and_attributes = KeysAndAttributes::builder()
+                .set_keys(Some(keys))
+                .consistent_read(consistent_read)
+                .build();
+
+            (table_name, keys_and_attributes)
+        })
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use std::collections::HashMap;
@@ -195,12 +1081,27 @@ mod tests {
     use http::Uri;
     use uuid::Uuid;
     use crate::client::{Client, ExistsTableResultType};
+    use crate::config::ClientConfig;
     use crate::query::get_item::{GetItemQuery, Key};
     use crate::query::put_item::{Items, PutItemQuery};
 
     const DYNAMODB_HOST: &str = "http://localhost:4566";
 
     #[tokio::test]
+    async fn test_unprocessed_requests_follow_the_retry_policy() {
+        let config = ClientConfig::builder()
+            .endpoint(Uri::from_static(DYNAMODB_HOST))
+            .max_attempts(3)
+            .build();
+        let client = Client::from_config(&config);
+
+        assert!(client.unprocessed_delay(1, 5).is_some());
+        assert!(client.unprocessed_delay(2, 5).is_some());
+        assert_eq!(None, client.unprocessed_delay(3, 5));
+    }
+
+    #[tokio::test]
+    #[ignore = "needs DynamoDB Local on localhost:4566"]
     async fn test_put_item() -> anyhow::Result<()> {
         let mut items: Items = HashMap::new();
 
@@ -251,14 +1152,13 @@ mod tests {
             .item()
             .context(format!("Record not found when added put item. message_id: {}", uuid))?;
 
-        let message_id = records.get("message_id").context("")?;
-
-        dbg!(message_id);
+        assert_eq!(Some(&AttributeValue::S(uuid.to_string())), records.get("message_id"));
 
         Ok(())
     }
 
     #[tokio::test]
+    #[ignore = "needs DynamoDB Local on localhost:4566"]
     async fn test_exists_table() {
         let exists_table_result_type = Client::new(self::DYNAMODB_HOST.parse::<Uri>().unwrap())
             .exists_table("migrations")
@@ -269,6 +1169,7 @@ mod tests {
     }
 
     #[tokio::test]
+    #[ignore = "needs DynamoDB Local on localhost:4566"]
     async fn test_exists_table_not_found() {
         let exists_table_result_type = Client::new(self::DYNAMODB_HOST.parse::<Uri>().unwrap())
             .exists_table("test")
@@ -277,4 +1178,5 @@ mod tests {
 
         assert_eq!(ExistsTableResultType::NotFound, exists_table_result_type)
     }
+
 }
@@ -0,0 +1,320 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use aws_config::environment::credentials::EnvironmentVariableCredentialsProvider;
+use aws_config::profile::ProfileFileCredentialsProvider;
+use aws_sdk_dynamodb::config::retry::RetryConfig;
+use aws_sdk_dynamodb::config::timeout::TimeoutConfig;
+use aws_sdk_dynamodb::{Credentials, Endpoint, Region};
+use aws_smithy_async::rt::sleep::TokioSleep;
+use aws_types::credentials::SharedCredentialsProvider;
+use http::Uri;
+
+use crate::metrics::{MetricsHook, NoMetrics};
+use crate::retry::{ExponentialBackoff, RetryPolicy};
+
+const DEFAULT_REGION: &str = "ap-northeast-1";
+const LOCAL_ACCESS_KEY_ID: &str = "test";
+const LOCAL_SECRET_ACCESS_KEY: &str = "test";
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CredentialsConfig {
+    Static {
+        access_key_id: String,
+        secret_access_key: String,
+        session_token: Option<String>,
+    },
+    /// AWS_ACCESS_KEY_ID, AWS_SECRET_ACCESS_KEY and AWS_SESSION_TOKEN.
+    Environment,
+    /// A profile from ~/.aws/credentials and ~/.aws/config, `None` meaning the default profile.
+    Profile(Option<String>),
+}
+
+impl CredentialsConfig {
+    pub fn new_static(
+        access_key_id: impl Into<String>,
+        secret_access_key: impl Into<String>,
+        session_token: Option<String>,
+    ) -> Self {
+        CredentialsConfig::Static {
+            access_key_id: access_key_id.into(),
+            secret_access_key: secret_access_key.into(),
+            session_token,
+        }
+    }
+
+    fn provider(&self) -> SharedCredentialsProvider {
+        match self {
+            CredentialsConfig::Static {
+                access_key_id,
+                secret_access_key,
+                session_token,
+            } => SharedCredentialsProvider::new(Credentials::new(
+                access_key_id,
+                secret_access_key,
+                session_token.clone(),
+                None,
+                "static",
+            )),
+            CredentialsConfig::Environment => {
+                SharedCredentialsProvider::new(EnvironmentVariableCredentialsProvider::new())
+            }
+            CredentialsConfig::Profile(profile_name) => {
+                let builder = ProfileFileCredentialsProvider::builder();
+
+                let provider = match profile_name {
+                    Some(name) => builder.profile_name(name).build(),
+                    None => builder.build(),
+                };
+
+                SharedCredentialsProvider::new(provider)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ClientConfig {
+    region: String,
+    credentials: CredentialsConfig,
+    endpoint: Option<Uri>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    operation_timeout: Option<Duration>,
+    operation_attempt_timeout: Option<Duration>,
+    retry_policy: Arc<dyn RetryPolicy>,
+    metrics_hook: Arc<dyn MetricsHook>,
+}
+
+impl ClientConfig {
+    pub fn builder() -> ClientConfigBuilder {
+        ClientConfigBuilder::default()
+    }
+
+    /// DynamoDB Local / LocalStack, which accept any static credentials.
+    pub fn local(uri: Uri) -> Self {
+        ClientConfig::builder()
+            .endpoint(uri)
+            .credentials(CredentialsConfig::new_static(
+                LOCAL_ACCESS_KEY_ID,
+                LOCAL_SECRET_ACCESS_KEY,
+                None,
+            ))
+            .build()
+    }
+
+    pub fn region(&self) -> &str {
+        &self.region
+    }
+
+    pub fn credentials(&self) -> &CredentialsConfig {
+        &self.credentials
+    }
+
+    pub fn endpoint(&self) -> &Option<Uri> {
+        &self.endpoint
+    }
+
+    pub fn connect_timeout(&self) -> &Option<Duration> {
+        &self.connect_timeout
+    }
+
+    pub fn read_timeout(&self) -> &Option<Duration> {
+        &self.read_timeout
+    }
+
+    pub fn operation_timeout(&self) -> &Option<Duration> {
+        &self.operation_timeout
+    }
+
+    pub fn operation_attempt_timeout(&self) -> &Option<Duration> {
+        &self.operation_attempt_timeout
+    }
+
+    pub fn retry_policy(&self) -> Arc<dyn RetryPolicy> {
+        self.retry_policy.clone()
+    }
+
+    pub fn metrics_hook(&self) -> Arc<dyn MetricsHook> {
+        self.metrics_hook.clone()
+    }
+
+    pub(crate) fn to_sdk_config(&self) -> aws_sdk_dynamodb::Config {
+        let mut builder = aws_sdk_dynamodb::Config::builder()
+            .region(Region::new(self.region.clone()))
+            .credentials_provider(self.credentials.provider())
+            .timeout_config(self.timeout_config())
+            // Retries are handled by `retry_policy` so every attempt is visible to the client.
+            .retry_config(RetryConfig::disabled())
+            .sleep_impl(Arc::new(TokioSleep::new()));
+
+        if let Some(uri) = &self.endpoint {
+            builder = builder.endpoint_resolver(Endpoint::immutable(uri.clone()));
+        }
+
+        builder.build()
+    }
+
+    /// DynamoDB Streams shares the endpoint, credentials and timeouts of DynamoDB.
+    pub(crate) fn to_streams_sdk_config(&self) -> aws_sdk_dynamodbstreams::Config {
+        let mut builder = aws_sdk_dynamodbstreams::Config::builder()
+            .region(Region::new(self.region.clone()))
+            .credentials_provider(self.credentials.provider())
+            .timeout_config(self.timeout_config())
+            .retry_config(RetryConfig::disabled())
+            .sleep_impl(Arc::new(TokioSleep::new()));
+
+        if let Some(uri) = &self.endpoint {
+            builder = builder.endpoint_resolver(Endpoint::immutable(uri.clone()));
+        }
+
+        builder.build()
+    }
+
+    fn timeout_config(&self) -> TimeoutConfig {
+        let mut timeout_config = TimeoutConfig::builder();
+        timeout_config
+            .set_connect_timeout(self.connect_timeout)
+            .set_read_timeout(self.read_timeout)
+            .set_operation_timeout(self.operation_timeout)
+            .set_operation_attempt_timeout(self.operation_attempt_timeout);
+
+        timeout_config.build()
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct ClientConfigBuilder {
+    region: Option<String>,
+    credentials: Option<CredentialsConfig>,
+    endpoint: Option<Uri>,
+    connect_timeout: Option<Duration>,
+    read_timeout: Option<Duration>,
+    operation_timeout: Option<Duration>,
+    operation_attempt_timeout: Option<Duration>,
+    max_attempts: Option<u32>,
+    initial_backoff: Option<Duration>,
+    retry_policy: Option<Arc<dyn RetryPolicy>>,
+    metrics_hook: Option<Arc<dyn MetricsHook>>,
+}
+
+impl ClientConfigBuilder {
+    /// Defaults to ap-northeast-1.
+    pub fn region(mut self, region: impl Into<String>) -> Self {
+        self.region = Some(region.into());
+        self
+    }
+
+    /// Defaults to `CredentialsConfig::Environment`.
+    pub fn credentials(mut self, credentials: CredentialsConfig) -> Self {
+        self.credentials = Some(credentials);
+        self
+    }
+
+    /// Overrides the regional AWS endpoint, e.g. for DynamoDB Local.
+    pub fn endpoint(mut self, uri: Uri) -> Self {
+        self.endpoint = Some(uri);
+        self
+    }
+
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+        self
+    }
+
+    /// Covers one request to DynamoDB. Each retry of the retry policy gets a fresh timeout.
+    pub fn operation_timeout(mut self, timeout: Duration) -> Self {
+        self.operation_timeout = Some(timeout);
+        self
+    }
+
+    /// Covers a single attempt of an operation.
+    pub fn operation_attempt_timeout(mut self, timeout: Duration) -> Self {
+        self.operation_attempt_timeout = Some(timeout);
+        self
+    }
+
+    /// Attempts including the first one for the default `ExponentialBackoff`. `1` disables retries.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = Some(max_attempts);
+        self
+    }
+
+    /// Base delay of the default `ExponentialBackoff`.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = Some(initial_backoff);
+        self
+    }
+
+    /// Replaces the default `ExponentialBackoff`; `max_attempts` and `initial_backoff` are then ignored.
+    pub fn retry_policy(mut self, retry_policy: impl RetryPolicy + 'static) -> Self {
+        self.retry_policy = Some(Arc::new(retry_policy));
+        self
+    }
+
+    /// Defaults to `NoMetrics`. Tracing spans are emitted either way.
+    pub fn metrics_hook(mut self, metrics_hook: impl MetricsHook + 'static) -> Self {
+        self.metrics_hook = Some(Arc::new(metrics_hook));
+        self
+    }
+
+    pub fn build(self) -> ClientConfig {
+        ClientConfig {
+            region: self.region.unwrap_or_else(|| DEFAULT_REGION.to_string()),
+            credentials: self.credentials.unwrap_or(CredentialsConfig::Environment),
+            endpoint: self.endpoint,
+            connect_timeout: self.connect_timeout,
+            read_timeout: self.read_timeout,
+            operation_timeout: self.operation_timeout,
+            operation_attempt_timeout: self.operation_attempt_timeout,
+            retry_policy: self.retry_policy.unwrap_or_else(|| {
+                let mut policy = ExponentialBackoff::new();
+
+                if let Some(max_attempts) = self.max_attempts {
+                    policy = policy.with_max_attempts(max_attempts);
+                }
+
+                if let Some(initial_backoff) = self.initial_backoff {
+                    policy = policy.with_base_delay(initial_backoff);
+                }
+
+                Arc::new(policy)
+            }),
+            metrics_hook: self.metrics_hook.unwrap_or_else(|| Arc::new(NoMetrics)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::Uri;
+
+    use crate::config::{ClientConfig, CredentialsConfig};
+
+    #[test]
+    fn test_builder_defaults() {
+        let config = ClientConfig::builder().build();
+
+        assert_eq!("ap-northeast-1", config.region());
+        assert_eq!(&CredentialsConfig::Environment, config.credentials());
+        assert_eq!(&None, config.endpoint());
+    }
+
+    #[test]
+    fn test_local() {
+        let uri = "http://localhost:4566".parse::<Uri>().unwrap();
+
+        let config = ClientConfig::local(uri.clone());
+
+        assert_eq!(&Some(uri), config.endpoint());
+        assert_eq!(
+            &CredentialsConfig::new_static("test", "test", None),
+            config.credentials()
+        );
+    }
+}
@@ -0,0 +1,115 @@
+use aws_sdk_dynamodb::types::SdkError;
+use aws_smithy_types::retry::ProvideErrorKind;
+use thiserror::Error;
+
+use crate::item::ItemError;
+use crate::query::transact_write::CancellationReason;
+
+#[derive(Error, Debug)]
+pub enum DynamoDbError {
+    #[error("The conditional request failed. Error: {0}")]
+    ConditionalCheckFailed(String),
+    #[error("Item in {table_name} is not at version {expected_version:?}.")]
+    VersionConflict {
+        table_name: String,
+        expected_version: Option<u64>,
+    },
+    #[error("An item with the same primary key already exists. Error: {0}")]
+    DuplicateItem(String),
+    #[error("Requested resource was not found. Error: {0}")]
+    ResourceNotFound(String),
+    #[error("Requested resource is in use. Error: {0}")]
+    ResourceInUse(String),
+    #[error("Request was throttled. Error: {0}")]
+    Throttled(String),
+    #[error("Request was rejected as invalid. Error: {0}")]
+    ValidationError(String),
+    #[error("Shard iterator expired. Error: {0}")]
+    ExpiredIterator(String),
+    #[error("Stream records were trimmed before they could be read. Error: {0}")]
+    TrimmedDataAccess(String),
+    #[error("Transaction canceled. Reasons: {0:?}")]
+    TransactionCanceled(Vec<CancellationReason>),
+    #[error("Request did not reach DynamoDB or got no usable response. Error: {0}")]
+    Transport(String),
+    #[error("DynamoDB returned an error. Code: {code}, Error: {message}")]
+    Service { code: String, message: String },
+    #[error("Gave up waiting. Error: {0}")]
+    WaitTimeout(String),
+    #[error("DynamoDB returned a response that could not be read. Error: {0}")]
+    UnexpectedResponse(String),
+    #[error("Item could not be mapped. Error: {0}")]
+    Item(#[from] ItemError),
+}
+
+impl DynamoDbError {
+    /// Maps a modeled DynamoDB error code, e.g. `ConditionalCheckFailedException`, to a variant.
+    pub fn from_code(code: &str, message: impl Into<String>) -> Self {
+        let message = message.into();
+
+        match code {
+            "ConditionalCheckFailedException" => DynamoDbError::ConditionalCheckFailed(message),
+            "DuplicateItemException" => DynamoDbError::DuplicateItem(message),
+            "ResourceNotFoundException" => DynamoDbError::ResourceNotFound(message),
+            "ResourceInUseException" => DynamoDbError::ResourceInUse(message),
+            "ProvisionedThroughputExceededException"
+            | "RequestLimitExceeded"
+            | "ThrottlingException"
+            | "LimitExceededException" => DynamoDbError::Throttled(message),
+            "ValidationException" => DynamoDbError::ValidationError(message),
+            "TransactionCanceledException" => DynamoDbError::TransactionCanceled(vec![]),
+            "ExpiredIteratorException" => DynamoDbError::ExpiredIterator(message),
+            "TrimmedDataAccessException" => DynamoDbError::TrimmedDataAccess(message),
+            _ => DynamoDbError::Service {
+                code: code.to_string(),
+                message,
+            },
+        }
+    }
+}
+
+impl<E, R> From<SdkError<E, R>> for DynamoDbError
+where
+    E: ProvideErrorKind + std::error::Error,
+{
+    fn from(error: SdkError<E, R>) -> Self {
+        match error {
+            SdkError::ServiceError { err, .. } => match err.code() {
+                Some(code) => DynamoDbError::from_code(code, err.to_string()),
+                None => DynamoDbError::Service {
+                    code: "Unknown".to_string(),
+                    message: err.to_string(),
+                },
+            },
+            SdkError::ConstructionFailure(err) => DynamoDbError::ValidationError(err.to_string()),
+            SdkError::TimeoutError(err) => DynamoDbError::Transport(err.to_string()),
+            SdkError::DispatchFailure(err) => DynamoDbError::Transport(err.to_string()),
+            SdkError::ResponseError { err, .. } => DynamoDbError::Transport(err.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::DynamoDbError;
+
+    #[test]
+    fn test_from_code() {
+        assert!(matches!(
+            DynamoDbError::from_code("ConditionalCheckFailedException", "failed"),
+            DynamoDbError::ConditionalCheckFailed(_)
+        ));
+        assert!(matches!(
+            DynamoDbError::from_code("ProvisionedThroughputExceededException", "slow down"),
+            DynamoDbError::Throttled(_)
+        ));
+        assert!(matches!(
+            DynamoDbError::from_code("ExpiredIteratorException", "expired"),
+            DynamoDbError::ExpiredIterator(_)
+        ));
+        assert!(matches!(
+            DynamoDbError::from_code("InternalServerError", "oops"),
+            DynamoDbError::Service { .. }
+        ));
+    }
+}
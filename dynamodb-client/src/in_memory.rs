@@ -0,0 +1,1698 @@
+mod expression;
+mod reserved_words;
+
+use std::cmp::Ordering;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, HashMap};
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use async_stream::try_stream;
+use async_trait::async_trait;
+use aws_sdk_dynamodb::model;
+use aws_sdk_dynamodb::model::ReturnValue;
+use aws_sdk_dynamodb::output::{
+    CreateTableOutput, DeleteItemOutput, DeleteTableOutput, GetItemOutput, ListTablesOutput,
+    PutItemOutput, TransactWriteItemsOutput, UpdateItemOutput, UpdateTableOutput,
+};
+use futures_util::stream;
+
+use crate::api::DynamoDbApi;
+use crate::client::{ExistsTableResultType, ItemStream, ScanPageStream, TableNameStream};
+use crate::error::DynamoDbError;
+use crate::in_memory::expression::{apply_update, attribute_equal, compare_attribute_values, evaluate_condition};
+use crate::query::batch_get::{BatchGetOutput, BatchGetQuery};
+use crate::query::batch_write::{BatchWriteOutput, BatchWriteQuery};
+use crate::query::create_table::CreateTableQuery;
+use crate::query::delete_item::DeleteItemQuery;
+use crate::query::delete_table::DeleteTableQuery;
+use crate::query::describe_table::{DescribeTableQuery, TableDescription};
+use crate::query::dynamodb_query::{
+    ExpressionAttributeNames, ExpressionAttributeValues, KeySchema, KeyType, Keys, ProvisionedThroughput,
+    StreamViewType,
+};
+use crate::query::get_item::GetItemQuery;
+use crate::query::list_tables::ListTablesQuery;
+use crate::query::parallel_scan::{ParallelScanQuery, ScanPage, SegmentCheckpoint};
+use crate::query::put_item::{Items, PutItemQuery};
+use crate::query::query::QueryQuery;
+use crate::query::scan::ScanQuery;
+use crate::query::transact_write::{
+    CancellationReason, CancellationReasonCode, TransactWriteItem, TransactWriteQuery,
+};
+use crate::query::update_item::UpdateItemQuery;
+use crate::query::update_table::{GlobalSecondaryIndexUpdate, UpdateTableQuery};
+use crate::stream::{StreamEventName, StreamRecord};
+
+/// Default page size of ListTables.
+const LIST_TABLES_LIMIT: usize = 100;
+
+/// A `DynamoDbApi` that keeps tables in memory, for tests that should not need DynamoDB Local.
+///
+/// Key schemas, condition, update, key condition and filter expressions are honoured, and every
+/// write on a table with a stream enabled emits a `StreamRecord`. Tables are `ACTIVE` as soon as
+/// they are created. Clones share the same tables.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryDynamoDb {
+    tables: Arc<Mutex<BTreeMap<String, Table>>>,
+}
+
+impl InMemoryDynamoDb {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every item of the table, in insertion order. Empty when the table does not exist.
+    pub fn items(&self, table_name: &str) -> Vec<Items> {
+        self.lock()
+            .get(table_name)
+            .map(|table| table.items.clone())
+            .unwrap_or_default()
+    }
+
+    /// Stream records emitted so far, oldest first. Empty when the table does not exist.
+    pub fn stream_records(&self, table_name: &str) -> Vec<StreamRecord> {
+        self.lock()
+            .get(table_name)
+            .map(|table| table.stream_records.clone())
+            .unwrap_or_default()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BTreeMap<String, Table>> {
+        self.tables.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+#[derive(Debug, Clone)]
+struct KeyAttributes {
+    hash_key: String,
+    range_key: Option<String>,
+}
+
+impl KeyAttributes {
+    fn from_key_schemas(key_schemas: &[KeySchema]) -> Result<Self, DynamoDbError> {
+        let find = |key_type: KeyType| {
+            key_schemas
+                .iter()
+                .find(|key_schema| key_schema.key_type() == key_type)
+                .map(|key_schema| key_schema.attribute_name().to_string())
+        };
+
+        let hash_key = find(KeyType::Hash)
+            .ok_or_else(|| DynamoDbError::ValidationError("A HASH key is required.".to_string()))?;
+
+        Ok(Self {
+            hash_key,
+            range_key: find(KeyType::Range),
+        })
+    }
+
+    fn names(&self) -> impl Iterator<Item = &String> {
+        std::iter::once(&self.hash_key).chain(self.range_key.as_ref())
+    }
+
+    /// `None` when the item lacks one of the attributes, i.e. it is not part of a sparse index.
+    fn keys_of(&self, item: &Items) -> Option<Keys> {
+        self.names()
+            .map(|name| item.get(name).map(|value| (name.clone(), value.clone())))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Table {
+    description: model::TableDescription,
+    key_attributes: KeyAttributes,
+    indexes: HashMap<String, KeyAttributes>,
+    stream_view_type: Option<StreamViewType>,
+    items: Vec<Items>,
+    stream_records: Vec<StreamRecord>,
+    sequence_number: u64,
+}
+
+impl Table {
+    fn create(table_name: &str, query: &CreateTableQuery) -> Result<Self, DynamoDbError> {
+        let key_attributes = KeyAttributes::from_key_schemas(query.key_schemas())?;
+
+        let mut indexes = HashMap::new();
+        for index in query.global_secondary_indexes() {
+            indexes.insert(
+                index.index_name().to_string(),
+                KeyAttributes::from_key_schemas(index.key_schemas())?,
+            );
+        }
+        for index in query.local_secondary_indexes() {
+            indexes.insert(
+                index.index_name().to_string(),
+                KeyAttributes::from_key_schemas(index.key_schemas())?,
+            );
+        }
+
+        let stream_specification = query.stream_specification();
+        let stream_view_type = stream_specification
+            .stream_enabled()
+            .then(|| stream_specification.stream_view_type().unwrap_or(StreamViewType::KeysOnly));
+
+        let description = model::TableDescription::builder()
+            .table_name(table_name)
+            .table_arn(format!("arn:aws:dynamodb:ddblocal:000000000000:table/{}", table_name))
+            .table_status(model::TableStatus::Active)
+            .set_key_schema(Some(key_schema_elements(query.key_schemas())))
+            .set_attribute_definitions(Some(
+                query
+                    .attribute_definitions()
+                    .iter()
+                    .map(|attribute_definition| {
+                        model::AttributeDefinition::builder()
+                            .attribute_name(attribute_definition.attribute_name())
+                            .attribute_type(attribute_definition.attribute_type().into())
+                            .build()
+                    })
+                    .collect(),
+            ))
+            .set_billing_mode_summary(query.billing_mode().map(|billing_mode| {
+                model::BillingModeSummary::builder()
+                    .billing_mode(billing_mode.into())
+                    .build()
+            }))
+            .set_provisioned_throughput(query.provisioned_throughput().as_ref().map(throughput_description))
+            .set_global_secondary_indexes(Some(
+                query
+                    .global_secondary_indexes()
+                    .iter()
+                    .map(|index| {
+                        model::GlobalSecondaryIndexDescription::builder()
+                            .index_name(index.index_name())
+                            .index_status(model::IndexStatus::Active)
+                            .set_key_schema(Some(key_schema_elements(index.key_schemas())))
+                            .build()
+                    })
+                    .collect(),
+            ))
+            .set_stream_specification(stream_specification.stream_enabled().then(|| {
+                model::StreamSpecification::builder()
+                    .stream_enabled(true)
+                    .set_stream_view_type(stream_view_type.map(Into::into))
+                    .build()
+            }))
+            .set_latest_stream_arn(stream_view_type.map(|_| {
+                format!("arn:aws:dynamodb:ddblocal:000000000000:table/{}/stream/0", table_name)
+            }))
+            .build();
+
+        Ok(Self {
+            description,
+            key_attributes,
+            indexes,
+            stream_view_type,
+            items: vec![],
+            stream_records: vec![],
+            sequence_number: 0,
+        })
+    }
+
+    fn description(&self) -> model::TableDescription {
+        let mut description = self.description.clone();
+        description.item_count = self.items.len() as i64;
+
+        description
+    }
+
+    /// Applies an UpdateTable request. Changes take effect at once, so the table stays `ACTIVE`.
+    fn update_schema(&mut self, query: &UpdateTableQuery) -> Result<(), DynamoDbError> {
+        let creates_or_deletes = query
+            .global_secondary_index_updates()
+            .iter()
+            .filter(|update| !matches!(update, GlobalSecondaryIndexUpdate::Update { .. }))
+            .count();
+        if creates_or_deletes > 1 {
+            return Err(DynamoDbError::ValidationError(
+                "Only 1 online index can be created or deleted simultaneously per table".to_string(),
+            ));
+        }
+
+        if let (Some(throughput), Some(current)) =
+            (query.provisioned_throughput(), &self.description.provisioned_throughput)
+        {
+            if current.read_capacity_units() == Some(*throughput.read_capacity_units())
+                && current.write_capacity_units() == Some(*throughput.write_capacity_units())
+            {
+                return Err(DynamoDbError::ValidationError(format!(
+                    "The provisioned throughput for the table will not change. The requested value equals the current value. Table: {}",
+                    query.table_name()
+                )));
+            }
+        }
+
+        let mut indexes = self.indexes.clone();
+        let mut index_descriptions = self.description.global_secondary_indexes.clone().unwrap_or_default();
+        for update in query.global_secondary_index_updates() {
+            match update {
+                GlobalSecondaryIndexUpdate::Create(index) => {
+                    if indexes.contains_key(index.index_name()) {
+                        return Err(DynamoDbError::ValidationError(format!(
+                            "Attempting to create an index which already exists: {}",
+                            index.index_name()
+                        )));
+                    }
+
+                    indexes.insert(
+                        index.index_name().to_string(),
+                        KeyAttributes::from_key_schemas(index.key_schemas())?,
+                    );
+                    index_descriptions.push(
+                        model::GlobalSecondaryIndexDescription::builder()
+                            .index_name(index.index_name())
+                            .index_status(model::IndexStatus::Active)
+                            .set_key_schema(Some(key_schema_elements(index.key_schemas())))
+                            .set_provisioned_throughput(
+                                index.provisioned_throughput().as_ref().map(throughput_description),
+                            )
+                            .build(),
+                    );
+                }
+                GlobalSecondaryIndexUpdate::Update {
+                    index_name,
+                    provisioned_throughput,
+                } => {
+                    let index = index_descriptions
+                        .iter_mut()
+                        .find(|index| index.index_name() == Some(index_name.as_str()))
+                        .ok_or_else(|| index_not_found(index_name))?;
+
+                    index.provisioned_throughput = Some(throughput_description(provisioned_throughput));
+                }
+                GlobalSecondaryIndexUpdate::Delete { index_name } => {
+                    indexes.remove(index_name).ok_or_else(|| index_not_found(index_name))?;
+                    index_descriptions.retain(|index| index.index_name() != Some(index_name.as_str()));
+                }
+            }
+        }
+
+        let stream_view_type = match query.stream_specification() {
+            Some(specification) if specification.stream_enabled() == self.stream_view_type.is_some() => {
+                return Err(DynamoDbError::ValidationError(format!(
+                    "Table {} already has its stream {}.",
+                    query.table_name(),
+                    if specification.stream_enabled() { "enabled" } else { "disabled" }
+                )));
+            }
+            Some(specification) => specification
+                .stream_enabled()
+                .then(|| specification.stream_view_type().unwrap_or(StreamViewType::KeysOnly)),
+            None => self.stream_view_type,
+        };
+
+        let mut attribute_definitions = self.description.attribute_definitions.clone().unwrap_or_default();
+        for attribute_definition in query.attribute_definitions() {
+            let exists = attribute_definitions
+                .iter()
+                .any(|current| current.attribute_name() == Some(attribute_definition.attribute_name()));
+            if !exists {
+                attribute_definitions.push(
+                    model::AttributeDefinition::builder()
+                        .attribute_name(attribute_definition.attribute_name())
+                        .attribute_type(attribute_definition.attribute_type().into())
+                        .build(),
+                );
+            }
+        }
+
+        self.description.attribute_definitions = Some(attribute_definitions);
+        self.description.global_secondary_indexes = Some(index_descriptions);
+        self.indexes = indexes;
+
+        if let Some(billing_mode) = query.billing_mode() {
+            self.description.billing_mode_summary = Some(
+                model::BillingModeSummary::builder()
+                    .billing_mode(billing_mode.into())
+                    .build(),
+            );
+        }
+        if let Some(throughput) = query.provisioned_throughput() {
+            self.description.provisioned_throughput = Some(throughput_description(throughput));
+        }
+
+        if query.stream_specification().is_some() {
+            self.description.stream_specification = Some(
+                model::StreamSpecification::builder()
+                    .stream_enabled(stream_view_type.is_some())
+                    .set_stream_view_type(stream_view_type.map(Into::into))
+                    .build(),
+            );
+            // A stream that is enabled again gets a new ARN; a disabled one keeps the last ARN.
+            if stream_view_type.is_some() {
+                self.description.latest_stream_arn = Some(format!(
+                    "arn:aws:dynamodb:ddblocal:000000000000:table/{}/stream/{}",
+                    query.table_name(),
+                    self.sequence_number
+                ));
+            }
+            self.stream_view_type = stream_view_type;
+        }
+
+        Ok(())
+    }
+
+    /// DynamoDB rejects keys that are missing an attribute of the key schema or carry extra ones.
+    fn validate_keys(&self, keys: &Keys) -> Result<(), DynamoDbError> {
+        let valid = keys.len() == self.key_attributes.names().count()
+            && self.key_attributes.names().all(|name| keys.contains_key(name));
+
+        if valid {
+            Ok(())
+        } else {
+            Err(DynamoDbError::ValidationError(
+                "The provided key element does not match the schema".to_string(),
+            ))
+        }
+    }
+
+    fn keys_of(&self, item: &Items) -> Result<Keys, DynamoDbError> {
+        self.key_attributes.keys_of(item).ok_or_else(|| {
+            DynamoDbError::ValidationError(
+                "One or more parameter values were invalid: Missing the key in the item".to_string(),
+            )
+        })
+    }
+
+    fn position(&self, keys: &Keys) -> Option<usize> {
+        self.items.iter().position(|item| {
+            keys.iter().all(|(name, value)| {
+                item.get(name)
+                    .map(|current| attribute_equal(current, value))
+                    .unwrap_or(false)
+            })
+        })
+    }
+
+    fn find(&self, keys: &Keys) -> Result<Option<&Items>, DynamoDbError> {
+        self.validate_keys(keys)?;
+
+        Ok(self.position(keys).map(|position| &self.items[position]))
+    }
+
+    fn put(&mut self, query: &PutItemQuery) -> Result<Option<Items>, DynamoDbError> {
+        let item = query.items();
+        let keys = self.keys_of(&item)?;
+        let position = self.position(&keys);
+        let old_item = position.map(|position| self.items[position].clone());
+
+        check_condition(
+            query.condition_expression(),
+            query.expression_attribute_names(),
+            query.expression_attribute_values(),
+            old_item.as_ref(),
+        )?;
+
+        match position {
+            Some(position) => self.items[position] = item.clone(),
+            None => self.items.push(item.clone()),
+        }
+        self.record(keys, Some(item), old_item.clone());
+
+        Ok(old_item)
+    }
+
+    /// Returns the item before and after the update, and the attributes the update touched.
+    fn update(&mut self, query: &UpdateItemQuery) -> Result<(Option<Items>, Items, Vec<String>), DynamoDbError> {
+        self.validate_keys(query.keys())?;
+
+        let position = self.position(query.keys());
+        let old_item = position.map(|position| self.items[position].clone());
+
+        check_condition(
+            query.condition_expression(),
+            query.expression_attribute_names(),
+            query.expression_attribute_values(),
+            old_item.as_ref(),
+        )?;
+
+        let mut new_item = old_item.clone().unwrap_or_else(|| query.keys().clone());
+        let touched = apply_update(
+            query.update_expression(),
+            query.expression_attribute_names(),
+            query.expression_attribute_values(),
+            &mut new_item,
+        )
+        .map_err(DynamoDbError::ValidationError)?;
+
+        if self.key_attributes.names().any(|name| touched.contains(name)) {
+            return Err(DynamoDbError::ValidationError(
+                "Cannot update attribute: This attribute is part of the key".to_string(),
+            ));
+        }
+
+        match position {
+            Some(position) => self.items[position] = new_item.clone(),
+            None => self.items.push(new_item.clone()),
+        }
+        self.record(query.keys().clone(), Some(new_item.clone()), old_item.clone());
+
+        Ok((old_item, new_item, touched))
+    }
+
+    fn delete(&mut self, query: &DeleteItemQuery) -> Result<Option<Items>, DynamoDbError> {
+        self.validate_keys(query.keys())?;
+
+        let position = self.position(query.keys());
+        let old_item = position.map(|position| self.items[position].clone());
+
+        check_condition(
+            query.condition_expression(),
+            query.expression_attribute_names(),
+            query.expression_attribute_values(),
+            old_item.as_ref(),
+        )?;
+
+        if let Some(position) = position {
+            self.items.remove(position);
+            self.record(query.keys().clone(), None, old_item.clone());
+        }
+
+        Ok(old_item)
+    }
+
+    /// Keys of the item an action of a transaction targets.
+    fn target_keys(&self, item: &TransactWriteItem) -> Result<Keys, DynamoDbError> {
+        match item {
+            TransactWriteItem::Put(query) => self.keys_of(&query.items()),
+            TransactWriteItem::Update(query) => Ok(query.keys().clone()),
+            TransactWriteItem::Delete(query) => Ok(query.keys().clone()),
+            TransactWriteItem::ConditionCheck(check) => Ok(check.keys().clone()),
+        }
+    }
+
+    fn transact(&mut self, item: &TransactWriteItem) -> Result<(), DynamoDbError> {
+        match item {
+            TransactWriteItem::Put(query) => self.put(query).map(|_| ()),
+            TransactWriteItem::Update(query) => self.update(query).map(|_| ()),
+            TransactWriteItem::Delete(query) => self.delete(query).map(|_| ()),
+            TransactWriteItem::ConditionCheck(check) => check_condition(
+                &Some(check.condition_expression().to_string()),
+                check.expression_attribute_names(),
+                check.expression_attribute_values(),
+                self.find(check.keys())?,
+            ),
+        }
+    }
+
+    /// Emits a stream record shaped by the stream view type. Nothing when streams are disabled.
+    fn record(&mut self, keys: Keys, new_image: Option<Items>, old_image: Option<Items>) {
+        let Some(stream_view_type) = self.stream_view_type else {
+            return;
+        };
+
+        let event_name = match (&new_image, &old_image) {
+            (Some(_), None) => StreamEventName::Insert,
+            (Some(_), Some(_)) => StreamEventName::Modify,
+            _ => StreamEventName::Remove,
+        };
+
+        let (new_image, old_image) = match stream_view_type {
+            StreamViewType::KeysOnly => (None, None),
+            StreamViewType::NewImage => (new_image, None),
+            StreamViewType::OldImage => (None, old_image),
+            StreamViewType::NewAndOldImages => (new_image, old_image),
+        };
+
+        self.sequence_number += 1;
+        self.stream_records.push(StreamRecord::new(
+            event_name,
+            format!("{:021}", self.sequence_number),
+            keys,
+            new_image,
+            old_image,
+        ));
+    }
+
+    /// Items of the table or of `index_name` matching `key_condition`, ordered by the sort key.
+    fn query(&self, query: &QueryQuery) -> Result<Vec<Items>, DynamoDbError> {
+        let key_attributes = self.key_attributes_of(query.index_name())?;
+
+        let mut items = vec![];
+        for item in &self.items {
+            if key_attributes.keys_of(item).is_none() {
+                continue;
+            }
+
+            let matches = evaluate(
+                query.key_condition_expression(),
+                query.expression_attribute_names(),
+                query.expression_attribute_values(),
+                item,
+            )? && match query.filter_expression() {
+                Some(filter_expression) => evaluate(
+                    filter_expression,
+                    query.expression_attribute_names(),
+                    query.expression_attribute_values(),
+                    item,
+                )?,
+                None => true,
+            };
+
+            if matches {
+                items.push(item.clone());
+            }
+        }
+
+        if let Some(range_key) = &key_attributes.range_key {
+            items.sort_by(|left, right| match (left.get(range_key), right.get(range_key)) {
+                (Some(left), Some(right)) => compare_attribute_values(left, right).unwrap_or(Ordering::Equal),
+                _ => Ordering::Equal,
+            });
+        }
+
+        if !*query.scan_index_forward() {
+            items.reverse();
+        }
+
+        Ok(items)
+    }
+
+    fn scan(&self, query: &ScanQuery) -> Result<Vec<Items>, DynamoDbError> {
+        let key_attributes = self.key_attributes_of(query.index_name())?;
+
+        let mut items = vec![];
+        for item in &self.items {
+            if key_attributes.keys_of(item).is_none() {
+                continue;
+            }
+
+            if let (Some(segment), Some(total_segments)) = (query.segment(), query.total_segments()) {
+                if segment_of(key_attributes, item, *total_segments) != *segment {
+                    continue;
+                }
+            }
+
+            let matches = match query.filter_expression() {
+                Some(filter_expression) => evaluate(
+                    filter_expression,
+                    query.expression_attribute_names(),
+                    query.expression_attribute_values(),
+                    item,
+                )?,
+                None => true,
+            };
+
+            if matches {
+                items.push(item.clone());
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Pages of `limit` items after `exclusive_start_key`, as one segment of a parallel scan reads them.
+    /// Items are read in key order, so a segment resumes correctly even if that key was deleted since.
+    fn scan_pages(
+        &self,
+        query: &ScanQuery,
+        exclusive_start_key: &Option<Keys>,
+    ) -> Result<Vec<ScanPage>, DynamoDbError> {
+        let mut items = self.scan(query)?;
+        items.sort_by(|left, right| compare_keys(&self.key_attributes, left, right));
+
+        if let Some(exclusive_start_key) = exclusive_start_key {
+            items.retain(|item| {
+                compare_keys(&self.key_attributes, item, exclusive_start_key) == Ordering::Greater
+            });
+        }
+
+        let segment = query.segment().unwrap_or_default();
+        let page_size = query.limit().map_or(items.len(), |limit| limit as usize).max(1);
+        let chunks = items.chunks(page_size).collect::<Vec<_>>();
+
+        if chunks.is_empty() {
+            return Ok(vec![ScanPage::new(segment, vec![], SegmentCheckpoint::Completed)]);
+        }
+
+        let mut pages = vec![];
+        for (index, chunk) in chunks.iter().enumerate() {
+            let checkpoint = match chunk.last() {
+                Some(last) if index + 1 < chunks.len() => SegmentCheckpoint::InProgress(self.keys_of(last)?),
+                _ => SegmentCheckpoint::Completed,
+            };
+
+            pages.push(ScanPage::new(segment, chunk.to_vec(), checkpoint));
+        }
+
+        Ok(pages)
+    }
+
+    fn key_attributes_of(&self, index_name: &Option<String>) -> Result<&KeyAttributes, DynamoDbError> {
+        match index_name {
+            Some(index_name) => self.indexes.get(index_name).ok_or_else(|| {
+                DynamoDbError::ValidationError(format!(
+                    "The table does not have the specified index: {}",
+                    index_name
+                ))
+            }),
+            None => Ok(&self.key_attributes),
+        }
+    }
+}
+
+/// Hash key first, then range key. Attributes of different types compare as equal.
+fn compare_keys(key_attributes: &KeyAttributes, left: &Items, right: &Items) -> Ordering {
+    key_attributes
+        .names()
+        .map(|name| match (left.get(name), right.get(name)) {
+            (Some(left), Some(right)) => compare_attribute_values(left, right).unwrap_or(Ordering::Equal),
+            _ => Ordering::Equal,
+        })
+        .find(|ordering| ordering.is_ne())
+        .unwrap_or(Ordering::Equal)
+}
+
+/// Segments are assigned by key, so an item stays in its segment while others are written or deleted.
+fn segment_of(key_attributes: &KeyAttributes, item: &Items, total_segments: i32) -> i32 {
+    let mut hasher = DefaultHasher::new();
+    for name in key_attributes.names() {
+        name.hash(&mut hasher);
+        format!("{:?}", item.get(name)).hash(&mut hasher);
+    }
+
+    (hasher.finish() % total_segments.max(1) as u64) as i32
+}
+
+fn throughput_description(throughput: &ProvisionedThroughput) -> model::ProvisionedThroughputDescription {
+    model::ProvisionedThroughputDescription::builder()
+        .read_capacity_units(*throughput.read_capacity_units())
+        .write_capacity_units(*throughput.write_capacity_units())
+        .build()
+}
+
+fn key_schema_elements(key_schemas: &[KeySchema]) -> Vec<model::KeySchemaElement> {
+    key_schemas
+        .iter()
+        .map(|key_schema| {
+            model::KeySchemaElement::builder()
+                .attribute_name(key_schema.attribute_name())
+                .key_type(key_schema.key_type().into())
+                .build()
+        })
+        .collect()
+}
+
+fn evaluate(
+    expression: &str,
+    names: &ExpressionAttributeNames,
+    values: &ExpressionAttributeValues,
+    item: &Items,
+) -> Result<bool, DynamoDbError> {
+    evaluate_condition(expression, names, values, item).map_err(DynamoDbError::ValidationError)
+}
+
+/// A missing item is evaluated as an item without attributes, like DynamoDB does.
+fn check_condition(
+    condition_expression: &Option<String>,
+    names: &ExpressionAttributeNames,
+    values: &ExpressionAttributeValues,
+    item: Option<&Items>,
+) -> Result<(), DynamoDbError> {
+    let Some(condition_expression) = condition_expression else {
+        return Ok(());
+    };
+
+    if evaluate(condition_expression, names, values, item.unwrap_or(&Items::new()))? {
+        Ok(())
+    } else {
+        Err(DynamoDbError::ConditionalCheckFailed(
+            "The conditional request failed".to_string(),
+        ))
+    }
+}
+
+fn table_mut<'a>(
+    tables: &'a mut BTreeMap<String, Table>,
+    table_name: &str,
+) -> Result<&'a mut Table, DynamoDbError> {
+    tables.get_mut(table_name).ok_or_else(|| resource_not_found(table_name))
+}
+
+fn table<'a>(tables: &'a BTreeMap<String, Table>, table_name: &str) -> Result<&'a Table, DynamoDbError> {
+    tables.get(table_name).ok_or_else(|| resource_not_found(table_name))
+}
+
+fn resource_not_found(table_name: &str) -> DynamoDbError {
+    DynamoDbError::ResourceNotFound(format!("Cannot do operations on a non-existent table: {}", table_name))
+}
+
+fn index_not_found(index_name: &str) -> DynamoDbError {
+    DynamoDbError::ResourceNotFound(format!("Requested resource not found: Index: {} not found", index_name))
+}
+
+fn return_values(
+    return_value: ReturnValue,
+    old_item: Option<Items>,
+    new_item: Items,
+    touched: &[String],
+) -> Option<Items> {
+    let updated = |item: Items| {
+        item.into_iter()
+            .filter(|(name, _)| touched.contains(name))
+            .collect::<Items>()
+    };
+
+    match return_value {
+        ReturnValue::AllOld => old_item,
+        ReturnValue::AllNew => Some(new_item),
+        ReturnValue::UpdatedOld => old_item.map(updated),
+        ReturnValue::UpdatedNew => Some(updated(new_item)),
+        _ => None,
+    }
+}
+
+fn cancellation_reason(index: usize, error: &DynamoDbError, old_item: Option<Items>) -> CancellationReason {
+    match error {
+        DynamoDbError::ConditionalCheckFailed(_) => CancellationReason::new(
+            index,
+            CancellationReasonCode::ConditionalCheckFailed,
+            Some(error.to_string()),
+            old_item,
+        ),
+        _ => CancellationReason::new(index, CancellationReasonCode::ValidationError, Some(error.to_string()), None),
+    }
+}
+
+/// The item an action of a transaction targets, as it is in `table`.
+fn current_item(table: &Table, item: &TransactWriteItem) -> Option<Items> {
+    let keys = table.target_keys(item).ok()?;
+
+    table.find(&keys).ok().flatten().cloned()
+}
+
+fn same_keys(left: &Keys, right: &Keys) -> bool {
+    left.len() == right.len()
+        && left
+            .iter()
+            .all(|(name, value)| right.get(name).is_some_and(|other| attribute_equal(value, other)))
+}
+
+#[async_trait]
+impl DynamoDbApi for InMemoryDynamoDb {
+    async fn create_table(
+        &self,
+        table_name: &str,
+        query: &CreateTableQuery,
+    ) -> Result<CreateTableOutput, DynamoDbError> {
+        let mut tables = self.lock();
+
+        if tables.contains_key(table_name) {
+            return Err(DynamoDbError::ResourceInUse(format!(
+                "Cannot create preexisting table: {}",
+                table_name
+            )));
+        }
+
+        let table = Table::create(table_name, query)?;
+        let description = table.description();
+        tables.insert(table_name.to_string(), table);
+
+        Ok(CreateTableOutput::builder().table_description(description).build())
+    }
+
+    async fn delete_table(&self, query: &DeleteTableQuery) -> Result<DeleteTableOutput, DynamoDbError> {
+        let table = self
+            .lock()
+            .remove(query.table_name())
+            .ok_or_else(|| resource_not_found(query.table_name()))?;
+
+        let mut description = table.description();
+        description.table_status = Some(model::TableStatus::Deleting);
+
+        Ok(DeleteTableOutput::builder().table_description(description).build())
+    }
+
+    async fn update_table(&self, query: &UpdateTableQuery) -> Result<UpdateTableOutput, DynamoDbError> {
+        let mut tables = self.lock();
+        let table = tables
+            .get_mut(query.table_name())
+            .ok_or_else(|| resource_not_found(query.table_name()))?;
+
+        table.update_schema(query)?;
+
+        Ok(UpdateTableOutput::builder().table_description(table.description()).build())
+    }
+
+    async fn describe_table(&self, query: &DescribeTableQuery) -> Result<TableDescription, DynamoDbError> {
+        let tables = self.lock();
+        let description = table(&tables, query.table_name())?.description();
+
+        TableDescription::try_from(&description).map_err(DynamoDbError::UnexpectedResponse)
+    }
+
+    async fn exists_table(&self, table_name: &str) -> Result<ExistsTableResultType, DynamoDbError> {
+        if self.lock().contains_key(table_name) {
+            Ok(ExistsTableResultType::Found)
+        } else {
+            Ok(ExistsTableResultType::NotFound)
+        }
+    }
+
+    /// Tables are active as soon as they exist, so this never has to wait.
+    async fn wait_until_active(
+        &self,
+        table_name: &str,
+        timeout: Duration,
+    ) -> Result<TableDescription, DynamoDbError> {
+        match self.describe_table(&DescribeTableQuery::new(table_name)).await {
+            Err(DynamoDbError::ResourceNotFound(_)) => Err(DynamoDbError::WaitTimeout(format!(
+                "Table {} did not become active within {:?}.",
+                table_name, timeout
+            ))),
+            result => result,
+        }
+    }
+
+    async fn wait_until_deleted(&self, table_name: &str, timeout: Duration) -> Result<(), DynamoDbError> {
+        match self.exists_table(table_name).await? {
+            ExistsTableResultType::NotFound => Ok(()),
+            ExistsTableResultType::Found => Err(DynamoDbError::WaitTimeout(format!(
+                "Table {} was not deleted within {:?}.",
+                table_name, timeout
+            ))),
+        }
+    }
+
+    async fn list_tables(&self, query: &ListTablesQuery) -> Result<ListTablesOutput, DynamoDbError> {
+        let tables = self.lock();
+        let limit = query.limit().map(|limit| limit as usize).unwrap_or(LIST_TABLES_LIMIT);
+
+        let mut table_names = tables
+            .keys()
+            .filter(|table_name| match query.exclusive_start_table_name() {
+                Some(start) => table_name.as_str() > start.as_str(),
+                None => true,
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+
+        let last_evaluated_table_name = if table_names.len() > limit {
+            table_names.truncate(limit);
+            table_names.last().cloned()
+        } else {
+            None
+        };
+
+        Ok(ListTablesOutput::builder()
+            .set_table_names(Some(table_names))
+            .set_last_evaluated_table_name(last_evaluated_table_name)
+            .build())
+    }
+
+    fn list_all_tables(&self, query: ListTablesQuery) -> TableNameStream {
+        let client = self.clone();
+
+        Box::pin(try_stream! {
+            let mut query = query;
+
+            loop {
+                let output = client.list_tables(&query).await?;
+
+                for table_name in output.table_names.unwrap_or_default() {
+                    yield table_name;
+                }
+
+                match output.last_evaluated_table_name {
+                    Some(table_name) => query = query.with_exclusive_start_table_name(table_name),
+                    None => break,
+                }
+            }
+        })
+    }
+
+    async fn get_item(&self, query: &GetItemQuery) -> Result<GetItemOutput, DynamoDbError> {
+        let tables = self.lock();
+        let keys = Keys::from([(query.key().name().to_string(), query.key().value().clone())]);
+        let item = table(&tables, query.table_name())?.find(&keys)?.cloned();
+
+        Ok(GetItemOutput::builder().set_item(item).build())
+    }
+
+    async fn put_item(&self, query: PutItemQuery) -> Result<PutItemOutput, DynamoDbError> {
+        let mut tables = self.lock();
+        let old_item = table_mut(&mut tables, query.table_name())?.put(&query)?;
+
+        let attributes = match query.return_values() {
+            ReturnValue::AllOld => old_item,
+            _ => None,
+        };
+
+        Ok(PutItemOutput::builder().set_attributes(attributes).build())
+    }
+
+    async fn update_item(&self, query: UpdateItemQuery) -> Result<UpdateItemOutput, DynamoDbError> {
+        let mut tables = self.lock();
+        let (old_item, new_item, touched) = table_mut(&mut tables, query.table_name())?.update(&query)?;
+
+        Ok(UpdateItemOutput::builder()
+            .set_attributes(return_values(query.return_values(), old_item, new_item, &touched))
+            .build())
+    }
+
+    async fn delete_item(&self, query: DeleteItemQuery) -> Result<DeleteItemOutput, DynamoDbError> {
+        let mut tables = self.lock();
+        let old_item = table_mut(&mut tables, query.table_name())?.delete(&query)?;
+
+        let attributes = match query.return_values() {
+            ReturnValue::AllOld => old_item,
+            _ => None,
+        };
+
+        Ok(DeleteItemOutput::builder().set_attributes(attributes).build())
+    }
+
+    /// Applies the actions to a copy of the tables and only keeps it when every action succeeded.
+    /// Conditions are checked against the tables as they were before the transaction, like DynamoDB does.
+    async fn transact_write(
+        &self,
+        query: TransactWriteQuery,
+    ) -> Result<TransactWriteItemsOutput, DynamoDbError> {
+        let mut tables = self.lock();
+
+        let mut targets: Vec<(&str, Keys)> = vec![];
+        for item in query.items() {
+            let Ok(keys) = table(&tables, item.table_name())?.target_keys(item) else {
+                continue;
+            };
+
+            if targets
+                .iter()
+                .any(|(table_name, other)| *table_name == item.table_name() && same_keys(other, &keys))
+            {
+                return Err(DynamoDbError::ValidationError(
+                    "Transaction request cannot include multiple operations on one item".to_string(),
+                ));
+            }
+            targets.push((item.table_name(), keys));
+        }
+
+        let mut reasons = vec![];
+        for (index, item) in query.items().iter().enumerate() {
+            let mut before = table(&tables, item.table_name())?.clone();
+
+            match before.transact(item) {
+                Ok(()) => reasons.push(CancellationReason::new(index, CancellationReasonCode::None, None, None)),
+                Err(error) => {
+                    let old_item = if query.return_old_items_on_condition_failure() {
+                        current_item(&before, item)
+                    } else {
+                        None
+                    };
+                    reasons.push(cancellation_reason(index, &error, old_item));
+                }
+            }
+        }
+
+        if reasons.iter().any(CancellationReason::is_failure) {
+            return Err(DynamoDbError::TransactionCanceled(reasons));
+        }
+
+        for item in query.items() {
+            table_mut(&mut tables, item.table_name())?.transact(item)?;
+        }
+
+        Ok(TransactWriteItemsOutput::builder().build())
+    }
+
+    /// The whole batch is validated before anything is written, like DynamoDB does.
+    async fn batch_write(&self, query: BatchWriteQuery) -> Result<BatchWriteOutput, DynamoDbError> {
+        let mut tables = self.lock();
+
+        let mut targets: Vec<(&str, Keys)> = vec![];
+        for (table_name, request) in query.requests() {
+            let table = table(&tables, table_name)?;
+            let put_item = request.put_request().and_then(|put_request| put_request.item());
+            let delete_keys = request.delete_request().and_then(|delete_request| delete_request.key());
+
+            let keys = match (put_item, delete_keys) {
+                (Some(item), _) => table.keys_of(item)?,
+                (None, Some(keys)) => {
+                    table.validate_keys(keys)?;
+                    keys.clone()
+                }
+                (None, None) => continue,
+            };
+
+            if targets
+                .iter()
+                .any(|(other_table_name, other)| other_table_name == table_name && same_keys(other, &keys))
+            {
+                return Err(DynamoDbError::ValidationError(
+                    "Provided list of item keys contains duplicates".to_string(),
+                ));
+            }
+            targets.push((table_name, keys));
+        }
+
+        for (table_name, request) in query.requests() {
+            let table = table_mut(&mut tables, table_name)?;
+
+            if let Some(item) = request.put_request().and_then(|put_request| put_request.item()) {
+                table.put(&PutItemQuery::new(table_name, item.clone(), None, None::<String>))?;
+            }
+
+            if let Some(keys) = request.delete_request().and_then(|delete_request| delete_request.key()) {
+                table.delete(&DeleteItemQuery::new(table_name, keys.clone()))?;
+            }
+        }
+
+        Ok(BatchWriteOutput::new(query.len(), vec![]))
+    }
+
+    async fn batch_get(&self, query: BatchGetQuery) -> Result<BatchGetOutput, DynamoDbError> {
+        let tables = self.lock();
+        let mut items = HashMap::<String, Vec<Items>>::new();
+
+        for (table_name, keys) in query.keys() {
+            if let Some(item) = table(&tables, table_name)?.find(keys)? {
+                items.entry(table_name.clone()).or_default().push(item.clone());
+            }
+        }
+
+        Ok(BatchGetOutput::new(items, vec![]))
+    }
+
+    fn query(&self, query: QueryQuery) -> ItemStream {
+        let items = table(&self.lock(), query.table_name()).and_then(|table| table.query(&query));
+
+        item_stream(items)
+    }
+
+    fn scan(&self, query: ScanQuery) -> ItemStream {
+        let items = table(&self.lock(), query.table_name()).and_then(|table| table.scan(&query));
+
+        item_stream(items)
+    }
+
+    /// Segments are read one after another, without rate limiting.
+    fn parallel_scan(&self, query: ParallelScanQuery) -> ScanPageStream {
+        let tables = self.lock();
+
+        let pages = query
+            .segments()
+            .iter()
+            .map(|(scan, exclusive_start_key)| {
+                table(&tables, scan.table_name())?.scan_pages(scan, exclusive_start_key)
+            })
+            .collect::<Result<Vec<_>, DynamoDbError>>();
+
+        match pages {
+            Ok(pages) => Box::pin(stream::iter(pages.into_iter().flatten().map(Ok))),
+            Err(error) => Box::pin(stream::once(async { Err(error) })),
+        }
+    }
+}
+
+fn item_stream(items: Result<Vec<Items>, DynamoDbError>) -> ItemStream {
+    match items {
+        Ok(items) => Box::pin(stream::iter(items.into_iter().map(Ok))),
+        Err(error) => Box::pin(stream::once(async { Err(error) })),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use aws_sdk_dynamodb::model::{AttributeValue, ReturnValue};
+    use futures_util::TryStreamExt;
+    use pretty_assertions::assert_eq;
+
+    use crate::api::DynamoDbApi;
+    use crate::client::ExistsTableResultType;
+    use crate::error::DynamoDbError;
+    use crate::in_memory::InMemoryDynamoDb;
+    use crate::query::batch_write::BatchWriteQuery;
+    use crate::query::create_table::CreateTableQuery;
+    use crate::query::delete_item::DeleteItemQuery;
+    use crate::query::delete_table::DeleteTableQuery;
+    use crate::query::describe_table::DescribeTableQuery;
+    use crate::query::dynamodb_query::Keys;
+    use crate::query::expression::{Condition, UpdateExpression};
+    use crate::query::get_item::{GetItemQuery, Key};
+    use crate::query::list_tables::ListTablesQuery;
+    use crate::query::parallel_scan::{ParallelScanQuery, ScanCheckpoints, ScanPage, SegmentCheckpoint};
+    use crate::query::put_item::{Items, PutItemQuery};
+    use crate::query::query::QueryQuery;
+    use crate::query::scan::ScanQuery;
+    use crate::query::transact_write::{CancellationReasonCode, ConditionCheck, TransactWriteQuery};
+    use crate::query::update_item::UpdateItemQuery;
+    use crate::query::update_table::UpdateTableQuery;
+    use crate::stream::StreamEventName;
+
+    fn s(value: &str) -> AttributeValue {
+        AttributeValue::S(value.to_string())
+    }
+
+    fn n(value: &str) -> AttributeValue {
+        AttributeValue::N(value.to_string())
+    }
+
+    fn message(channel_id: &str, posted_at: &str, text: &str) -> Items {
+        Items::from([
+            ("channel_id".to_string(), s(channel_id)),
+            ("posted_at".to_string(), n(posted_at)),
+            ("text".to_string(), s(text)),
+        ])
+    }
+
+    fn messages_table_query() -> CreateTableQuery {
+        serde_json::from_str::<CreateTableQuery>(
+            r#"{
+                "TableName": "Messages",
+                "KeySchema": [
+                    {"AttributeName": "channel_id", "KeyType": "HASH"},
+                    {"AttributeName": "posted_at", "KeyType": "RANGE"}
+                ],
+                "AttributeDefinitions": [
+                    {"AttributeName": "channel_id", "AttributeType": "S"},
+                    {"AttributeName": "posted_at", "AttributeType": "N"}
+                ],
+                "BillingMode": "PAY_PER_REQUEST",
+                "StreamSpecification": {"StreamEnabled": true, "StreamViewType": "NEW_AND_OLD_IMAGES"}
+            }"#,
+        )
+        .unwrap()
+    }
+
+    async fn messages_table() -> InMemoryDynamoDb {
+        let dynamodb = InMemoryDynamoDb::new();
+        dynamodb.create_table("Messages", &messages_table_query()).await.unwrap();
+
+        dynamodb
+    }
+
+    fn message_keys(channel_id: &str, posted_at: &str) -> Keys {
+        Keys::from([
+            ("channel_id".to_string(), s(channel_id)),
+            ("posted_at".to_string(), n(posted_at)),
+        ])
+    }
+
+    #[tokio::test]
+    async fn test_table_lifecycle() {
+        let dynamodb = messages_table().await;
+
+        assert!(matches!(
+            dynamodb.create_table("Messages", &messages_table_query()).await,
+            Err(DynamoDbError::ResourceInUse(_))
+        ));
+        assert_eq!(
+            ExistsTableResultType::Found,
+            dynamodb.exists_table("Messages").await.unwrap()
+        );
+
+        let description = dynamodb
+            .wait_until_active("Messages", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(description.is_active());
+        assert_eq!(2, description.key_schemas().len());
+
+        let table_names = dynamodb
+            .list_all_tables(ListTablesQuery::new().with_limit(1))
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(vec!["Messages".to_string()], table_names);
+
+        dynamodb.delete_table(&DeleteTableQuery::new("Messages")).await.unwrap();
+
+        dynamodb
+            .wait_until_deleted("Messages", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert!(matches!(
+            dynamodb.describe_table(&DescribeTableQuery::new("Messages")).await,
+            Err(DynamoDbError::ResourceNotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_update_table() {
+        let dynamodb = messages_table().await;
+
+        let query = serde_json::from_str::<UpdateTableQuery>(
+            r#"{
+                "TableName": "Messages",
+                "AttributeDefinitions": [{"AttributeName": "text", "AttributeType": "S"}],
+                "StreamSpecification": {"StreamEnabled": false},
+                "GlobalSecondaryIndexUpdates": [
+                    {"Create": {
+                        "IndexName": "text-index",
+                        "KeySchema": [{"AttributeName": "text", "KeyType": "HASH"}],
+                        "Projection": {"ProjectionType": "ALL"}
+                    }},
+                    {"Delete": {"IndexName": "missing-index"}}
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        assert!(matches!(
+            dynamodb.update_table(&query).await,
+            Err(DynamoDbError::ValidationError(_))
+        ));
+
+        let mut steps = query.into_steps();
+        let delete_missing_index = steps.pop().unwrap();
+        for step in &steps {
+            dynamodb.update_table(step).await.unwrap();
+        }
+        assert!(matches!(
+            dynamodb.update_table(&delete_missing_index).await,
+            Err(DynamoDbError::ResourceNotFound(_))
+        ));
+
+        let description = dynamodb
+            .wait_until_active("Messages", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(
+            vec!["text-index"],
+            description
+                .global_secondary_indexes()
+                .iter()
+                .map(|index| index.index_name())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(3, description.attribute_definitions().len());
+        assert!(!description.stream_specification().as_ref().unwrap().stream_enabled());
+
+        dynamodb
+            .put_item(PutItemQuery::new("Messages", message("general", "1", "hello"), None, None::<String>))
+            .await
+            .unwrap();
+        assert!(dynamodb.stream_records("Messages").is_empty());
+
+        let items = dynamodb
+            .query(
+                QueryQuery::new("Messages", "#text = :text")
+                    .with_index_name("text-index")
+                    .with_expression_attribute_name("#text", "text")
+                    .with_expression_attribute_value(":text", s("hello")),
+            )
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(1, items.len());
+    }
+
+    #[tokio::test]
+    async fn test_put_with_condition() {
+        let dynamodb = messages_table().await;
+        let put = || {
+            PutItemQuery::new("Messages", message("general", "1", "hello"), None, None::<String>)
+                .with_condition(Condition::attribute_not_exists("channel_id"))
+        };
+
+        dynamodb.put_item(put()).await.unwrap();
+
+        assert!(matches!(
+            dynamodb.put_item(put()).await,
+            Err(DynamoDbError::ConditionalCheckFailed(_))
+        ));
+        assert!(matches!(
+            dynamodb
+                .put_item(PutItemQuery::new("Messages", Items::new(), None, None::<String>))
+                .await,
+            Err(DynamoDbError::ValidationError(_))
+        ));
+        assert_eq!(1, dynamodb.items("Messages").len());
+    }
+
+    #[tokio::test]
+    async fn test_versioned_put_and_update() {
+        let dynamodb = messages_table().await;
+
+        assert_eq!(
+            1,
+            dynamodb
+                .put_versioned("Messages", message("general", "1", "hello"), None)
+                .await
+                .unwrap()
+        );
+        assert!(matches!(
+            dynamodb
+                .put_versioned("Messages", message("general", "1", "again"), None)
+                .await,
+            Err(DynamoDbError::VersionConflict {
+                expected_version: None,
+                ..
+            })
+        ));
+
+        let update = || UpdateExpression::new().set("text", s("edited"));
+        assert_eq!(
+            2,
+            dynamodb
+                .update_versioned("Messages", message_keys("general", "1"), update(), 1)
+                .await
+                .unwrap()
+        );
+        assert!(matches!(
+            dynamodb
+                .update_versioned("Messages", message_keys("general", "1"), update(), 1)
+                .await,
+            Err(DynamoDbError::VersionConflict {
+                expected_version: Some(1),
+                ..
+            })
+        ));
+        assert_eq!(
+            3,
+            dynamodb
+                .put_versioned("Messages", message("general", "1", "replaced"), Some(2))
+                .await
+                .unwrap()
+        );
+
+        let items = dynamodb.items("Messages");
+        assert_eq!(Some(&n("3")), items[0].get("version"));
+        assert_eq!(Some(&s("replaced")), items[0].get("text"));
+    }
+
+    #[tokio::test]
+    async fn test_versioned_update_rejects_invalid_versions() {
+        let dynamodb = messages_table().await;
+        dynamodb
+            .put_versioned("Messages", message("general", "1", "hello"), None)
+            .await
+            .unwrap();
+
+        let touches_version = UpdateExpression::new()
+            .set("text", s("edited"))
+            .add("version", n("1"));
+        assert!(matches!(
+            dynamodb
+                .update_versioned("Messages", message_keys("general", "1"), touches_version, 1)
+                .await,
+            Err(DynamoDbError::ValidationError(_))
+        ));
+
+        let update = UpdateExpression::new().set("text", s("edited"));
+        assert!(matches!(
+            dynamodb
+                .update_versioned("Messages", message_keys("general", "1"), update, u64::MAX)
+                .await,
+            Err(DynamoDbError::ValidationError(_))
+        ));
+        assert!(matches!(
+            dynamodb
+                .put_versioned("Messages", message("general", "1", "replaced"), Some(u64::MAX))
+                .await,
+            Err(DynamoDbError::ValidationError(_))
+        ));
+
+        let items = dynamodb.items("Messages");
+        assert_eq!(Some(&n("1")), items[0].get("version"));
+        assert_eq!(Some(&s("hello")), items[0].get("text"));
+    }
+
+    #[tokio::test]
+    async fn test_update_and_delete_emit_stream_records() {
+        let dynamodb = messages_table().await;
+
+        dynamodb
+            .put_item(PutItemQuery::new("Messages", message("general", "1", "hello"), None, None::<String>))
+            .await
+            .unwrap();
+
+        let output = dynamodb
+            .update_item(
+                UpdateItemQuery::new(
+                    "Messages",
+                    message_keys("general", "1"),
+                    UpdateExpression::new().set("text", s("edited")),
+                )
+                .with_condition(Condition::eq("text", s("hello")))
+                .with_return_values(ReturnValue::UpdatedNew),
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            Some(Items::from([("text".to_string(), s("edited"))])),
+            output.attributes
+        );
+
+        let output = dynamodb
+            .delete_item(
+                DeleteItemQuery::new("Messages", message_keys("general", "1"))
+                    .with_return_values(ReturnValue::AllOld),
+            )
+            .await
+            .unwrap();
+        assert_eq!(Some(message("general", "1", "edited")), output.attributes);
+
+        let records = dynamodb.stream_records("Messages");
+        assert_eq!(
+            vec![StreamEventName::Insert, StreamEventName::Modify, StreamEventName::Remove],
+            records.iter().map(|record| record.event_name()).collect::<Vec<_>>()
+        );
+        assert_eq!(&Some(message("general", "1", "hello")), records[1].old_image());
+        assert_eq!(&None, records[2].new_image());
+    }
+
+    #[tokio::test]
+    async fn test_query_and_scan() {
+        let dynamodb = messages_table().await;
+
+        for (channel_id, posted_at) in [("general", "10"), ("general", "2"), ("random", "1"), ("general", "7")] {
+            dynamodb
+                .put_item(PutItemQuery::new("Messages", message(channel_id, posted_at, "hi"), None, None::<String>))
+                .await
+                .unwrap();
+        }
+
+        let items = dynamodb
+            .query(
+                QueryQuery::new("Messages", "channel_id = :channel_id AND posted_at >= :from")
+                    .with_expression_attribute_value(":channel_id", s("general"))
+                    .with_expression_attribute_value(":from", n("3"))
+                    .with_scan_index_forward(false),
+            )
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(vec![message("general", "10", "hi"), message("general", "7", "hi")], items);
+
+        let items = dynamodb
+            .scan(
+                ScanQuery::new("Messages")
+                    .with_filter_expression("channel_id <> :channel_id")
+                    .with_expression_attribute_value(":channel_id", s("general")),
+            )
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(vec![message("random", "1", "hi")], items);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_scan_resumes_from_checkpoints() {
+        let dynamodb = messages_table().await;
+        for posted_at in 1..=7 {
+            let text = format!("message {}", posted_at);
+            dynamodb
+                .put_item(PutItemQuery::new(
+                    "Messages",
+                    message("general", &posted_at.to_string(), &text),
+                    None,
+                    None::<String>,
+                ))
+                .await
+                .unwrap();
+        }
+
+        let query = ParallelScanQuery::new(ScanQuery::new("Messages").with_limit(2), 3);
+        let pages = dynamodb
+            .parallel_scan(query.clone())
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(7, pages.iter().map(|page| page.items().len()).sum::<usize>());
+        assert_eq!(
+            3,
+            pages
+                .iter()
+                .filter(|page| page.checkpoint() == &SegmentCheckpoint::Completed)
+                .count()
+        );
+
+        let first_page = pages
+            .iter()
+            .find(|page| matches!(page.checkpoint(), SegmentCheckpoint::InProgress(_)))
+            .unwrap();
+        let completed_segment = (first_page.segment() + 1) % 3;
+        let checkpoints = ScanCheckpoints::from([
+            (first_page.segment(), first_page.checkpoint().clone()),
+            (completed_segment, SegmentCheckpoint::Completed),
+        ]);
+
+        let resumed = dynamodb
+            .parallel_scan(query.clone().with_checkpoints(checkpoints.clone()))
+            .map_ok(ScanPage::into_items)
+            .try_concat()
+            .await
+            .unwrap();
+        let expected = pages
+            .iter()
+            .filter(|page| page.segment() != completed_segment && page != &first_page)
+            .flat_map(|page| page.items().clone())
+            .collect::<Vec<_>>();
+        assert_eq!(expected.len(), resumed.len());
+        assert_eq!(expected, resumed);
+
+        let SegmentCheckpoint::InProgress(checkpoint_keys) = first_page.checkpoint() else {
+            unreachable!()
+        };
+        dynamodb
+            .delete_item(DeleteItemQuery::new("Messages", checkpoint_keys.clone()))
+            .await
+            .unwrap();
+
+        let resumed_after_delete = dynamodb
+            .parallel_scan(query.with_checkpoints(checkpoints))
+            .map_ok(ScanPage::into_items)
+            .try_concat()
+            .await
+            .unwrap();
+        assert_eq!(expected, resumed_after_delete);
+    }
+
+    #[tokio::test]
+    async fn test_transact_write_is_atomic() {
+        let dynamodb = messages_table().await;
+
+        let result = dynamodb
+            .transact_write(
+                TransactWriteQuery::new()
+                    .put(PutItemQuery::new("Messages", message("general", "1", "hello"), None, None::<String>))
+                    .delete(
+                        DeleteItemQuery::new("Messages", message_keys("general", "2"))
+                            .with_condition(Condition::attribute_exists("channel_id")),
+                    ),
+            )
+            .await;
+
+        match result {
+            Err(DynamoDbError::TransactionCanceled(reasons)) => {
+                assert_eq!(&CancellationReasonCode::None, reasons[0].code());
+                assert_eq!(&CancellationReasonCode::ConditionalCheckFailed, reasons[1].code());
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        let output = dynamodb
+            .get_item(&GetItemQuery::new("Messages", Key::new("channel_id", s("general")), false))
+            .await;
+        assert!(matches!(output, Err(DynamoDbError::ValidationError(_))));
+        assert!(dynamodb.items("Messages").is_empty());
+        assert!(dynamodb.stream_records("Messages").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transact_write_checks_conditions_before_the_transaction() {
+        let dynamodb = messages_table().await;
+        dynamodb
+            .put_item(PutItemQuery::new("Messages", message("general", "1", "hello"), None, None::<String>))
+            .await
+            .unwrap();
+
+        let result = dynamodb
+            .transact_write(
+                TransactWriteQuery::new()
+                    .update(
+                        UpdateItemQuery::new(
+                            "Messages",
+                            message_keys("general", "1"),
+                            UpdateExpression::new().set("text", s("edited")),
+                        )
+                        .with_condition(Condition::eq("text", s("hello"))),
+                    )
+                    .condition_check(ConditionCheck::new(
+                        "Messages",
+                        message_keys("general", "2"),
+                        Condition::attribute_not_exists("channel_id"),
+                    )),
+            )
+            .await;
+
+        assert!(result.is_ok());
+        assert_eq!(vec![message("general", "1", "edited")], dynamodb.items("Messages"));
+    }
+
+    #[tokio::test]
+    async fn test_transact_write_rejects_several_actions_on_one_item() {
+        let dynamodb = messages_table().await;
+
+        let result = dynamodb
+            .transact_write(
+                TransactWriteQuery::new()
+                    .put(PutItemQuery::new("Messages", message("general", "1", "hello"), None, None::<String>))
+                    .condition_check(ConditionCheck::new(
+                        "Messages",
+                        message_keys("general", "1"),
+                        Condition::attribute_exists("channel_id"),
+                    )),
+            )
+            .await;
+
+        assert!(matches!(result, Err(DynamoDbError::ValidationError(_))));
+        assert!(dynamodb.items("Messages").is_empty());
+        assert!(dynamodb.stream_records("Messages").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_batch_write_validates_the_whole_batch_first() {
+        let dynamodb = messages_table().await;
+
+        let missing_key = Items::from([("channel_id".to_string(), s("general"))]);
+        let invalid = BatchWriteQuery::new()
+            .put("Messages", message("general", "1", "hello"))
+            .put("Messages", missing_key);
+        assert!(matches!(dynamodb.batch_write(invalid).await, Err(DynamoDbError::ValidationError(_))));
+
+        let duplicates = BatchWriteQuery::new()
+            .put("Messages", message("general", "1", "hello"))
+            .delete("Messages", message_keys("general", "1"));
+        assert!(matches!(dynamodb.batch_write(duplicates).await, Err(DynamoDbError::ValidationError(_))));
+
+        assert!(dynamodb.items("Messages").is_empty());
+        assert!(dynamodb.stream_records("Messages").is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_transact_write_returns_old_items_on_condition_failure() {
+        let dynamodb = messages_table().await;
+        let existing = message("general", "1", "hello");
+        dynamodb
+            .put_item(PutItemQuery::new("Messages", existing.clone(), None, None::<String>))
+            .await
+            .unwrap();
+
+        let transaction = TransactWriteQuery::new().put(PutItemQuery::new(
+            "Messages",
+            message("general", "1", "bye"),
+            None,
+            Some("attribute_not_exists(channel_id)"),
+        ));
+
+        match dynamodb.transact_write(transaction.clone()).await {
+            Err(DynamoDbError::TransactionCanceled(reasons)) => assert_eq!(&None, reasons[0].item()),
+            result => panic!("unexpected result: {:?}", result),
+        }
+
+        match dynamodb.transact_write(transaction.with_old_items_on_condition_failure()).await {
+            Err(DynamoDbError::TransactionCanceled(reasons)) => {
+                assert_eq!(&CancellationReasonCode::ConditionalCheckFailed, reasons[0].code());
+                assert_eq!(&Some(existing), reasons[0].item());
+            }
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
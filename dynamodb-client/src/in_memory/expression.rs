@@ -0,0 +1,1050 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+
+use aws_sdk_dynamodb::model::AttributeValue;
+
+use crate::in_memory::reserved_words::is_reserved_word;
+use crate::query::dynamodb_query::{ExpressionAttributeNames, ExpressionAttributeValues};
+use crate::query::put_item::Items;
+
+/// Evaluates a condition, filter or key condition expression against `item`.
+pub(crate) fn evaluate_condition(
+    expression: &str,
+    names: &ExpressionAttributeNames,
+    values: &ExpressionAttributeValues,
+    item: &Items,
+) -> Result<bool, String> {
+    let mut parser = Parser::new(expression, names, values)?;
+    let condition = parser.parse_condition()?;
+    parser.expect_end()?;
+
+    condition.evaluate(item)
+}
+
+/// Applies an update expression to `item` and returns the top-level attributes it touched.
+/// Every operand is read from the item as it was before the update, as DynamoDB does.
+pub(crate) fn apply_update(
+    expression: &str,
+    names: &ExpressionAttributeNames,
+    values: &ExpressionAttributeValues,
+    item: &mut Items,
+) -> Result<Vec<String>, String> {
+    let mut parser = Parser::new(expression, names, values)?;
+    let actions = parser.parse_update()?;
+    parser.expect_end()?;
+
+    let original = item.clone();
+    let mut touched = vec![];
+
+    for (path, value) in &actions.set {
+        let value = value.evaluate(&original)?;
+        set_path(item, path, value)?;
+        touched.push(path.root().to_string());
+    }
+
+    for path in &actions.remove {
+        remove_path(item, path);
+        touched.push(path.root().to_string());
+    }
+
+    for (path, value) in &actions.add {
+        let current = get_path(&original, path).cloned();
+        set_path(item, path, add(current, value.clone())?)?;
+        touched.push(path.root().to_string());
+    }
+
+    for (path, value) in &actions.delete {
+        match get_path(&original, path).cloned() {
+            Some(current) => match delete(current, value)? {
+                Some(remaining) => set_path(item, path, remaining)?,
+                None => remove_path(item, path),
+            },
+            None => continue,
+        }
+        touched.push(path.root().to_string());
+    }
+
+    Ok(touched)
+}
+
+/// Ordering used for sort keys: numbers numerically, strings and binaries byte-wise.
+pub(crate) fn compare_attribute_values(left: &AttributeValue, right: &AttributeValue) -> Option<Ordering> {
+    match (left, right) {
+        (AttributeValue::S(left), AttributeValue::S(right)) => Some(left.cmp(right)),
+        (AttributeValue::N(left), AttributeValue::N(right)) => {
+            left.parse::<f64>().ok()?.partial_cmp(&right.parse::<f64>().ok()?)
+        }
+        (AttributeValue::B(left), AttributeValue::B(right)) => Some(left.as_ref().cmp(right.as_ref())),
+        _ => None,
+    }
+}
+
+pub(crate) fn attribute_equal(left: &AttributeValue, right: &AttributeValue) -> bool {
+    match (left, right) {
+        (AttributeValue::N(_), AttributeValue::N(_)) => {
+            compare_attribute_values(left, right) == Some(Ordering::Equal)
+        }
+        (AttributeValue::Ss(left), AttributeValue::Ss(right)) => {
+            left.iter().collect::<HashSet<_>>() == right.iter().collect::<HashSet<_>>()
+        }
+        (AttributeValue::Ns(left), AttributeValue::Ns(right)) => {
+            left.len() == right.len() && left.iter().all(|number| contains_number(right, number))
+        }
+        (AttributeValue::Bs(left), AttributeValue::Bs(right)) => {
+            left.len() == right.len() && left.iter().all(|binary| right.contains(binary))
+        }
+        (AttributeValue::L(left), AttributeValue::L(right)) => {
+            left.len() == right.len()
+                && left.iter().zip(right).all(|(left, right)| attribute_equal(left, right))
+        }
+        (AttributeValue::M(left), AttributeValue::M(right)) => {
+            left.len() == right.len()
+                && left.iter().all(|(name, value)| {
+                    right.get(name).map(|other| attribute_equal(value, other)) == Some(true)
+                })
+        }
+        _ => left == right,
+    }
+}
+
+fn contains_number(numbers: &[String], number: &str) -> bool {
+    numbers.iter().any(|candidate| {
+        attribute_equal(
+            &AttributeValue::N(candidate.clone()),
+            &AttributeValue::N(number.to_string()),
+        )
+    })
+}
+
+fn type_name(value: &AttributeValue) -> &'static str {
+    match value {
+        AttributeValue::S(_) => "S",
+        AttributeValue::N(_) => "N",
+        AttributeValue::B(_) => "B",
+        AttributeValue::Bool(_) => "BOOL",
+        AttributeValue::Null(_) => "NULL",
+        AttributeValue::L(_) => "L",
+        AttributeValue::M(_) => "M",
+        AttributeValue::Ss(_) => "SS",
+        AttributeValue::Ns(_) => "NS",
+        AttributeValue::Bs(_) => "BS",
+        _ => "UNKNOWN",
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Identifier(String),
+    Name(String),
+    Value(String),
+    Number(usize),
+    LeftParen,
+    RightParen,
+    LeftBracket,
+    RightBracket,
+    Comma,
+    Dot,
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Plus,
+    Minus,
+}
+
+fn tokenize(expression: &str) -> Result<Vec<Token>, String> {
+    let chars = expression.chars().collect::<Vec<_>>();
+    let mut tokens = vec![];
+    let mut position = 0;
+
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+
+    while position < chars.len() {
+        let c = chars[position];
+
+        let word_end = |start: usize| {
+            let mut end = start;
+            while end < chars.len() && is_word(chars[end]) {
+                end += 1;
+            }
+            end
+        };
+
+        let token = match c {
+            c if c.is_whitespace() => {
+                position += 1;
+                continue;
+            }
+            '#' | ':' => {
+                let end = word_end(position + 1);
+                if end == position + 1 {
+                    return Err(format!("Invalid placeholder at position {}.", position));
+                }
+                let word = chars[position..end].iter().collect::<String>();
+                position = end;
+
+                if c == '#' {
+                    Token::Name(word)
+                } else {
+                    Token::Value(word)
+                }
+            }
+            c if c.is_ascii_digit() => {
+                let end = word_end(position);
+                let word = chars[position..end].iter().collect::<String>();
+                position = end;
+
+                Token::Number(
+                    word.parse()
+                        .map_err(|_| format!("Invalid list index {}.", word))?,
+                )
+            }
+            c if is_word(c) => {
+                let end = word_end(position);
+                let word = chars[position..end].iter().collect::<String>();
+                position = end;
+
+                Token::Identifier(word)
+            }
+            _ => {
+                let next = chars.get(position + 1).copied();
+                let (token, length) = match (c, next) {
+                    ('<', Some('>')) => (Token::Ne, 2),
+                    ('<', Some('=')) => (Token::Le, 2),
+                    ('>', Some('=')) => (Token::Ge, 2),
+                    ('<', _) => (Token::Lt, 1),
+                    ('>', _) => (Token::Gt, 1),
+                    ('=', _) => (Token::Eq, 1),
+                    ('(', _) => (Token::LeftParen, 1),
+                    (')', _) => (Token::RightParen, 1),
+                    ('[', _) => (Token::LeftBracket, 1),
+                    (']', _) => (Token::RightBracket, 1),
+                    (',', _) => (Token::Comma, 1),
+                    ('.', _) => (Token::Dot, 1),
+                    ('+', _) => (Token::Plus, 1),
+                    ('-', _) => (Token::Minus, 1),
+                    _ => return Err(format!("Unexpected character {:?} at position {}.", c, position)),
+                };
+                position += length;
+
+                token
+            }
+        };
+
+        tokens.push(token);
+    }
+
+    Ok(tokens)
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum PathElement {
+    Attribute(String),
+    Index(usize),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Path {
+    elements: Vec<PathElement>,
+}
+
+impl Path {
+    fn root(&self) -> &str {
+        match &self.elements[0] {
+            PathElement::Attribute(name) => name,
+            PathElement::Index(_) => "",
+        }
+    }
+}
+
+fn get_path<'a>(item: &'a Items, path: &Path) -> Option<&'a AttributeValue> {
+    let mut elements = path.elements.iter();
+
+    let mut current = match elements.next()? {
+        PathElement::Attribute(name) => item.get(name)?,
+        PathElement::Index(_) => return None,
+    };
+
+    for element in elements {
+        current = match (element, current) {
+            (PathElement::Attribute(name), AttributeValue::M(map)) => map.get(name)?,
+            (PathElement::Index(index), AttributeValue::L(list)) => list.get(*index)?,
+            _ => return None,
+        };
+    }
+
+    Some(current)
+}
+
+fn set_path(item: &mut Items, path: &Path, value: AttributeValue) -> Result<(), String> {
+    let invalid = || "The document path provided in the update expression is invalid for update.".to_string();
+
+    let (last, parents) = path.elements.split_last().ok_or_else(invalid)?;
+
+    if parents.is_empty() {
+        return match last {
+            PathElement::Attribute(name) => {
+                item.insert(name.clone(), value);
+                Ok(())
+            }
+            PathElement::Index(_) => Err(invalid()),
+        };
+    }
+
+    let mut current = match &parents[0] {
+        PathElement::Attribute(name) => item.get_mut(name).ok_or_else(invalid)?,
+        PathElement::Index(_) => return Err(invalid()),
+    };
+
+    for element in &parents[1..] {
+        current = match (element, current) {
+            (PathElement::Attribute(name), AttributeValue::M(map)) => map.get_mut(name).ok_or_else(invalid)?,
+            (PathElement::Index(index), AttributeValue::L(list)) => list.get_mut(*index).ok_or_else(invalid)?,
+            _ => return Err(invalid()),
+        };
+    }
+
+    match (last, current) {
+        (PathElement::Attribute(name), AttributeValue::M(map)) => {
+            map.insert(name.clone(), value);
+        }
+        (PathElement::Index(index), AttributeValue::L(list)) if *index < list.len() => {
+            list[*index] = value;
+        }
+        (PathElement::Index(_), AttributeValue::L(list)) => list.push(value),
+        _ => return Err(invalid()),
+    }
+
+    Ok(())
+}
+
+fn remove_path(item: &mut Items, path: &Path) {
+    let Some((last, parents)) = path.elements.split_last() else {
+        return;
+    };
+
+    if parents.is_empty() {
+        if let PathElement::Attribute(name) = last {
+            item.remove(name);
+        }
+        return;
+    }
+
+    let mut current = match &parents[0] {
+        PathElement::Attribute(name) => match item.get_mut(name) {
+            Some(value) => value,
+            None => return,
+        },
+        PathElement::Index(_) => return,
+    };
+
+    for element in &parents[1..] {
+        current = match (element, current) {
+            (PathElement::Attribute(name), AttributeValue::M(map)) => match map.get_mut(name) {
+                Some(value) => value,
+                None => return,
+            },
+            (PathElement::Index(index), AttributeValue::L(list)) => match list.get_mut(*index) {
+                Some(value) => value,
+                None => return,
+            },
+            _ => return,
+        };
+    }
+
+    match (last, current) {
+        (PathElement::Attribute(name), AttributeValue::M(map)) => {
+            map.remove(name);
+        }
+        (PathElement::Index(index), AttributeValue::L(list)) if *index < list.len() => {
+            list.remove(*index);
+        }
+        _ => {}
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Path(Path),
+    Value(AttributeValue),
+    Size(Path),
+}
+
+impl Operand {
+    fn evaluate(&self, item: &Items) -> Option<AttributeValue> {
+        match self {
+            Operand::Path(path) => get_path(item, path).cloned(),
+            Operand::Value(value) => Some(value.clone()),
+            Operand::Size(path) => {
+                let size = match get_path(item, path)? {
+                    AttributeValue::S(value) => value.chars().count(),
+                    AttributeValue::B(value) => value.as_ref().len(),
+                    AttributeValue::L(values) => values.len(),
+                    AttributeValue::M(values) => values.len(),
+                    AttributeValue::Ss(values) | AttributeValue::Ns(values) => values.len(),
+                    AttributeValue::Bs(values) => values.len(),
+                    _ => return None,
+                };
+
+                Some(AttributeValue::N(size.to_string()))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone)]
+enum Condition {
+    Compare(Operand, Comparator, Operand),
+    Between(Operand, Operand, Operand),
+    In(Operand, Vec<Operand>),
+    AttributeExists(Path),
+    AttributeNotExists(Path),
+    AttributeType(Path, Operand),
+    BeginsWith(Path, Operand),
+    Contains(Path, Operand),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    fn evaluate(&self, item: &Items) -> Result<bool, String> {
+        let result = match self {
+            Condition::Compare(left, comparator, right) => {
+                match (left.evaluate(item), right.evaluate(item)) {
+                    (Some(left), Some(right)) => match comparator {
+                        Comparator::Eq => attribute_equal(&left, &right),
+                        Comparator::Ne => !attribute_equal(&left, &right),
+                        _ => {
+                            let ordering = compare_attribute_values(&left, &right);
+
+                            match (comparator, ordering) {
+                                (_, None) => false,
+                                (Comparator::Lt, Some(ordering)) => ordering == Ordering::Less,
+                                (Comparator::Le, Some(ordering)) => ordering != Ordering::Greater,
+                                (Comparator::Gt, Some(ordering)) => ordering == Ordering::Greater,
+                                (Comparator::Ge, Some(ordering)) => ordering != Ordering::Less,
+                                (_, Some(_)) => false,
+                            }
+                        }
+                    },
+                    (None, Some(_)) | (Some(_), None) => matches!(comparator, Comparator::Ne),
+                    (None, None) => false,
+                }
+            }
+            Condition::Between(operand, low, high) => {
+                match (operand.evaluate(item), low.evaluate(item), high.evaluate(item)) {
+                    (Some(value), Some(low), Some(high)) => {
+                        matches!(
+                            compare_attribute_values(&value, &low),
+                            Some(Ordering::Greater | Ordering::Equal)
+                        ) && matches!(
+                            compare_attribute_values(&value, &high),
+                            Some(Ordering::Less | Ordering::Equal)
+                        )
+                    }
+                    _ => false,
+                }
+            }
+            Condition::In(operand, candidates) => match operand.evaluate(item) {
+                Some(value) => candidates.iter().any(|candidate| {
+                    candidate
+                        .evaluate(item)
+                        .map(|candidate| attribute_equal(&value, &candidate))
+                        .unwrap_or(false)
+                }),
+                None => false,
+            },
+            Condition::AttributeExists(path) => get_path(item, path).is_some(),
+            Condition::AttributeNotExists(path) => get_path(item, path).is_none(),
+            Condition::AttributeType(path, expected) => match (get_path(item, path), expected.evaluate(item)) {
+                (Some(value), Some(AttributeValue::S(expected))) => type_name(value) == expected,
+                (_, Some(AttributeValue::S(_))) => false,
+                _ => return Err("attribute_type expects a string operand.".to_string()),
+            },
+            Condition::BeginsWith(path, prefix) => match (get_path(item, path), prefix.evaluate(item)) {
+                (Some(AttributeValue::S(value)), Some(AttributeValue::S(prefix))) => value.starts_with(&prefix),
+                (Some(AttributeValue::B(value)), Some(AttributeValue::B(prefix))) => {
+                    value.as_ref().starts_with(prefix.as_ref())
+                }
+                _ => false,
+            },
+            Condition::Contains(path, operand) => match (get_path(item, path), operand.evaluate(item)) {
+                (Some(AttributeValue::S(value)), Some(AttributeValue::S(operand))) => value.contains(&operand),
+                (Some(AttributeValue::Ss(values)), Some(AttributeValue::S(operand))) => values.contains(&operand),
+                (Some(AttributeValue::Ns(values)), Some(AttributeValue::N(operand))) => contains_number(values, &operand),
+                (Some(AttributeValue::Bs(values)), Some(AttributeValue::B(operand))) => values.contains(&operand),
+                (Some(AttributeValue::L(values)), Some(operand)) => {
+                    values.iter().any(|value| attribute_equal(value, &operand))
+                }
+                _ => false,
+            },
+            Condition::And(left, right) => left.evaluate(item)? && right.evaluate(item)?,
+            Condition::Or(left, right) => left.evaluate(item)? || right.evaluate(item)?,
+            Condition::Not(condition) => !condition.evaluate(item)?,
+        };
+
+        Ok(result)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SetOperand {
+    Path(Path),
+    Value(AttributeValue),
+    IfNotExists(Path, Box<SetOperand>),
+    ListAppend(Box<SetOperand>, Box<SetOperand>),
+}
+
+impl SetOperand {
+    fn evaluate(&self, item: &Items) -> Result<AttributeValue, String> {
+        match self {
+            SetOperand::Path(path) => get_path(item, path)
+                .cloned()
+                .ok_or_else(|| "The provided expression refers to an attribute that does not exist in the item.".to_string()),
+            SetOperand::Value(value) => Ok(value.clone()),
+            SetOperand::IfNotExists(path, fallback) => match get_path(item, path) {
+                Some(value) => Ok(value.clone()),
+                None => fallback.evaluate(item),
+            },
+            SetOperand::ListAppend(left, right) => match (left.evaluate(item)?, right.evaluate(item)?) {
+                (AttributeValue::L(mut left), AttributeValue::L(right)) => {
+                    left.extend(right);
+                    Ok(AttributeValue::L(left))
+                }
+                _ => Err("list_append expects two lists.".to_string()),
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SetValue {
+    Operand(SetOperand),
+    Plus(SetOperand, SetOperand),
+    Minus(SetOperand, SetOperand),
+}
+
+impl SetValue {
+    fn evaluate(&self, item: &Items) -> Result<AttributeValue, String> {
+        match self {
+            SetValue::Operand(operand) => operand.evaluate(item),
+            SetValue::Plus(left, right) => arithmetic(left.evaluate(item)?, right.evaluate(item)?, false),
+            SetValue::Minus(left, right) => arithmetic(left.evaluate(item)?, right.evaluate(item)?, true),
+        }
+    }
+}
+
+fn arithmetic(left: AttributeValue, right: AttributeValue, subtract: bool) -> Result<AttributeValue, String> {
+    let (AttributeValue::N(left), AttributeValue::N(right)) = (left, right) else {
+        return Err("An operand in the update expression has an incorrect data type.".to_string());
+    };
+
+    if let (Ok(left), Ok(right)) = (left.parse::<i128>(), right.parse::<i128>()) {
+        let result = if subtract { left - right } else { left + right };
+        return Ok(AttributeValue::N(result.to_string()));
+    }
+
+    match (left.parse::<f64>(), right.parse::<f64>()) {
+        (Ok(left), Ok(right)) => {
+            let result = if subtract { left - right } else { left + right };
+            Ok(AttributeValue::N(result.to_string()))
+        }
+        _ => Err(format!("Invalid number operands {} and {}.", left, right)),
+    }
+}
+
+fn add(current: Option<AttributeValue>, value: AttributeValue) -> Result<AttributeValue, String> {
+    match (current, value) {
+        (None, value @ (AttributeValue::N(_) | AttributeValue::Ss(_) | AttributeValue::Ns(_) | AttributeValue::Bs(_))) => Ok(value),
+        (Some(current @ AttributeValue::N(_)), value @ AttributeValue::N(_)) => arithmetic(current, value, false),
+        (Some(AttributeValue::Ss(mut current)), AttributeValue::Ss(values)) => {
+            for value in values {
+                if !current.contains(&value) {
+                    current.push(value);
+                }
+            }
+            Ok(AttributeValue::Ss(current))
+        }
+        (Some(AttributeValue::Ns(mut current)), AttributeValue::Ns(values)) => {
+            for value in values {
+                if !contains_number(&current, &value) {
+                    current.push(value);
+                }
+            }
+            Ok(AttributeValue::Ns(current))
+        }
+        (Some(AttributeValue::Bs(mut current)), AttributeValue::Bs(values)) => {
+            for value in values {
+                if !current.contains(&value) {
+                    current.push(value);
+                }
+            }
+            Ok(AttributeValue::Bs(current))
+        }
+        _ => Err("An operand in the update expression has an incorrect data type.".to_string()),
+    }
+}
+
+/// `None` when the set is empty afterwards, in which case DynamoDB removes the attribute.
+fn delete(current: AttributeValue, value: &AttributeValue) -> Result<Option<AttributeValue>, String> {
+    let remaining = match (current, value) {
+        (AttributeValue::Ss(current), AttributeValue::Ss(values)) => {
+            let remaining = current.into_iter().filter(|value| !values.contains(value)).collect::<Vec<_>>();
+            (!remaining.is_empty()).then_some(AttributeValue::Ss(remaining))
+        }
+        (AttributeValue::Ns(current), AttributeValue::Ns(values)) => {
+            let remaining = current
+                .into_iter()
+                .filter(|value| !contains_number(values, value))
+                .collect::<Vec<_>>();
+            (!remaining.is_empty()).then_some(AttributeValue::Ns(remaining))
+        }
+        (AttributeValue::Bs(current), AttributeValue::Bs(values)) => {
+            let remaining = current.into_iter().filter(|value| !values.contains(value)).collect::<Vec<_>>();
+            (!remaining.is_empty()).then_some(AttributeValue::Bs(remaining))
+        }
+        _ => return Err("An operand in the update expression has an incorrect data type.".to_string()),
+    };
+
+    Ok(remaining)
+}
+
+#[derive(Debug, Default)]
+struct UpdateActions {
+    set: Vec<(Path, SetValue)>,
+    remove: Vec<Path>,
+    add: Vec<(Path, AttributeValue)>,
+    delete: Vec<(Path, AttributeValue)>,
+}
+
+struct Parser<'a> {
+    tokens: Vec<Token>,
+    position: usize,
+    names: &'a ExpressionAttributeNames,
+    values: &'a ExpressionAttributeValues,
+}
+
+impl<'a> Parser<'a> {
+    fn new(
+        expression: &str,
+        names: &'a ExpressionAttributeNames,
+        values: &'a ExpressionAttributeValues,
+    ) -> Result<Self, String> {
+        Ok(Self {
+            tokens: tokenize(expression)?,
+            position: 0,
+            names,
+            values,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<&Token> {
+        self.tokens.get(self.position + offset)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            token => Err(format!("Expected {:?} but found {:?}.", expected, token)),
+        }
+    }
+
+    fn expect_end(&self) -> Result<(), String> {
+        match self.peek() {
+            None => Ok(()),
+            Some(token) => Err(format!("Unexpected token {:?}.", token)),
+        }
+    }
+
+    fn is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Identifier(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn is_function(&self, function: &str) -> bool {
+        matches!(self.peek(), Some(Token::Identifier(word)) if word == function)
+            && self.peek_at(1) == Some(&Token::LeftParen)
+    }
+
+    fn parse_condition(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_and()?;
+
+        while self.is_keyword("OR") {
+            self.next();
+            condition = Condition::Or(Box::new(condition), Box::new(self.parse_and()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_and(&mut self) -> Result<Condition, String> {
+        let mut condition = self.parse_not()?;
+
+        while self.is_keyword("AND") {
+            self.next();
+            condition = Condition::And(Box::new(condition), Box::new(self.parse_not()?));
+        }
+
+        Ok(condition)
+    }
+
+    fn parse_not(&mut self) -> Result<Condition, String> {
+        if self.is_keyword("NOT") {
+            self.next();
+            return Ok(Condition::Not(Box::new(self.parse_not()?)));
+        }
+
+        self.parse_primary()
+    }
+
+    fn parse_primary(&mut self) -> Result<Condition, String> {
+        if self.peek() == Some(&Token::LeftParen) {
+            self.next();
+            let condition = self.parse_condition()?;
+            self.expect(Token::RightParen)?;
+
+            return Ok(condition);
+        }
+
+        for function in ["attribute_exists", "attribute_not_exists", "attribute_type", "begins_with", "contains"] {
+            if self.is_function(function) {
+                self.next();
+                self.expect(Token::LeftParen)?;
+                let path = self.parse_path()?;
+
+                let condition = match function {
+                    "attribute_exists" => Condition::AttributeExists(path),
+                    "attribute_not_exists" => Condition::AttributeNotExists(path),
+                    _ => {
+                        self.expect(Token::Comma)?;
+                        let operand = self.parse_operand()?;
+
+                        match function {
+                            "attribute_type" => Condition::AttributeType(path, operand),
+                            "begins_with" => Condition::BeginsWith(path, operand),
+                            _ => Condition::Contains(path, operand),
+                        }
+                    }
+                };
+                self.expect(Token::RightParen)?;
+
+                return Ok(condition);
+            }
+        }
+
+        let left = self.parse_operand()?;
+
+        if self.is_keyword("BETWEEN") {
+            self.next();
+            let low = self.parse_operand()?;
+            if !self.is_keyword("AND") {
+                return Err("Expected AND in BETWEEN.".to_string());
+            }
+            self.next();
+            let high = self.parse_operand()?;
+
+            return Ok(Condition::Between(left, low, high));
+        }
+
+        if self.is_keyword("IN") {
+            self.next();
+            self.expect(Token::LeftParen)?;
+            let mut candidates = vec![self.parse_operand()?];
+            while self.peek() == Some(&Token::Comma) {
+                self.next();
+                candidates.push(self.parse_operand()?);
+            }
+            self.expect(Token::RightParen)?;
+
+            return Ok(Condition::In(left, candidates));
+        }
+
+        let comparator = match self.next() {
+            Some(Token::Eq) => Comparator::Eq,
+            Some(Token::Ne) => Comparator::Ne,
+            Some(Token::Lt) => Comparator::Lt,
+            Some(Token::Le) => Comparator::Le,
+            Some(Token::Gt) => Comparator::Gt,
+            Some(Token::Ge) => Comparator::Ge,
+            token => return Err(format!("Expected a comparator but found {:?}.", token)),
+        };
+
+        Ok(Condition::Compare(left, comparator, self.parse_operand()?))
+    }
+
+    fn parse_operand(&mut self) -> Result<Operand, String> {
+        if let Some(Token::Value(_)) = self.peek() {
+            return Ok(Operand::Value(self.parse_value()?));
+        }
+
+        if self.is_function("size") {
+            self.next();
+            self.expect(Token::LeftParen)?;
+            let path = self.parse_path()?;
+            self.expect(Token::RightParen)?;
+
+            return Ok(Operand::Size(path));
+        }
+
+        Ok(Operand::Path(self.parse_path()?))
+    }
+
+    fn parse_value(&mut self) -> Result<AttributeValue, String> {
+        match self.next() {
+            Some(Token::Value(placeholder)) => self
+                .values
+                .get(&placeholder)
+                .cloned()
+                .ok_or_else(|| format!("An expression attribute value used in expression is not defined: {}", placeholder)),
+            token => Err(format!("Expected an expression attribute value but found {:?}.", token)),
+        }
+    }
+
+    fn parse_name(&mut self) -> Result<String, String> {
+        match self.next() {
+            Some(Token::Identifier(name)) if is_reserved_word(&name) => Err(format!(
+                "Attribute name is a reserved keyword; reserved keyword: {}",
+                name
+            )),
+            Some(Token::Identifier(name)) => Ok(name),
+            Some(Token::Name(placeholder)) => self
+                .names
+                .get(&placeholder)
+                .cloned()
+                .ok_or_else(|| format!("An expression attribute name used in expression is not defined: {}", placeholder)),
+            token => Err(format!("Expected an attribute name but found {:?}.", token)),
+        }
+    }
+
+    fn parse_path(&mut self) -> Result<Path, String> {
+        let mut elements = vec![PathElement::Attribute(self.parse_name()?)];
+
+        loop {
+            match self.peek() {
+                Some(Token::Dot) => {
+                    self.next();
+                    elements.push(PathElement::Attribute(self.parse_name()?));
+                }
+                Some(Token::LeftBracket) => {
+                    self.next();
+                    match self.next() {
+                        Some(Token::Number(index)) => elements.push(PathElement::Index(index)),
+                        token => return Err(format!("Expected a list index but found {:?}.", token)),
+                    }
+                    self.expect(Token::RightBracket)?;
+                }
+                _ => return Ok(Path { elements }),
+            }
+        }
+    }
+
+    fn parse_update(&mut self) -> Result<UpdateActions, String> {
+        let mut actions = UpdateActions::default();
+
+        while self.peek().is_some() {
+            let clause = match self.next() {
+                Some(Token::Identifier(word)) => word.to_ascii_uppercase(),
+                token => return Err(format!("Expected SET, REMOVE, ADD or DELETE but found {:?}.", token)),
+            };
+
+            loop {
+                match clause.as_str() {
+                    "SET" => {
+                        let path = self.parse_path()?;
+                        self.expect(Token::Eq)?;
+                        let value = self.parse_set_value()?;
+                        actions.set.push((path, value));
+                    }
+                    "REMOVE" => actions.remove.push(self.parse_path()?),
+                    "ADD" => {
+                        let path = self.parse_path()?;
+                        actions.add.push((path, self.parse_value()?));
+                    }
+                    "DELETE" => {
+                        let path = self.parse_path()?;
+                        actions.delete.push((path, self.parse_value()?));
+                    }
+                    _ => return Err(format!("Unknown update clause {}.", clause)),
+                }
+
+                if self.peek() == Some(&Token::Comma) {
+                    self.next();
+                } else {
+                    break;
+                }
+            }
+        }
+
+        Ok(actions)
+    }
+
+    fn parse_set_value(&mut self) -> Result<SetValue, String> {
+        let left = self.parse_set_operand()?;
+
+        match self.peek() {
+            Some(Token::Plus) => {
+                self.next();
+                Ok(SetValue::Plus(left, self.parse_set_operand()?))
+            }
+            Some(Token::Minus) => {
+                self.next();
+                Ok(SetValue::Minus(left, self.parse_set_operand()?))
+            }
+            _ => Ok(SetValue::Operand(left)),
+        }
+    }
+
+    fn parse_set_operand(&mut self) -> Result<SetOperand, String> {
+        if let Some(Token::Value(_)) = self.peek() {
+            return Ok(SetOperand::Value(self.parse_value()?));
+        }
+
+        if self.is_function("if_not_exists") {
+            self.next();
+            self.expect(Token::LeftParen)?;
+            let path = self.parse_path()?;
+            self.expect(Token::Comma)?;
+            let fallback = self.parse_set_operand()?;
+            self.expect(Token::RightParen)?;
+
+            return Ok(SetOperand::IfNotExists(path, Box::new(fallback)));
+        }
+
+        if self.is_function("list_append") {
+            self.next();
+            self.expect(Token::LeftParen)?;
+            let left = self.parse_set_operand()?;
+            self.expect(Token::Comma)?;
+            let right = self.parse_set_operand()?;
+            self.expect(Token::RightParen)?;
+
+            return Ok(SetOperand::ListAppend(Box::new(left), Box::new(right)));
+        }
+
+        Ok(SetOperand::Path(self.parse_path()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use pretty_assertions::assert_eq;
+
+    use crate::in_memory::expression::{apply_update, evaluate_condition};
+    use crate::query::expression::{Condition, UpdateExpression};
+    use crate::query::put_item::Items;
+
+    fn item() -> Items {
+        HashMap::from([
+            ("id".to_string(), AttributeValue::S("1".to_string())),
+            ("count".to_string(), AttributeValue::N("5".to_string())),
+            ("tags".to_string(), AttributeValue::Ss(vec!["a".to_string(), "b".to_string()])),
+            (
+                "profile".to_string(),
+                AttributeValue::M(HashMap::from([(
+                    "name".to_string(),
+                    AttributeValue::S("alice".to_string()),
+                )])),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_evaluate_built_conditions() {
+        let condition = Condition::attribute_exists("id")
+            .and(Condition::gt("count", AttributeValue::N("4".to_string())))
+            .and(Condition::contains("tags", AttributeValue::S("a".to_string())))
+            .and(!Condition::begins_with("id", AttributeValue::S("2".to_string())))
+            .build();
+
+        assert!(evaluate_condition(condition.expression(), condition.names(), condition.values(), &item()).unwrap());
+
+        let condition = Condition::between(
+            "count",
+            AttributeValue::N("6".to_string()),
+            AttributeValue::N("9".to_string()),
+        )
+        .or(Condition::attribute_not_exists("id"))
+        .build();
+
+        assert!(!evaluate_condition(condition.expression(), condition.names(), condition.values(), &item()).unwrap());
+    }
+
+    #[test]
+    fn test_evaluate_raw_conditions() {
+        let values = HashMap::from([
+            (":name".to_string(), AttributeValue::S("alice".to_string())),
+            (":two".to_string(), AttributeValue::N("2".to_string())),
+        ]);
+
+        let names = HashMap::from([("#n".to_string(), "name".to_string())]);
+
+        assert!(evaluate_condition("profile.#n = :name AND size(tags) = :two", &names, &values, &item()).unwrap());
+        assert!(evaluate_condition("profile.#n IN (:name, :two)", &names, &values, &item()).unwrap());
+        assert!(evaluate_condition("tags = :missing", &HashMap::new(), &values, &item()).is_err());
+    }
+
+    #[test]
+    fn test_reject_reserved_words() {
+        let values = HashMap::from([(":name".to_string(), AttributeValue::S("alice".to_string()))]);
+
+        let error = evaluate_condition("profile.name = :name", &HashMap::new(), &values, &item()).unwrap_err();
+        assert_eq!("Attribute name is a reserved keyword; reserved keyword: name", error);
+
+        let mut item = item();
+        assert!(apply_update("SET Count = :name", &HashMap::new(), &values, &mut item).is_err());
+    }
+
+    #[test]
+    fn test_apply_update() {
+        let update = UpdateExpression::new()
+            .set("name", AttributeValue::S("bob".to_string()))
+            .set_if_not_exists("created_at", AttributeValue::S("now".to_string()))
+            .remove("tags")
+            .add("count", AttributeValue::N("2".to_string()))
+            .build();
+
+        let mut item = item();
+        let touched = apply_update(update.expression(), update.names(), update.values(), &mut item).unwrap();
+
+        assert_eq!(Some(&AttributeValue::S("bob".to_string())), item.get("name"));
+        assert_eq!(Some(&AttributeValue::S("now".to_string())), item.get("created_at"));
+        assert_eq!(None, item.get("tags"));
+        assert_eq!(Some(&AttributeValue::N("7".to_string())), item.get("count"));
+        assert_eq!(4, touched.len());
+    }
+
+    #[test]
+    fn test_apply_arithmetic_update() {
+        let values = HashMap::from([(":one".to_string(), AttributeValue::N("1".to_string()))]);
+
+        let mut item = item();
+        apply_update("SET #c = #c - :one", &HashMap::from([("#c".to_string(), "count".to_string())]), &values, &mut item).unwrap();
+
+        assert_eq!(Some(&AttributeValue::N("4".to_string())), item.get("count"));
+    }
+}
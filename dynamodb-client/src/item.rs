@@ -0,0 +1,267 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use aws_sdk_dynamodb::model::AttributeValue;
+use serde::de::{DeserializeOwned, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::query::put_item::Items;
+
+pub mod de;
+pub mod ser;
+
+/// Newtype names the serializer recognises to emit SS, NS and BS instead of L.
+pub(crate) const STRING_SET: &str = "$dynamodb::StringSet";
+pub(crate) const NUMBER_SET: &str = "$dynamodb::NumberSet";
+pub(crate) const BINARY_SET: &str = "$dynamodb::BinarySet";
+
+#[derive(Error, Debug, PartialEq)]
+pub enum ItemError {
+    #[error("Only structs and maps can be stored as an item. Actual value: {0:?}")]
+    NotAnItem(AttributeValue),
+    #[error("Map keys must be strings or numbers. Actual value: {0:?}")]
+    InvalidMapKey(AttributeValue),
+    #[error("DynamoDB does not accept empty sets.")]
+    EmptySet,
+    #[error("Set element does not match the set type. Actual value: {0:?}")]
+    InvalidSetElement(AttributeValue),
+    #[error("Number attribute could not be parsed. Actual value: {0}")]
+    InvalidNumber(String),
+    #[error("Attribute value is not supported. Actual value: {0:?}")]
+    UnsupportedAttributeValue(AttributeValue),
+    #[error("{0}")]
+    Message(String),
+}
+
+impl serde::ser::Error for ItemError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        ItemError::Message(message.to_string())
+    }
+}
+
+impl serde::de::Error for ItemError {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        ItemError::Message(message.to_string())
+    }
+}
+
+/// Serializes a struct or map into the attributes of an item.
+pub fn to_item<T: Serialize + ?Sized>(value: &T) -> Result<Items, ItemError> {
+    match to_attribute_value(value)? {
+        AttributeValue::M(items) => Ok(items),
+        attribute_value => Err(ItemError::NotAnItem(attribute_value)),
+    }
+}
+
+/// Deserializes the attributes of an item, e.g. `GetItemOutput::item`, into `T`.
+pub fn from_item<T: DeserializeOwned>(items: Items) -> Result<T, ItemError> {
+    from_attribute_value(AttributeValue::M(items))
+}
+
+pub fn to_attribute_value<T: Serialize + ?Sized>(value: &T) -> Result<AttributeValue, ItemError> {
+    value.serialize(ser::Serializer)
+}
+
+pub fn from_attribute_value<T: DeserializeOwned>(value: AttributeValue) -> Result<T, ItemError> {
+    T::deserialize(de::Deserializer::new(value))
+}
+
+/// Stored as B. A plain `Vec<u8>` is stored as a list of numbers instead.
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Binary(pub Vec<u8>);
+
+impl Serialize for Binary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Binary {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct BinaryVisitor;
+
+        impl<'de> Visitor<'de> for BinaryVisitor {
+            type Value = Binary;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("binary data")
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, value: &[u8]) -> Result<Binary, E> {
+                Ok(Binary(value.to_vec()))
+            }
+
+            fn visit_byte_buf<E: serde::de::Error>(self, value: Vec<u8>) -> Result<Binary, E> {
+                Ok(Binary(value))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Binary, A::Error> {
+                let mut bytes = vec![];
+                while let Some(byte) = seq.next_element()? {
+                    bytes.push(byte);
+                }
+
+                Ok(Binary(bytes))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(BinaryVisitor)
+    }
+}
+
+/// Stored as SS.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "$dynamodb::StringSet")]
+pub struct StringSet(pub BTreeSet<String>);
+
+/// Stored as NS.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "$dynamodb::NumberSet")]
+pub struct NumberSet<T: Ord>(pub BTreeSet<T>);
+
+/// Stored as BS.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename = "$dynamodb::BinarySet")]
+pub struct BinarySet(pub BTreeSet<Binary>);
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeSet, HashMap};
+
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use aws_sdk_dynamodb::types::Blob;
+    use pretty_assertions::assert_eq;
+    use serde::{Deserialize, Serialize};
+
+    use crate::item::{from_item, to_attribute_value, to_item, Binary, BinarySet, ItemError, NumberSet, StringSet};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum MessageType {
+        Post,
+        Reply { parent_id: String },
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Message {
+        message_id: String,
+        account_id: u32,
+        score: f64,
+        pinned: bool,
+        edited_at: Option<String>,
+        mentions: Vec<String>,
+        metadata: HashMap<String, String>,
+        tags: StringSet,
+        reactions: NumberSet<u32>,
+        attachments: BinarySet,
+        thumbnail: Binary,
+        message_type: MessageType,
+        reply: MessageType,
+    }
+
+    fn message() -> Message {
+        Message {
+            message_id: "m-1".to_string(),
+            account_id: 111,
+            score: 1.5,
+            pinned: true,
+            edited_at: None,
+            mentions: vec!["a".to_string(), "b".to_string()],
+            metadata: HashMap::from([("client".to_string(), "ios".to_string())]),
+            tags: StringSet(BTreeSet::from(["x".to_string(), "y".to_string()])),
+            reactions: NumberSet(BTreeSet::from([1, 2])),
+            attachments: BinarySet(BTreeSet::from([Binary(vec![1]), Binary(vec![2])])),
+            thumbnail: Binary(vec![0, 255]),
+            message_type: MessageType::Post,
+            reply: MessageType::Reply {
+                parent_id: "m-0".to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_to_item() {
+        let items = to_item(&message()).unwrap();
+
+        assert_eq!(Some(&AttributeValue::S("m-1".to_string())), items.get("message_id"));
+        assert_eq!(Some(&AttributeValue::N("111".to_string())), items.get("account_id"));
+        assert_eq!(Some(&AttributeValue::N("1.5".to_string())), items.get("score"));
+        assert_eq!(Some(&AttributeValue::Bool(true)), items.get("pinned"));
+        assert_eq!(None, items.get("edited_at"));
+        assert_eq!(
+            Some(&AttributeValue::L(vec![
+                AttributeValue::S("a".to_string()),
+                AttributeValue::S("b".to_string())
+            ])),
+            items.get("mentions")
+        );
+        assert_eq!(
+            Some(&AttributeValue::M(HashMap::from([(
+                "client".to_string(),
+                AttributeValue::S("ios".to_string())
+            )]))),
+            items.get("metadata")
+        );
+        assert_eq!(
+            Some(&AttributeValue::Ss(vec!["x".to_string(), "y".to_string()])),
+            items.get("tags")
+        );
+        assert_eq!(
+            Some(&AttributeValue::Ns(vec!["1".to_string(), "2".to_string()])),
+            items.get("reactions")
+        );
+        assert_eq!(
+            Some(&AttributeValue::Bs(vec![Blob::new(vec![1]), Blob::new(vec![2])])),
+            items.get("attachments")
+        );
+        assert_eq!(Some(&AttributeValue::B(Blob::new(vec![0, 255]))), items.get("thumbnail"));
+        assert_eq!(Some(&AttributeValue::S("Post".to_string())), items.get("message_type"));
+        assert_eq!(
+            Some(&AttributeValue::M(HashMap::from([(
+                "Reply".to_string(),
+                AttributeValue::M(HashMap::from([(
+                    "parent_id".to_string(),
+                    AttributeValue::S("m-0".to_string())
+                )]))
+            )]))),
+            items.get("reply")
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let items = to_item(&message()).unwrap();
+
+        assert_eq!(message(), from_item::<Message>(items).unwrap());
+    }
+
+    #[test]
+    fn test_none_is_left_out() {
+        let edited = Message {
+            edited_at: Some("2023-03-01".to_string()),
+            ..message()
+        };
+        let items = to_item(&edited).unwrap();
+        assert_eq!(Some(&AttributeValue::S("2023-03-01".to_string())), items.get("edited_at"));
+        assert_eq!(edited, from_item::<Message>(items).unwrap());
+
+        let items = to_item(&HashMap::from([("a", Some(1)), ("b", None)])).unwrap();
+        assert_eq!(HashMap::from([("a".to_string(), AttributeValue::N("1".to_string()))]), items);
+        assert_eq!(
+            vec![AttributeValue::Null(true)],
+            match to_attribute_value(&vec![None::<u32>]).unwrap() {
+                AttributeValue::L(elements) => elements,
+                value => panic!("unexpected value: {:?}", value),
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        assert!(matches!(to_item(&"message"), Err(ItemError::NotAnItem(_))));
+        assert_eq!(
+            Err(ItemError::EmptySet),
+            to_item(&HashMap::from([("tags", StringSet::default())]))
+        );
+    }
+}
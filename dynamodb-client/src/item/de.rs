@@ -0,0 +1,301 @@
+use std::collections::hash_map;
+use std::vec;
+
+use aws_sdk_dynamodb::model::AttributeValue;
+use serde::de::value::StringDeserializer;
+use serde::de::{
+    DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess, Visitor,
+};
+use serde::forward_to_deserialize_any;
+
+use crate::item::ItemError;
+
+/// Deserializes a single `AttributeValue`, the reverse of `item::ser::Serializer`.
+#[derive(Debug)]
+pub struct Deserializer {
+    value: AttributeValue,
+}
+
+impl Deserializer {
+    pub fn new(value: AttributeValue) -> Self {
+        Self { value }
+    }
+}
+
+impl<'de> serde::Deserializer<'de> for Deserializer {
+    type Error = ItemError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ItemError> {
+        match self.value {
+            AttributeValue::S(value) => visitor.visit_string(value),
+            AttributeValue::N(value) => visit_number(value, visitor),
+            AttributeValue::B(value) => visitor.visit_byte_buf(value.into_inner()),
+            AttributeValue::Bool(value) => visitor.visit_bool(value),
+            AttributeValue::Null(_) => visitor.visit_unit(),
+            AttributeValue::L(values) => visitor.visit_seq(SeqDeserializer::new(values)),
+            AttributeValue::M(values) => visitor.visit_map(MapDeserializer::new(values)),
+            AttributeValue::Ss(values) => visitor.visit_seq(SeqDeserializer::new(
+                values.into_iter().map(AttributeValue::S).collect(),
+            )),
+            AttributeValue::Ns(values) => visitor.visit_seq(SeqDeserializer::new(
+                values.into_iter().map(AttributeValue::N).collect(),
+            )),
+            AttributeValue::Bs(values) => visitor.visit_seq(SeqDeserializer::new(
+                values.into_iter().map(AttributeValue::B).collect(),
+            )),
+            value => Err(ItemError::UnsupportedAttributeValue(value)),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ItemError> {
+        match self.value {
+            AttributeValue::Null(_) => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ItemError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ItemError> {
+        match self.value {
+            AttributeValue::S(variant) => visitor.visit_enum(EnumDeserializer {
+                variant,
+                value: None,
+            }),
+            AttributeValue::M(values) if values.len() == 1 => {
+                let (variant, value) = values.into_iter().next().expect("map has one entry");
+
+                visitor.visit_enum(EnumDeserializer {
+                    variant,
+                    value: Some(value),
+                })
+            }
+            value => Err(ItemError::Message(format!(
+                "Enum must be stored as S or a single-entry M. Actual value: {:?}",
+                value
+            ))),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+fn visit_number<'de, V: Visitor<'de>>(value: String, visitor: V) -> Result<V::Value, ItemError> {
+    if let Ok(number) = value.parse::<i64>() {
+        visitor.visit_i64(number)
+    } else if let Ok(number) = value.parse::<u64>() {
+        visitor.visit_u64(number)
+    } else if let Ok(number) = value.parse::<f64>() {
+        visitor.visit_f64(number)
+    } else {
+        Err(ItemError::InvalidNumber(value))
+    }
+}
+
+struct SeqDeserializer {
+    values: vec::IntoIter<AttributeValue>,
+}
+
+impl SeqDeserializer {
+    fn new(values: Vec<AttributeValue>) -> Self {
+        Self {
+            values: values.into_iter(),
+        }
+    }
+}
+
+impl<'de> SeqAccess<'de> for SeqDeserializer {
+    type Error = ItemError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, ItemError> {
+        self.values
+            .next()
+            .map(|value| seed.deserialize(Deserializer::new(value)))
+            .transpose()
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+struct MapDeserializer {
+    values: hash_map::IntoIter<String, AttributeValue>,
+    next_value: Option<AttributeValue>,
+}
+
+impl MapDeserializer {
+    fn new(values: hash_map::HashMap<String, AttributeValue>) -> Self {
+        Self {
+            values: values.into_iter(),
+            next_value: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for MapDeserializer {
+    type Error = ItemError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, ItemError> {
+        match self.values.next() {
+            Some((key, value)) => {
+                self.next_value = Some(value);
+                seed.deserialize(KeyDeserializer { key }).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value, ItemError> {
+        let value = self
+            .next_value
+            .take()
+            .ok_or_else(|| ItemError::Message("next_value called before next_key".to_string()))?;
+
+        seed.deserialize(Deserializer::new(value))
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.values.len())
+    }
+}
+
+/// Map keys are always strings in DynamoDB; numeric keys are parsed back on request.
+struct KeyDeserializer {
+    key: String,
+}
+
+macro_rules! deserialize_parsed_key {
+    ($($method:ident => $visit:ident,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ItemError> {
+                match self.key.parse() {
+                    Ok(number) => visitor.$visit(number),
+                    Err(_) => Err(ItemError::InvalidNumber(self.key)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> serde::Deserializer<'de> for KeyDeserializer {
+    type Error = ItemError;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, ItemError> {
+        visitor.visit_string(self.key)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, ItemError> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ItemError> {
+        visitor.visit_enum(EnumDeserializer {
+            variant: self.key,
+            value: None,
+        })
+    }
+
+    deserialize_parsed_key! {
+        deserialize_i8 => visit_i8,
+        deserialize_i16 => visit_i16,
+        deserialize_i32 => visit_i32,
+        deserialize_i64 => visit_i64,
+        deserialize_u8 => visit_u8,
+        deserialize_u16 => visit_u16,
+        deserialize_u32 => visit_u32,
+        deserialize_u64 => visit_u64,
+    }
+
+    forward_to_deserialize_any! {
+        bool i128 u128 f32 f64 char str string bytes byte_buf option unit unit_struct
+        seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+struct EnumDeserializer {
+    variant: String,
+    value: Option<AttributeValue>,
+}
+
+impl<'de> EnumAccess<'de> for EnumDeserializer {
+    type Error = ItemError;
+    type Variant = VariantDeserializer;
+
+    fn variant_seed<V: DeserializeSeed<'de>>(
+        self,
+        seed: V,
+    ) -> Result<(V::Value, VariantDeserializer), ItemError> {
+        let variant: StringDeserializer<ItemError> = self.variant.into_deserializer();
+
+        Ok((
+            seed.deserialize(variant)?,
+            VariantDeserializer { value: self.value },
+        ))
+    }
+}
+
+struct VariantDeserializer {
+    value: Option<AttributeValue>,
+}
+
+impl VariantDeserializer {
+    fn value(self) -> Result<Deserializer, ItemError> {
+        self.value
+            .map(Deserializer::new)
+            .ok_or_else(|| ItemError::Message("Enum variant has no value.".to_string()))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = ItemError;
+
+    fn unit_variant(self) -> Result<(), ItemError> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T: DeserializeSeed<'de>>(self, seed: T) -> Result<T::Value, ItemError> {
+        seed.deserialize(self.value()?)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, visitor: V) -> Result<V::Value, ItemError> {
+        serde::Deserializer::deserialize_seq(self.value()?, visitor)
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, ItemError> {
+        serde::Deserializer::deserialize_map(self.value()?, visitor)
+    }
+}
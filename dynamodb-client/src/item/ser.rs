@@ -0,0 +1,395 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::model::AttributeValue;
+use aws_sdk_dynamodb::types::Blob;
+use serde::ser::{
+    SerializeMap, SerializeSeq, SerializeStruct, SerializeStructVariant, SerializeTuple,
+    SerializeTupleStruct, SerializeTupleVariant,
+};
+use serde::Serialize;
+
+use crate::item::{ItemError, BINARY_SET, NUMBER_SET, STRING_SET};
+
+/// Serializes any `Serialize` value into a single `AttributeValue`.
+///
+/// Numbers become N, `None` and `()` become NULL, sequences and tuples become L,
+/// structs and maps become M. Struct fields and map values that would be NULL are left out,
+/// so an empty `Option` neither breaks a sparse index key nor defeats `attribute_not_exists`. Enums are externally tagged: unit variants are stored as S,
+/// the others as a single-entry M.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Serializer;
+
+impl serde::Serializer for Serializer {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+    type SerializeSeq = SerializeList;
+    type SerializeTuple = SerializeList;
+    type SerializeTupleStruct = SerializeList;
+    type SerializeTupleVariant = SerializeVariant<SerializeList>;
+    type SerializeMap = SerializeAttributes;
+    type SerializeStruct = SerializeAttributes;
+    type SerializeStructVariant = SerializeVariant<SerializeAttributes>;
+
+    fn serialize_bool(self, value: bool) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::Bool(value))
+    }
+
+    fn serialize_i8(self, value: i8) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_i16(self, value: i16) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_i32(self, value: i32) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_i64(self, value: i64) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_i128(self, value: i128) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_u8(self, value: u8) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_u16(self, value: u16) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_u32(self, value: u32) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_u64(self, value: u64) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_u128(self, value: u128) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::N(value.to_string()))
+    }
+
+    fn serialize_f32(self, value: f32) -> Result<AttributeValue, ItemError> {
+        self.serialize_f64(value as f64)
+    }
+
+    fn serialize_f64(self, value: f64) -> Result<AttributeValue, ItemError> {
+        if value.is_finite() {
+            Ok(AttributeValue::N(value.to_string()))
+        } else {
+            Err(ItemError::InvalidNumber(value.to_string()))
+        }
+    }
+
+    fn serialize_char(self, value: char) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::S(value.to_string()))
+    }
+
+    fn serialize_str(self, value: &str) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::S(value.to_string()))
+    }
+
+    fn serialize_bytes(self, value: &[u8]) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::B(Blob::new(value)))
+    }
+
+    fn serialize_none(self) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_some<T: Serialize + ?Sized>(self, value: &T) -> Result<AttributeValue, ItemError> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::Null(true))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::S(variant.to_string()))
+    }
+
+    fn serialize_newtype_struct<T: Serialize + ?Sized>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<AttributeValue, ItemError> {
+        let attribute_value = value.serialize(self)?;
+
+        match name {
+            STRING_SET => to_set(attribute_value, |element| match element {
+                AttributeValue::S(value) => Ok(value),
+                element => Err(ItemError::InvalidSetElement(element)),
+            })
+            .map(AttributeValue::Ss),
+            NUMBER_SET => to_set(attribute_value, |element| match element {
+                AttributeValue::N(value) => Ok(value),
+                element => Err(ItemError::InvalidSetElement(element)),
+            })
+            .map(AttributeValue::Ns),
+            BINARY_SET => to_set(attribute_value, |element| match element {
+                AttributeValue::B(value) => Ok(value),
+                element => Err(ItemError::InvalidSetElement(element)),
+            })
+            .map(AttributeValue::Bs),
+            _ => Ok(attribute_value),
+        }
+    }
+
+    fn serialize_newtype_variant<T: Serialize + ?Sized>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::M(HashMap::from([(
+            variant.to_string(),
+            value.serialize(self)?,
+        )])))
+    }
+
+    fn serialize_seq(self, len: Option<usize>) -> Result<SerializeList, ItemError> {
+        Ok(SerializeList::new(len.unwrap_or_default()))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SerializeList, ItemError> {
+        Ok(SerializeList::new(len))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeList, ItemError> {
+        Ok(SerializeList::new(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeList>, ItemError> {
+        Ok(SerializeVariant::new(variant, SerializeList::new(len)))
+    }
+
+    fn serialize_map(self, len: Option<usize>) -> Result<SerializeAttributes, ItemError> {
+        Ok(SerializeAttributes::new(len.unwrap_or_default()))
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        len: usize,
+    ) -> Result<SerializeAttributes, ItemError> {
+        Ok(SerializeAttributes::new(len))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<SerializeVariant<SerializeAttributes>, ItemError> {
+        Ok(SerializeVariant::new(variant, SerializeAttributes::new(len)))
+    }
+}
+
+fn to_set<T>(
+    attribute_value: AttributeValue,
+    element: impl Fn(AttributeValue) -> Result<T, ItemError>,
+) -> Result<Vec<T>, ItemError> {
+    match attribute_value {
+        AttributeValue::L(elements) if elements.is_empty() => Err(ItemError::EmptySet),
+        AttributeValue::L(elements) => elements.into_iter().map(element).collect(),
+        attribute_value => Err(ItemError::InvalidSetElement(attribute_value)),
+    }
+}
+
+pub struct SerializeList {
+    elements: Vec<AttributeValue>,
+}
+
+impl SerializeList {
+    fn new(len: usize) -> Self {
+        Self {
+            elements: Vec::with_capacity(len),
+        }
+    }
+
+    fn push<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ItemError> {
+        self.elements.push(value.serialize(Serializer)?);
+        Ok(())
+    }
+}
+
+impl SerializeSeq for SerializeList {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ItemError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::L(self.elements))
+    }
+}
+
+impl SerializeTuple for SerializeList {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+
+    fn serialize_element<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ItemError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::L(self.elements))
+    }
+}
+
+impl SerializeTupleStruct for SerializeList {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ItemError> {
+        self.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::L(self.elements))
+    }
+}
+
+pub struct SerializeAttributes {
+    attributes: HashMap<String, AttributeValue>,
+    next_key: Option<String>,
+}
+
+impl SerializeAttributes {
+    fn new(len: usize) -> Self {
+        Self {
+            attributes: HashMap::with_capacity(len),
+            next_key: None,
+        }
+    }
+
+    fn insert<T: Serialize + ?Sized>(&mut self, key: String, value: &T) -> Result<(), ItemError> {
+        match value.serialize(Serializer)? {
+            AttributeValue::Null(_) => {}
+            attribute_value => {
+                self.attributes.insert(key, attribute_value);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl SerializeMap for SerializeAttributes {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+
+    fn serialize_key<T: Serialize + ?Sized>(&mut self, key: &T) -> Result<(), ItemError> {
+        let key = match key.serialize(Serializer)? {
+            AttributeValue::S(key) | AttributeValue::N(key) => key,
+            key => return Err(ItemError::InvalidMapKey(key)),
+        };
+
+        self.next_key = Some(key);
+        Ok(())
+    }
+
+    fn serialize_value<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ItemError> {
+        let key = self
+            .next_key
+            .take()
+            .ok_or_else(|| ItemError::Message("serialize_value called before serialize_key".to_string()))?;
+
+        self.insert(key, value)
+    }
+
+    fn end(self) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::M(self.attributes))
+    }
+}
+
+impl SerializeStruct for SerializeAttributes {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ItemError> {
+        self.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<AttributeValue, ItemError> {
+        Ok(AttributeValue::M(self.attributes))
+    }
+}
+
+/// Wraps the contents of a tuple or struct variant in a single-entry M keyed by the variant name.
+pub struct SerializeVariant<S> {
+    variant: &'static str,
+    inner: S,
+}
+
+impl<S> SerializeVariant<S> {
+    fn new(variant: &'static str, inner: S) -> Self {
+        Self { variant, inner }
+    }
+
+    fn wrap(variant: &'static str, value: AttributeValue) -> AttributeValue {
+        AttributeValue::M(HashMap::from([(variant.to_string(), value)]))
+    }
+}
+
+impl SerializeTupleVariant for SerializeVariant<SerializeList> {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+
+    fn serialize_field<T: Serialize + ?Sized>(&mut self, value: &T) -> Result<(), ItemError> {
+        self.inner.push(value)
+    }
+
+    fn end(self) -> Result<AttributeValue, ItemError> {
+        Ok(Self::wrap(self.variant, AttributeValue::L(self.inner.elements)))
+    }
+}
+
+impl SerializeStructVariant for SerializeVariant<SerializeAttributes> {
+    type Ok = AttributeValue;
+    type Error = ItemError;
+
+    fn serialize_field<T: Serialize + ?Sized>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<(), ItemError> {
+        self.inner.insert(key.to_string(), value)
+    }
+
+    fn end(self) -> Result<AttributeValue, ItemError> {
+        Ok(Self::wrap(self.variant, AttributeValue::M(self.inner.attributes)))
+    }
+}
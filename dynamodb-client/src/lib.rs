@@ -1,2 +1,13 @@
+pub mod api;
 pub mod client;
+pub mod config;
+pub mod error;
+pub mod in_memory;
+pub mod item;
+pub mod metrics;
 pub mod query;
+mod rate_limiter;
+pub mod retry;
+pub mod stream;
+pub mod stream_client;
+pub mod table_dump;
@@ -0,0 +1,289 @@
+use std::fmt::Debug;
+use std::time::Duration;
+
+use aws_sdk_dynamodb::model::ConsumedCapacity;
+use aws_sdk_dynamodb::output::{
+    BatchExecuteStatementOutput, BatchGetItemOutput, BatchWriteItemOutput, CreateTableOutput, DeleteItemOutput,
+    DeleteTableOutput, DescribeTableOutput, ExecuteStatementOutput, GetItemOutput, ListTablesOutput,
+    PutItemOutput, QueryOutput, ScanOutput, TransactWriteItemsOutput, UpdateItemOutput, UpdateTableOutput,
+    UpdateTimeToLiveOutput,
+};
+use aws_sdk_dynamodbstreams::output::{
+    DescribeStreamOutput, GetRecordsOutput, GetShardIteratorOutput, ListStreamsOutput,
+};
+use tracing::Span;
+
+use crate::error::DynamoDbError;
+use crate::retry::RetryPolicy;
+
+/// Counter, incremented once per operation including all of its retries.
+pub const REQUESTS_TOTAL: &str = "dynamodb_requests_total";
+/// Counter, incremented when an operation fails after its last retry.
+pub const ERRORS_TOTAL: &str = "dynamodb_errors_total";
+/// Counter of attempts beyond the first one.
+pub const RETRIES_TOTAL: &str = "dynamodb_retries_total";
+/// Histogram of operation durations in seconds, including backoff between retries.
+pub const REQUEST_DURATION_SECONDS: &str = "dynamodb_request_duration_seconds";
+/// Histogram of the capacity units reported by DynamoDB per operation.
+pub const CONSUMED_CAPACITY_UNITS: &str = "dynamodb_consumed_capacity_units";
+
+/// Receives counters and histograms for every operation, e.g. to forward them to Prometheus.
+/// Labels are `operation` and `table`.
+pub trait MetricsHook: Debug + Send + Sync {
+    fn increment_counter(&self, name: &'static str, value: u64, labels: &[(&'static str, &str)]);
+
+    fn record_histogram(&self, name: &'static str, value: f64, labels: &[(&'static str, &str)]);
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoMetrics;
+
+impl MetricsHook for NoMetrics {
+    fn increment_counter(&self, _name: &'static str, _value: u64, _labels: &[(&'static str, &str)]) {}
+
+    fn record_histogram(&self, _name: &'static str, _value: f64, _labels: &[(&'static str, &str)]) {}
+}
+
+/// One call to DynamoDB as seen by tracing and the metrics hook. `table_name` is empty when the
+/// operation is not bound to a table and comma separated when it spans several.
+pub(crate) struct Operation<'a> {
+    name: &'static str,
+    table_name: String,
+    retry_policy: &'a dyn RetryPolicy,
+    metrics: &'a dyn MetricsHook,
+}
+
+impl<'a> Operation<'a> {
+    pub(crate) fn new(
+        name: &'static str,
+        table_name: impl Into<String>,
+        retry_policy: &'a dyn RetryPolicy,
+        metrics: &'a dyn MetricsHook,
+    ) -> Self {
+        Self {
+            name,
+            table_name: table_name.into(),
+            retry_policy,
+            metrics,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        self.name
+    }
+
+    pub(crate) fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub(crate) fn retry_policy(&self) -> &dyn RetryPolicy {
+        self.retry_policy
+    }
+
+    pub(crate) fn record<T: ConsumedCapacityUnits>(
+        &self,
+        span: &Span,
+        duration: Duration,
+        attempts: u32,
+        result: &Result<T, DynamoDbError>,
+    ) {
+        let retries = attempts.saturating_sub(1);
+        let consumed_capacity = result
+            .as_ref()
+            .ok()
+            .and_then(ConsumedCapacityUnits::consumed_capacity_units);
+
+        span.record("duration_ms", duration.as_millis() as u64);
+        span.record("retries", retries);
+        if let Some(consumed_capacity) = consumed_capacity {
+            span.record("consumed_capacity", consumed_capacity);
+        }
+
+        let labels = [("operation", self.name), ("table", self.table_name.as_str())];
+
+        self.metrics.increment_counter(REQUESTS_TOTAL, 1, &labels);
+        if retries > 0 {
+            self.metrics.increment_counter(RETRIES_TOTAL, retries as u64, &labels);
+        }
+        if result.is_err() {
+            self.metrics.increment_counter(ERRORS_TOTAL, 1, &labels);
+        }
+        self.metrics
+            .record_histogram(REQUEST_DURATION_SECONDS, duration.as_secs_f64(), &labels);
+        if let Some(consumed_capacity) = consumed_capacity {
+            self.metrics
+                .record_histogram(CONSUMED_CAPACITY_UNITS, consumed_capacity, &labels);
+        }
+    }
+}
+
+/// Outputs that carry the `ConsumedCapacity` requested with `ReturnConsumedCapacity::Total`.
+pub(crate) trait ConsumedCapacityUnits {
+    fn consumed_capacity_units(&self) -> Option<f64> {
+        None
+    }
+}
+
+fn total_capacity_units(consumed_capacities: Option<&[ConsumedCapacity]>) -> Option<f64> {
+    consumed_capacities.map(|consumed_capacities| {
+        consumed_capacities
+            .iter()
+            .filter_map(ConsumedCapacity::capacity_units)
+            .sum()
+    })
+}
+
+macro_rules! without_consumed_capacity {
+    ($($output:ty),*) => {
+        $(impl ConsumedCapacityUnits for $output {})*
+    };
+}
+
+macro_rules! with_consumed_capacity {
+    ($($output:ty),*) => {
+        $(impl ConsumedCapacityUnits for $output {
+            fn consumed_capacity_units(&self) -> Option<f64> {
+                self.consumed_capacity().and_then(ConsumedCapacity::capacity_units)
+            }
+        })*
+    };
+}
+
+macro_rules! with_consumed_capacities {
+    ($($output:ty),*) => {
+        $(impl ConsumedCapacityUnits for $output {
+            fn consumed_capacity_units(&self) -> Option<f64> {
+                total_capacity_units(self.consumed_capacity())
+            }
+        })*
+    };
+}
+
+without_consumed_capacity!(
+    CreateTableOutput,
+    DeleteTableOutput,
+    DescribeTableOutput,
+    UpdateTableOutput,
+    UpdateTimeToLiveOutput,
+    ListTablesOutput,
+    ListStreamsOutput,
+    DescribeStreamOutput,
+    GetShardIteratorOutput,
+    GetRecordsOutput
+);
+
+with_consumed_capacity!(
+    GetItemOutput,
+    PutItemOutput,
+    UpdateItemOutput,
+    DeleteItemOutput,
+    QueryOutput,
+    ScanOutput,
+    ExecuteStatementOutput
+);
+
+with_consumed_capacities!(
+    TransactWriteItemsOutput,
+    BatchWriteItemOutput,
+    BatchGetItemOutput,
+    BatchExecuteStatementOutput
+);
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Mutex;
+    use std::time::Duration;
+
+    use aws_sdk_dynamodb::model::ConsumedCapacity;
+    use aws_sdk_dynamodb::output::PutItemOutput;
+    use pretty_assertions::assert_eq;
+
+    use crate::error::DynamoDbError;
+    use crate::metrics::{
+        MetricsHook, Operation, CONSUMED_CAPACITY_UNITS, ERRORS_TOTAL, REQUESTS_TOTAL, REQUEST_DURATION_SECONDS,
+        RETRIES_TOTAL,
+    };
+    use crate::retry::{with_retry, ExponentialBackoff, NoRetry};
+
+    #[derive(Debug, Default)]
+    struct RecordingMetrics {
+        counters: Mutex<Vec<(&'static str, u64, String)>>,
+        histograms: Mutex<Vec<&'static str>>,
+        consumed_capacity: Mutex<Vec<f64>>,
+    }
+
+    impl MetricsHook for RecordingMetrics {
+        fn increment_counter(&self, name: &'static str, value: u64, labels: &[(&'static str, &str)]) {
+            let labels = labels
+                .iter()
+                .map(|(name, value)| format!("{}={}", name, value))
+                .collect::<Vec<_>>()
+                .join(",");
+
+            self.counters.lock().unwrap().push((name, value, labels));
+        }
+
+        fn record_histogram(&self, name: &'static str, value: f64, _labels: &[(&'static str, &str)]) {
+            self.histograms.lock().unwrap().push(name);
+            if name == CONSUMED_CAPACITY_UNITS {
+                self.consumed_capacity.lock().unwrap().push(value);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_records_metrics() {
+        let metrics = RecordingMetrics::default();
+        let policy = ExponentialBackoff::new().with_base_delay(Duration::from_millis(1));
+        let attempts = AtomicU32::new(0);
+        let attempts = &attempts;
+
+        let operation = Operation::new("put_item", "Messages", &policy, &metrics);
+        with_retry(&operation, move || async move {
+            if attempts.fetch_add(1, Ordering::SeqCst) == 0 {
+                return Err(DynamoDbError::Throttled("slow down".to_string()));
+            }
+
+            Ok(PutItemOutput::builder()
+                .consumed_capacity(ConsumedCapacity::builder().capacity_units(2.0).build())
+                .build())
+        })
+        .await
+        .unwrap();
+
+        let labels = "operation=put_item,table=Messages".to_string();
+        assert_eq!(
+            vec![(REQUESTS_TOTAL, 1, labels.clone()), (RETRIES_TOTAL, 1, labels)],
+            *metrics.counters.lock().unwrap()
+        );
+        assert_eq!(
+            vec![REQUEST_DURATION_SECONDS, CONSUMED_CAPACITY_UNITS],
+            *metrics.histograms.lock().unwrap()
+        );
+        assert_eq!(vec![2.0], *metrics.consumed_capacity.lock().unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_with_retry_counts_errors() {
+        let metrics = RecordingMetrics::default();
+
+        let operation = Operation::new("put_item", "Messages", &NoRetry, &metrics);
+        let result = with_retry(&operation, || async {
+            Err::<PutItemOutput, _>(DynamoDbError::ConditionalCheckFailed("failed".to_string()))
+        })
+        .await;
+
+        assert!(matches!(result, Err(DynamoDbError::ConditionalCheckFailed(_))));
+        assert_eq!(
+            vec![REQUESTS_TOTAL, ERRORS_TOTAL],
+            metrics
+                .counters
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(name, _, _)| *name)
+                .collect::<Vec<_>>()
+        );
+    }
+}
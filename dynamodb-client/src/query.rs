@@ -1,6 +1,24 @@
+pub mod attribute_value;
+pub mod batch_execute_statement;
+pub mod batch_get;
+pub mod batch_write;
 pub mod create_table;
+pub mod delete_item;
 pub mod delete_table;
+pub mod describe_table;
 pub mod dynamodb_query;
+pub mod execute_statement;
+pub mod expression;
 pub mod get_item;
+pub mod list_streams;
 pub mod list_tables;
+pub mod parallel_scan;
 pub mod put_item;
+#[allow(clippy::module_inception)]
+pub mod query;
+pub mod read_stream;
+pub mod scan;
+pub mod transact_write;
+pub mod update_item;
+pub mod update_table;
+pub mod update_time_to_live;
@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::model::AttributeValue;
+use aws_sdk_dynamodb::types::Blob;
+use aws_smithy_types::base64;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::query::put_item::Items;
+
+/// An `AttributeValue` in the DynamoDB JSON format, e.g. `{"S": "text"}` or `{"N": "1"}`.
+/// Binary values are base64 encoded, as in the AWS CLI.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum JsonAttributeValue {
+    S(String),
+    N(String),
+    B(String),
+    #[serde(rename = "BOOL")]
+    Bool(bool),
+    #[serde(rename = "NULL")]
+    Null(bool),
+    M(HashMap<String, JsonAttributeValue>),
+    L(Vec<JsonAttributeValue>),
+    #[serde(rename = "SS")]
+    Ss(Vec<String>),
+    #[serde(rename = "NS")]
+    Ns(Vec<String>),
+    #[serde(rename = "BS")]
+    Bs(Vec<String>),
+}
+
+impl JsonAttributeValue {
+    pub fn into_attribute_value(self) -> Result<AttributeValue, String> {
+        let attribute_value = match self {
+            JsonAttributeValue::S(value) => AttributeValue::S(value),
+            JsonAttributeValue::N(value) => AttributeValue::N(value),
+            JsonAttributeValue::B(value) => AttributeValue::B(decode_blob(&value)?),
+            JsonAttributeValue::Bool(value) => AttributeValue::Bool(value),
+            JsonAttributeValue::Null(value) => AttributeValue::Null(value),
+            JsonAttributeValue::M(values) => AttributeValue::M(
+                values
+                    .into_iter()
+                    .map(|(name, value)| Ok((name, value.into_attribute_value()?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            JsonAttributeValue::L(values) => AttributeValue::L(
+                values
+                    .into_iter()
+                    .map(JsonAttributeValue::into_attribute_value)
+                    .collect::<Result<_, String>>()?,
+            ),
+            JsonAttributeValue::Ss(values) => AttributeValue::Ss(values),
+            JsonAttributeValue::Ns(values) => AttributeValue::Ns(values),
+            JsonAttributeValue::Bs(values) => AttributeValue::Bs(
+                values
+                    .iter()
+                    .map(|value| decode_blob(value))
+                    .collect::<Result<_, String>>()?,
+            ),
+        };
+
+        Ok(attribute_value)
+    }
+
+    pub fn from_attribute_value(attribute_value: &AttributeValue) -> Result<Self, String> {
+        let json_attribute_value = match attribute_value {
+            AttributeValue::S(value) => JsonAttributeValue::S(value.clone()),
+            AttributeValue::N(value) => JsonAttributeValue::N(value.clone()),
+            AttributeValue::B(value) => JsonAttributeValue::B(base64::encode(value.as_ref())),
+            AttributeValue::Bool(value) => JsonAttributeValue::Bool(*value),
+            AttributeValue::Null(value) => JsonAttributeValue::Null(*value),
+            AttributeValue::M(values) => JsonAttributeValue::M(
+                values
+                    .iter()
+                    .map(|(name, value)| Ok((name.clone(), Self::from_attribute_value(value)?)))
+                    .collect::<Result<_, String>>()?,
+            ),
+            AttributeValue::L(values) => JsonAttributeValue::L(
+                values
+                    .iter()
+                    .map(Self::from_attribute_value)
+                    .collect::<Result<_, String>>()?,
+            ),
+            AttributeValue::Ss(values) => JsonAttributeValue::Ss(values.clone()),
+            AttributeValue::Ns(values) => JsonAttributeValue::Ns(values.clone()),
+            AttributeValue::Bs(values) => JsonAttributeValue::Bs(
+                values
+                    .iter()
+                    .map(|value| base64::encode(value.as_ref()))
+                    .collect(),
+            ),
+            unknown_value => return Err(format!("Attribute value {:?} was not supported.", unknown_value)),
+        };
+
+        Ok(json_attribute_value)
+    }
+}
+
+fn decode_blob(value: &str) -> Result<Blob, String> {
+    base64::decode(value)
+        .map(Blob::new)
+        .map_err(|error| format!("Binary attribute value is not valid base64. Error: {}", error))
+}
+
+/// `#[serde(with = "...")]` support for an `Items` map in the DynamoDB JSON format.
+pub mod items {
+    use std::collections::HashMap;
+
+    use serde::de::Error as _;
+    use serde::ser::Error as _;
+
+    use super::*;
+
+    pub fn serialize<S: Serializer>(items: &Items, serializer: S) -> Result<S::Ok, S::Error> {
+        items
+            .iter()
+            .map(|(name, value)| Ok((name, JsonAttributeValue::from_attribute_value(value)?)))
+            .collect::<Result<HashMap<_, _>, String>>()
+            .map_err(S::Error::custom)?
+            .serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Items, D::Error> {
+        HashMap::<String, JsonAttributeValue>::deserialize(deserializer)?
+            .into_iter()
+            .map(|(name, value)| Ok((name, value.into_attribute_value()?)))
+            .collect::<Result<Items, String>>()
+            .map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use aws_sdk_dynamodb::types::Blob;
+    use pretty_assertions::assert_eq;
+
+    use crate::query::attribute_value::JsonAttributeValue;
+
+    #[test]
+    fn test_round_trip() {
+        let json = r#"{"M": {
+            "id": {"S": "1"},
+            "count": {"N": "2"},
+            "data": {"B": "AP8="},
+            "flag": {"BOOL": true},
+            "none": {"NULL": true},
+            "list": {"L": [{"S": "a"}, {"N": "3"}]},
+            "tags": {"SS": ["x", "y"]},
+            "scores": {"NS": ["1", "2"]},
+            "blobs": {"BS": ["AQ=="]}
+        }}"#;
+
+        let attribute_value = serde_json::from_str::<JsonAttributeValue>(json)
+            .unwrap()
+            .into_attribute_value()
+            .unwrap();
+
+        let expected = AttributeValue::M(HashMap::from([
+            ("id".to_string(), AttributeValue::S("1".to_string())),
+            ("count".to_string(), AttributeValue::N("2".to_string())),
+            ("data".to_string(), AttributeValue::B(Blob::new(vec![0, 255]))),
+            ("flag".to_string(), AttributeValue::Bool(true)),
+            ("none".to_string(), AttributeValue::Null(true)),
+            (
+                "list".to_string(),
+                AttributeValue::L(vec![
+                    AttributeValue::S("a".to_string()),
+                    AttributeValue::N("3".to_string()),
+                ]),
+            ),
+            (
+                "tags".to_string(),
+                AttributeValue::Ss(vec!["x".to_string(), "y".to_string()]),
+            ),
+            (
+                "scores".to_string(),
+                AttributeValue::Ns(vec!["1".to_string(), "2".to_string()]),
+            ),
+            ("blobs".to_string(), AttributeValue::Bs(vec![Blob::new(vec![1])])),
+        ]));
+
+        assert_eq!(expected, attribute_value);
+        assert_eq!(
+            serde_json::from_str::<JsonAttributeValue>(json).unwrap(),
+            JsonAttributeValue::from_attribute_value(&attribute_value).unwrap()
+        );
+    }
+}
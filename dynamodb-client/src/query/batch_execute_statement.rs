@@ -0,0 +1,173 @@
+use aws_sdk_dynamodb::model::{BatchStatementError as AwsBatchStatementError, BatchStatementErrorCodeEnum};
+
+use crate::error::DynamoDbError;
+use crate::query::execute_statement::ExecuteStatementQuery;
+use crate::query::put_item::Items;
+
+/// Max statements DynamoDB accepts in one BatchExecuteStatement call.
+pub const BATCH_EXECUTE_STATEMENT_CHUNK_SIZE: usize = 25;
+
+/// Statements must all read or all write. `limit` and `next_token` of the statements are ignored.
+#[derive(Debug, Clone, Default)]
+pub struct BatchExecuteStatementQuery {
+    statements: Vec<ExecuteStatementQuery>,
+}
+
+impl BatchExecuteStatementQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn statement(mut self, statement: ExecuteStatementQuery) -> Self {
+        self.statements.push(statement);
+        self
+    }
+
+    pub fn statements(&self) -> &Vec<ExecuteStatementQuery> {
+        &self.statements
+    }
+
+    pub fn len(&self) -> usize {
+        self.statements.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.statements.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BatchStatementErrorCode {
+    AccessDenied,
+    ConditionalCheckFailed,
+    DuplicateItem,
+    InternalServerError,
+    ItemCollectionSizeLimitExceeded,
+    ProvisionedThroughputExceeded,
+    RequestLimitExceeded,
+    ResourceNotFound,
+    ThrottlingError,
+    TransactionConflict,
+    ValidationError,
+    Unknown(String),
+}
+
+impl From<&BatchStatementErrorCodeEnum> for BatchStatementErrorCode {
+    fn from(code: &BatchStatementErrorCodeEnum) -> Self {
+        match code {
+            BatchStatementErrorCodeEnum::AccessDenied => BatchStatementErrorCode::AccessDenied,
+            BatchStatementErrorCodeEnum::ConditionalCheckFailed => BatchStatementErrorCode::ConditionalCheckFailed,
+            BatchStatementErrorCodeEnum::DuplicateItem => BatchStatementErrorCode::DuplicateItem,
+            BatchStatementErrorCodeEnum::InternalServerError => BatchStatementErrorCode::InternalServerError,
+            BatchStatementErrorCodeEnum::ItemCollectionSizeLimitExceeded => {
+                BatchStatementErrorCode::ItemCollectionSizeLimitExceeded
+            }
+            BatchStatementErrorCodeEnum::ProvisionedThroughputExceeded => {
+                BatchStatementErrorCode::ProvisionedThroughputExceeded
+            }
+            BatchStatementErrorCodeEnum::RequestLimitExceeded => BatchStatementErrorCode::RequestLimitExceeded,
+            BatchStatementErrorCodeEnum::ResourceNotFound => BatchStatementErrorCode::ResourceNotFound,
+            BatchStatementErrorCodeEnum::ThrottlingError => BatchStatementErrorCode::ThrottlingError,
+            BatchStatementErrorCodeEnum::TransactionConflict => BatchStatementErrorCode::TransactionConflict,
+            BatchStatementErrorCodeEnum::ValidationError => BatchStatementErrorCode::ValidationError,
+            code => BatchStatementErrorCode::Unknown(code.as_str().to_string()),
+        }
+    }
+}
+
+/// Why a single statement of the batch failed. The other statements are not affected.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchStatementError {
+    code: BatchStatementErrorCode,
+    message: Option<String>,
+}
+
+impl BatchStatementError {
+    pub fn new(code: BatchStatementErrorCode, message: Option<String>) -> Self {
+        Self { code, message }
+    }
+
+    pub fn from_aws(error: &AwsBatchStatementError) -> Self {
+        Self {
+            code: error
+                .code()
+                .map(BatchStatementErrorCode::from)
+                .unwrap_or_else(|| BatchStatementErrorCode::Unknown("".to_string())),
+            message: error.message().map(|message| message.to_string()),
+        }
+    }
+
+    pub fn code(&self) -> &BatchStatementErrorCode {
+        &self.code
+    }
+
+    pub fn message(&self) -> &Option<String> {
+        &self.message
+    }
+}
+
+impl From<BatchStatementError> for DynamoDbError {
+    fn from(error: BatchStatementError) -> Self {
+        let message = error.message.unwrap_or_default();
+
+        match error.code {
+            BatchStatementErrorCode::ConditionalCheckFailed => DynamoDbError::ConditionalCheckFailed(message),
+            BatchStatementErrorCode::DuplicateItem => DynamoDbError::DuplicateItem(message),
+            BatchStatementErrorCode::ResourceNotFound => DynamoDbError::ResourceNotFound(message),
+            BatchStatementErrorCode::ValidationError => DynamoDbError::ValidationError(message),
+            BatchStatementErrorCode::ProvisionedThroughputExceeded
+            | BatchStatementErrorCode::RequestLimitExceeded
+            | BatchStatementErrorCode::ThrottlingError => DynamoDbError::Throttled(message),
+            code => DynamoDbError::Service {
+                code: format!("{:?}", code),
+                message,
+            },
+        }
+    }
+}
+
+/// One response per statement, in the order of `BatchExecuteStatementQuery::statements`.
+/// A successful SELECT holds the item, or `None` when no item matched.
+#[derive(Debug, Clone, Default)]
+pub struct BatchExecuteStatementOutput {
+    responses: Vec<Result<Option<Items>, BatchStatementError>>,
+}
+
+impl BatchExecuteStatementOutput {
+    pub fn new(responses: Vec<Result<Option<Items>, BatchStatementError>>) -> Self {
+        Self { responses }
+    }
+
+    pub fn responses(&self) -> &Vec<Result<Option<Items>, BatchStatementError>> {
+        &self.responses
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.responses.iter().all(Result::is_ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::{BatchStatementError as AwsBatchStatementError, BatchStatementErrorCodeEnum};
+    use pretty_assertions::assert_eq;
+
+    use crate::error::DynamoDbError;
+    use crate::query::batch_execute_statement::{BatchStatementError, BatchStatementErrorCode};
+
+    #[test]
+    fn test_from_aws_error() {
+        let error = BatchStatementError::from_aws(
+            &AwsBatchStatementError::builder()
+                .code(BatchStatementErrorCodeEnum::DuplicateItem)
+                .message("Duplicate primary key exists in table")
+                .build(),
+        );
+
+        assert_eq!(&BatchStatementErrorCode::DuplicateItem, error.code());
+        assert!(matches!(DynamoDbError::from(error), DynamoDbError::DuplicateItem(_)));
+
+        let error = BatchStatementError::new(BatchStatementErrorCode::ThrottlingError, None);
+        assert!(matches!(DynamoDbError::from(error), DynamoDbError::Throttled(_)));
+    }
+}
@@ -0,0 +1,81 @@
+use std::collections::HashMap;
+
+use crate::query::dynamodb_query::Keys;
+use crate::query::put_item::Items;
+
+/// Max keys DynamoDB accepts in one BatchGetItem call.
+pub const BATCH_GET_CHUNK_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchGetQuery {
+    keys: Vec<(String, Keys)>,
+    consistent_read: bool,
+}
+
+impl BatchGetQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn get(mut self, table_name: impl Into<String>, keys: impl Into<Keys>) -> Self {
+        self.keys.push((table_name.into(), keys.into()));
+        self
+    }
+
+    pub fn with_consistent_read(mut self, consistent_read: bool) -> Self {
+        self.consistent_read = consistent_read;
+        self
+    }
+
+    pub fn keys(&self) -> &Vec<(String, Keys)> {
+        &self.keys
+    }
+
+    pub fn consistent_read(&self) -> &bool {
+        &self.consistent_read
+    }
+
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchGetOutput {
+    items: HashMap<String, Vec<Items>>,
+    unprocessed_keys: Vec<(String, Keys)>,
+}
+
+impl BatchGetOutput {
+    pub fn new(items: HashMap<String, Vec<Items>>, unprocessed_keys: Vec<(String, Keys)>) -> Self {
+        Self {
+            items,
+            unprocessed_keys,
+        }
+    }
+
+    /// Items found, grouped by table name. Missing keys are simply absent.
+    pub fn items(&self) -> &HashMap<String, Vec<Items>> {
+        &self.items
+    }
+
+    pub fn table_items(&self, table_name: &str) -> &[Items] {
+        self.items
+            .get(table_name)
+            .map(|items| items.as_slice())
+            .unwrap_or(&[])
+    }
+
+    /// Keys still unprocessed after every retry was spent.
+    pub fn unprocessed_keys(&self) -> &Vec<(String, Keys)> {
+        &self.unprocessed_keys
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.unprocessed_keys.is_empty()
+    }
+}
@@ -0,0 +1,128 @@
+use aws_sdk_dynamodb::model::{DeleteRequest, PutRequest, ReturnValue, WriteRequest};
+
+use crate::error::DynamoDbError;
+use crate::query::dynamodb_query::Keys;
+use crate::query::put_item::{Items, PutItemQuery};
+
+/// Max requests DynamoDB accepts in one BatchWriteItem call.
+pub const BATCH_WRITE_CHUNK_SIZE: usize = 25;
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchWriteQuery {
+    requests: Vec<(String, WriteRequest)>,
+}
+
+impl BatchWriteQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put(mut self, table_name: impl Into<String>, items: Items) -> Self {
+        let request = WriteRequest::builder()
+            .put_request(PutRequest::builder().set_item(Some(items)).build())
+            .build();
+
+        self.requests.push((table_name.into(), request));
+        self
+    }
+
+    pub fn delete(mut self, table_name: impl Into<String>, keys: impl Into<Keys>) -> Self {
+        let request = WriteRequest::builder()
+            .delete_request(DeleteRequest::builder().set_key(Some(keys.into())).build())
+            .build();
+
+        self.requests.push((table_name.into(), request));
+        self
+    }
+
+    pub fn requests(&self) -> &Vec<(String, WriteRequest)> {
+        &self.requests
+    }
+
+    pub fn len(&self) -> usize {
+        self.requests.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.requests.is_empty()
+    }
+}
+
+/// BatchWriteItem supports neither conditions nor return values, so puts that use them are rejected
+/// rather than turned into unconditional overwrites.
+impl TryFrom<Vec<PutItemQuery>> for BatchWriteQuery {
+    type Error = DynamoDbError;
+
+    fn try_from(put_items: Vec<PutItemQuery>) -> Result<Self, Self::Error> {
+        if let Some(index) = put_items.iter().position(|put_item| {
+            put_item.condition_expression().is_some()
+                || !put_item.expression_attribute_names().is_empty()
+                || !put_item.expression_attribute_values().is_empty()
+                || put_item.return_values() != ReturnValue::None
+        }) {
+            return Err(DynamoDbError::ValidationError(format!(
+                "Entry {} has a condition or return values, which BatchWriteItem does not support.",
+                index
+            )));
+        }
+
+        Ok(put_items
+            .into_iter()
+            .fold(BatchWriteQuery::new(), |query, put_item| {
+                query.put(put_item.table_name(), put_item.items())
+            }))
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct BatchWriteOutput {
+    processed_count: usize,
+    unprocessed: Vec<(String, WriteRequest)>,
+}
+
+impl BatchWriteOutput {
+    pub fn new(processed_count: usize, unprocessed: Vec<(String, WriteRequest)>) -> Self {
+        Self {
+            processed_count,
+            unprocessed,
+        }
+    }
+
+    pub fn processed_count(&self) -> usize {
+        self.processed_count
+    }
+
+    /// Requests still unprocessed after every retry was spent.
+    pub fn unprocessed(&self) -> &Vec<(String, WriteRequest)> {
+        &self.unprocessed
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.unprocessed.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use pretty_assertions::assert_eq;
+
+    use crate::error::DynamoDbError;
+    use crate::query::batch_write::BatchWriteQuery;
+    use crate::query::put_item::{Items, PutItemQuery};
+
+    #[test]
+    fn test_try_from_put_items_rejects_conditions() {
+        let item = Items::from([("id".to_string(), AttributeValue::S("1".to_string()))]);
+        let put = PutItemQuery::new("Messages", item.clone(), None, None::<String>);
+        let guarded = PutItemQuery::new("Messages", item, None, Some("attribute_not_exists(id)"));
+
+        let query = BatchWriteQuery::try_from(vec![put.clone(), put.clone()]).unwrap();
+        assert_eq!(2, query.len());
+
+        match BatchWriteQuery::try_from(vec![put, guarded]) {
+            Err(DynamoDbError::ValidationError(message)) => assert!(message.starts_with("Entry 1 ")),
+            result => panic!("unexpected result: {:?}", result),
+        }
+    }
+}
@@ -1,10 +1,12 @@
-use serde::{Deserialize, Serialize};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize};
 
 use crate::query::dynamodb_query::{
-    AttributeDefinition, KeySchema, ProvisionedThroughput, StreamSpecification,
+    AttributeDefinition, BillingMode, GlobalSecondaryIndex, KeySchema, LocalSecondaryIndex,
+    ProvisionedThroughput, SseSpecification, StreamSpecification, Tag,
 };
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+#[derive(Serialize, Debug)]
 pub struct CreateTableQuery {
     #[serde(rename = "TableName")]
     table_name: String,
@@ -12,11 +14,20 @@ pub struct CreateTableQuery {
     attribute_definitions: Vec<AttributeDefinition>,
     #[serde(rename = "KeySchema")]
     key_schemas: Vec<KeySchema>,
-    #[serde(rename = "ProvisionedThroughput")]
-    provisioned_throughput: ProvisionedThroughput,
+    #[serde(rename = "ProvisionedThroughput", skip_serializing_if = "Option::is_none")]
+    provisioned_throughput: Option<ProvisionedThroughput>,
     #[serde(rename = "StreamSpecification")]
-    #[serde(default = "StreamSpecification::default")]
     stream_specification: StreamSpecification,
+    #[serde(rename = "GlobalSecondaryIndexes", skip_serializing_if = "Vec::is_empty")]
+    global_secondary_indexes: Vec<GlobalSecondaryIndex>,
+    #[serde(rename = "LocalSecondaryIndexes", skip_serializing_if = "Vec::is_empty")]
+    local_secondary_indexes: Vec<LocalSecondaryIndex>,
+    #[serde(rename = "BillingMode", skip_serializing_if = "Option::is_none")]
+    billing_mode: Option<BillingMode>,
+    #[serde(rename = "SSESpecification", skip_serializing_if = "Option::is_none")]
+    sse_specification: Option<SseSpecification>,
+    #[serde(rename = "Tags", skip_serializing_if = "Vec::is_empty")]
+    tags: Vec<Tag>,
 }
 
 impl CreateTableQuery {
@@ -32,11 +43,209 @@ impl CreateTableQuery {
         &self.attribute_definitions
     }
 
-    pub fn provisioned_throughput(&self) -> &ProvisionedThroughput {
+    /// Required unless the billing mode is `PAY_PER_REQUEST`.
+    pub fn provisioned_throughput(&self) -> &Option<ProvisionedThroughput> {
         &self.provisioned_throughput
     }
 
     pub fn stream_specification(&self) -> &StreamSpecification {
         &self.stream_specification
     }
+
+    pub fn global_secondary_indexes(&self) -> &Vec<GlobalSecondaryIndex> {
+        &self.global_secondary_indexes
+    }
+
+    pub fn local_secondary_indexes(&self) -> &Vec<LocalSecondaryIndex> {
+        &self.local_secondary_indexes
+    }
+
+    /// `None` leaves the DynamoDB default, `PROVISIONED`.
+    pub fn billing_mode(&self) -> Option<BillingMode> {
+        self.billing_mode
+    }
+
+    pub fn sse_specification(&self) -> &Option<SseSpecification> {
+        &self.sse_specification
+    }
+
+    pub fn tags(&self) -> &Vec<Tag> {
+        &self.tags
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct CreateTableQueryJson {
+    table_name: String,
+    attribute_definitions: Vec<AttributeDefinition>,
+    #[serde(rename = "KeySchema")]
+    key_schemas: Vec<KeySchema>,
+    #[serde(default)]
+    provisioned_throughput: Option<ProvisionedThroughput>,
+    #[serde(default = "StreamSpecification::default")]
+    stream_specification: StreamSpecification,
+    #[serde(default)]
+    global_secondary_indexes: Vec<GlobalSecondaryIndex>,
+    #[serde(default)]
+    local_secondary_indexes: Vec<LocalSecondaryIndex>,
+    #[serde(default)]
+    billing_mode: Option<BillingMode>,
+    #[serde(rename = "SSESpecification")]
+    #[serde(default)]
+    sse_specification: Option<SseSpecification>,
+    #[serde(default)]
+    tags: Vec<Tag>,
+}
+
+/// Rejects provisioned tables and indexes without a `ProvisionedThroughput`, which DynamoDB would refuse.
+impl<'de> Deserialize<'de> for CreateTableQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let json = CreateTableQueryJson::deserialize(deserializer)?;
+
+        if json.billing_mode != Some(BillingMode::PayPerRequest) {
+            if json.provisioned_throughput.is_none() {
+                return Err(D::Error::missing_field("ProvisionedThroughput"));
+            }
+
+            if let Some(index) = json
+                .global_secondary_indexes
+                .iter()
+                .find(|index| index.provisioned_throughput().is_none())
+            {
+                return Err(D::Error::custom(format!(
+                    "missing field `ProvisionedThroughput` in global secondary index {} of a PROVISIONED table",
+                    index.index_name()
+                )));
+            }
+        }
+
+        Ok(CreateTableQuery {
+            table_name: json.table_name,
+            attribute_definitions: json.attribute_definitions,
+            key_schemas: json.key_schemas,
+            provisioned_throughput: json.provisioned_throughput,
+            stream_specification: json.stream_specification,
+            global_secondary_indexes: json.global_secondary_indexes,
+            local_secondary_indexes: json.local_secondary_indexes,
+            billing_mode: json.billing_mode,
+            sse_specification: json.sse_specification,
+            tags: json.tags,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::query::create_table::CreateTableQuery;
+    use crate::query::dynamodb_query::{BillingMode, ProjectionType};
+
+    #[test]
+    fn test_deserialize_on_demand_table_with_indexes() {
+        let json = r#"{
+            "TableName": "Messages",
+            "AttributeDefinitions": [
+                {"AttributeName": "message_id", "AttributeType": "S"},
+                {"AttributeName": "channel_id", "AttributeType": "S"},
+                {"AttributeName": "posted_at", "AttributeType": "S"}
+            ],
+            "KeySchema": [{"AttributeName": "message_id", "KeyType": "HASH"}],
+            "BillingMode": "PAY_PER_REQUEST",
+            "GlobalSecondaryIndexes": [{
+                "IndexName": "channel_id-posted_at-index",
+                "KeySchema": [
+                    {"AttributeName": "channel_id", "KeyType": "HASH"},
+                    {"AttributeName": "posted_at", "KeyType": "RANGE"}
+                ],
+                "Projection": {"ProjectionType": "INCLUDE", "NonKeyAttributes": ["message"]}
+            }],
+            "SSESpecification": {"Enabled": true, "SSEType": "KMS"},
+            "Tags": [{"Key": "service", "Value": "message"}]
+        }"#;
+
+        let query = serde_json::from_str::<CreateTableQuery>(json).unwrap();
+
+        assert_eq!(Some(BillingMode::PayPerRequest), query.billing_mode());
+        assert!(query.provisioned_throughput().is_none());
+
+        let index = &query.global_secondary_indexes()[0];
+        assert_eq!("channel_id-posted_at-index", index.index_name());
+        assert_eq!(2, index.key_schemas().len());
+        assert_eq!(ProjectionType::Include, index.projection().projection_type());
+        assert_eq!(
+            &Some(vec!["message".to_string()]),
+            index.projection().non_key_attributes()
+        );
+        assert!(query.local_secondary_indexes().is_empty());
+        assert!(query.sse_specification().as_ref().unwrap().enabled());
+        assert_eq!("service", query.tags()[0].key());
+    }
+
+    #[test]
+    fn test_deserialize_provisioned_table_requires_throughput() {
+        let table = |billing_mode: &str, index_throughput: &str| {
+            format!(
+                r#"{{
+                    "TableName": "Messages",
+                    "AttributeDefinitions": [
+                        {{"AttributeName": "message_id", "AttributeType": "S"}},
+                        {{"AttributeName": "channel_id", "AttributeType": "S"}}
+                    ],
+                    "KeySchema": [{{"AttributeName": "message_id", "KeyType": "HASH"}}],
+                    {}
+                    "GlobalSecondaryIndexes": [{{
+                        "IndexName": "channel_id-index",
+                        "KeySchema": [{{"AttributeName": "channel_id", "KeyType": "HASH"}}],
+                        {}
+                        "Projection": {{"ProjectionType": "KEYS_ONLY"}}
+                    }}]
+                }}"#,
+                billing_mode, index_throughput
+            )
+        };
+        let throughput = r#""ProvisionedThroughput": {"ReadCapacityUnits": 1, "WriteCapacityUnits": 1},"#;
+
+        for billing_mode in [r#""BillingMode": "PROVISIONED","#, ""] {
+            let error = serde_json::from_str::<CreateTableQuery>(&table(billing_mode, throughput)).unwrap_err();
+            assert!(error.to_string().contains("missing field `ProvisionedThroughput`"));
+        }
+
+        let with_table_throughput = format!(r#""BillingMode": "PROVISIONED", {}"#, throughput);
+        let error = serde_json::from_str::<CreateTableQuery>(&table(&with_table_throughput, "")).unwrap_err();
+        assert!(error.to_string().contains("channel_id-index"));
+
+        let query = serde_json::from_str::<CreateTableQuery>(&table(&with_table_throughput, throughput)).unwrap();
+        assert_eq!(Some(BillingMode::Provisioned), query.billing_mode());
+        assert!(query.provisioned_throughput().is_some());
+    }
+
+    #[test]
+    fn test_serialize_leaves_out_unset_fields() {
+        let json = r#"{
+            "TableName": "Messages",
+            "AttributeDefinitions": [{"AttributeName": "message_id", "AttributeType": "S"}],
+            "KeySchema": [{"AttributeName": "message_id", "KeyType": "HASH"}],
+            "BillingMode": "PAY_PER_REQUEST"
+        }"#;
+
+        let query = serde_json::from_str::<CreateTableQuery>(json).unwrap();
+        let serialized = serde_json::to_value(&query).unwrap();
+
+        for field in [
+            "ProvisionedThroughput",
+            "GlobalSecondaryIndexes",
+            "LocalSecondaryIndexes",
+            "SSESpecification",
+            "Tags",
+        ] {
+            assert!(serialized.get(field).is_none(), "{} was serialized", field);
+        }
+        assert_eq!("PAY_PER_REQUEST", serialized["BillingMode"]);
+        serde_json::from_value::<CreateTableQuery>(serialized).unwrap();
+    }
 }
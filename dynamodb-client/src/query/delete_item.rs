@@ -0,0 +1,175 @@
+use aws_sdk_dynamodb::model::{AttributeValue, ReturnValue};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+use crate::query::attribute_value::items;
+use crate::query::dynamodb_query::{ExpressionAttributeNames, ExpressionAttributeValues, Keys};
+use crate::query::expression::Expression;
+
+#[derive(Debug, Clone)]
+pub struct DeleteItemQuery {
+    table_name: String,
+    keys: Keys,
+    condition_expression: Option<String>,
+    expression_attribute_names: ExpressionAttributeNames,
+    expression_attribute_values: ExpressionAttributeValues,
+    return_values: Option<ReturnValue>,
+}
+
+impl DeleteItemQuery {
+    pub fn new(table_name: impl Into<String>, keys: impl Into<Keys>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            keys: keys.into(),
+            condition_expression: None,
+            expression_attribute_names: ExpressionAttributeNames::new(),
+            expression_attribute_values: ExpressionAttributeValues::new(),
+            return_values: None,
+        }
+    }
+
+    /// `condition` is either a `Condition` or a raw expression string.
+    pub fn with_condition(mut self, condition: impl Into<Expression>) -> Self {
+        let condition = condition.into();
+
+        self.condition_expression = Some(condition.expression().to_string());
+        self.expression_attribute_names
+            .extend(condition.names().clone());
+        self.expression_attribute_values
+            .extend(condition.values().clone());
+        self
+    }
+
+    pub fn with_expression_attribute_name(
+        mut self,
+        placeholder: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.expression_attribute_names
+            .insert(placeholder.into(), name.into());
+        self
+    }
+
+    pub fn with_expression_attribute_value(
+        mut self,
+        placeholder: impl Into<String>,
+        value: AttributeValue,
+    ) -> Self {
+        self.expression_attribute_values
+            .insert(placeholder.into(), value);
+        self
+    }
+
+    /// Only `ReturnValue::None` and `ReturnValue::AllOld` are accepted by DeleteItem.
+    pub fn with_return_values(mut self, return_values: ReturnValue) -> Self {
+        self.return_values = Some(return_values);
+        self
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn keys(&self) -> &Keys {
+        &self.keys
+    }
+
+    pub fn condition_expression(&self) -> &Option<String> {
+        &self.condition_expression
+    }
+
+    pub fn expression_attribute_names(&self) -> &ExpressionAttributeNames {
+        &self.expression_attribute_names
+    }
+
+    pub fn expression_attribute_values(&self) -> &ExpressionAttributeValues {
+        &self.expression_attribute_values
+    }
+
+    pub fn return_values(&self) -> ReturnValue {
+        match &self.return_values {
+            Some(value) => value.clone(),
+            None => ReturnValue::None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct DeleteItemQueryJson {
+    table_name: String,
+    #[serde(with = "items")]
+    key: Keys,
+    #[serde(default)]
+    condition_expression: Option<String>,
+    #[serde(default)]
+    expression_attribute_names: ExpressionAttributeNames,
+    #[serde(default, with = "items")]
+    expression_attribute_values: ExpressionAttributeValues,
+    #[serde(default)]
+    return_values: Option<String>,
+}
+
+/// Reads the DeleteItem request format of the AWS CLI, with the key in DynamoDB JSON.
+impl<'de> Deserialize<'de> for DeleteItemQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let json = DeleteItemQueryJson::deserialize(deserializer)?;
+
+        let return_values = match json.return_values.as_deref() {
+            None => None,
+            Some(value @ ("NONE" | "ALL_OLD")) => Some(ReturnValue::from(value)),
+            Some(value) => return Err(D::Error::unknown_variant(value, &["NONE", "ALL_OLD"])),
+        };
+
+        Ok(DeleteItemQuery {
+            table_name: json.table_name,
+            keys: json.key,
+            condition_expression: json.condition_expression,
+            expression_attribute_names: json.expression_attribute_names,
+            expression_attribute_values: json.expression_attribute_values,
+            return_values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::{AttributeValue, ReturnValue};
+    use pretty_assertions::assert_eq;
+
+    use crate::query::delete_item::DeleteItemQuery;
+
+    #[test]
+    fn test_deserialize() {
+        let json = r##"{
+            "TableName": "Channels",
+            "Key": {"channel_id": {"S": "general"}},
+            "ConditionExpression": "#owner = :owner",
+            "ExpressionAttributeNames": {"#owner": "owner"},
+            "ExpressionAttributeValues": {":owner": {"S": "system"}},
+            "ReturnValues": "ALL_OLD"
+        }"##;
+
+        let query = serde_json::from_str::<DeleteItemQuery>(json).unwrap();
+
+        assert_eq!("Channels", query.table_name());
+        assert_eq!(
+            Some(&AttributeValue::S("general".to_string())),
+            query.keys().get("channel_id")
+        );
+        assert_eq!(&Some("#owner = :owner".to_string()), query.condition_expression());
+        assert_eq!(
+            Some(&AttributeValue::S("system".to_string())),
+            query.expression_attribute_values().get(":owner")
+        );
+        assert_eq!(ReturnValue::AllOld, query.return_values());
+
+        assert!(serde_json::from_str::<DeleteItemQuery>(
+            r#"{"TableName": "Channels", "Key": {}, "ReturnValues": "ALL_NEW"}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,327 @@
+use aws_sdk_dynamodb::model;
+use serde::{Deserialize, Serialize};
+
+use crate::query::dynamodb_query::{
+    parse_aws_enum, AttributeDefinition, BillingMode, KeySchema, ProvisionedThroughput,
+    StreamSpecification,
+};
+
+#[derive(Serialize, Deserialize, Debug, Default, PartialEq)]
+pub struct DescribeTableQuery {
+    #[serde(rename = "TableName")]
+    table_name: String,
+}
+
+impl DescribeTableQuery {
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn new(table_name: impl Into<String>) -> Self {
+        Self { table_name: table_name.into() }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TableStatus {
+    Creating,
+    Updating,
+    Deleting,
+    Active,
+    InaccessibleEncryptionCredentials,
+    Archiving,
+    Archived,
+    Unknown(String),
+}
+
+impl From<&model::TableStatus> for TableStatus {
+    fn from(table_status: &model::TableStatus) -> Self {
+        match table_status {
+            model::TableStatus::Creating => TableStatus::Creating,
+            model::TableStatus::Updating => TableStatus::Updating,
+            model::TableStatus::Deleting => TableStatus::Deleting,
+            model::TableStatus::Active => TableStatus::Active,
+            model::TableStatus::InaccessibleEncryptionCredentials => {
+                TableStatus::InaccessibleEncryptionCredentials
+            }
+            model::TableStatus::Archiving => TableStatus::Archiving,
+            model::TableStatus::Archived => TableStatus::Archived,
+            unknown_value => TableStatus::Unknown(unknown_value.as_str().to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum IndexStatus {
+    Creating,
+    Updating,
+    Deleting,
+    Active,
+    Unknown(String),
+}
+
+impl From<&model::IndexStatus> for IndexStatus {
+    fn from(index_status: &model::IndexStatus) -> Self {
+        match index_status {
+            model::IndexStatus::Creating => IndexStatus::Creating,
+            model::IndexStatus::Updating => IndexStatus::Updating,
+            model::IndexStatus::Deleting => IndexStatus::Deleting,
+            model::IndexStatus::Active => IndexStatus::Active,
+            unknown_value => IndexStatus::Unknown(unknown_value.as_str().to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct IndexDescription {
+    index_name: String,
+    index_status: Option<IndexStatus>,
+    key_schemas: Vec<KeySchema>,
+    provisioned_throughput: Option<ProvisionedThroughput>,
+}
+
+impl IndexDescription {
+    pub fn index_name(&self) -> &str {
+        &self.index_name
+    }
+
+    /// Some DynamoDB emulators leave the status out.
+    pub fn index_status(&self) -> &Option<IndexStatus> {
+        &self.index_status
+    }
+
+    pub fn key_schemas(&self) -> &Vec<KeySchema> {
+        &self.key_schemas
+    }
+
+    pub fn provisioned_throughput(&self) -> &Option<ProvisionedThroughput> {
+        &self.provisioned_throughput
+    }
+
+    pub fn is_active(&self) -> bool {
+        matches!(self.index_status, None | Some(IndexStatus::Active))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableDescription {
+    table_name: String,
+    table_arn: Option<String>,
+    table_status: TableStatus,
+    key_schemas: Vec<KeySchema>,
+    attribute_definitions: Vec<AttributeDefinition>,
+    billing_mode: Option<BillingMode>,
+    provisioned_throughput: Option<ProvisionedThroughput>,
+    global_secondary_indexes: Vec<IndexDescription>,
+    stream_specification: Option<StreamSpecification>,
+    latest_stream_arn: Option<String>,
+    item_count: i64,
+    table_size_bytes: i64,
+}
+
+impl TableDescription {
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn table_arn(&self) -> &Option<String> {
+        &self.table_arn
+    }
+
+    pub fn table_status(&self) -> &TableStatus {
+        &self.table_status
+    }
+
+    pub fn key_schemas(&self) -> &Vec<KeySchema> {
+        &self.key_schemas
+    }
+
+    pub fn attribute_definitions(&self) -> &Vec<AttributeDefinition> {
+        &self.attribute_definitions
+    }
+
+    pub fn billing_mode(&self) -> Option<BillingMode> {
+        self.billing_mode
+    }
+
+    pub fn provisioned_throughput(&self) -> &Option<ProvisionedThroughput> {
+        &self.provisioned_throughput
+    }
+
+    pub fn global_secondary_indexes(&self) -> &Vec<IndexDescription> {
+        &self.global_secondary_indexes
+    }
+
+    pub fn stream_specification(&self) -> &Option<StreamSpecification> {
+        &self.stream_specification
+    }
+
+    pub fn latest_stream_arn(&self) -> &Option<String> {
+        &self.latest_stream_arn
+    }
+
+    /// Approximate, refreshed by DynamoDB about every six hours.
+    pub fn item_count(&self) -> i64 {
+        self.item_count
+    }
+
+    pub fn table_size_bytes(&self) -> i64 {
+        self.table_size_bytes
+    }
+
+    /// The table and all of its global secondary indexes are `ACTIVE`.
+    pub fn is_active(&self) -> bool {
+        self.table_status == TableStatus::Active
+            && self.global_secondary_indexes.iter().all(IndexDescription::is_active)
+    }
+}
+
+impl TryFrom<&model::TableDescription> for TableDescription {
+    type Error = String;
+
+    fn try_from(description: &model::TableDescription) -> Result<Self, Self::Error> {
+        let table_name = description
+            .table_name()
+            .ok_or("TableName is missing from the table description.")?;
+
+        let global_secondary_indexes = description
+            .global_secondary_indexes()
+            .unwrap_or_default()
+            .iter()
+            .map(|index| {
+                Ok(IndexDescription {
+                    index_name: index.index_name().unwrap_or_default().to_string(),
+                    index_status: index.index_status().map(IndexStatus::from),
+                    key_schemas: key_schemas(index.key_schema())?,
+                    provisioned_throughput: index
+                        .provisioned_throughput()
+                        .map(provisioned_throughput),
+                })
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        Ok(Self {
+            table_name: table_name.to_string(),
+            table_arn: description.table_arn().map(|table_arn| table_arn.to_string()),
+            table_status: description
+                .table_status()
+                .map(TableStatus::from)
+                .unwrap_or_else(|| TableStatus::Unknown("".to_string())),
+            key_schemas: key_schemas(description.key_schema())?,
+            attribute_definitions: description
+                .attribute_definitions()
+                .unwrap_or_default()
+                .iter()
+                .map(|attribute_definition| {
+                    Ok(AttributeDefinition::new(
+                        attribute_definition.attribute_name().unwrap_or_default(),
+                        parse_enum(attribute_definition.attribute_type().map(|value| value.as_str()))?,
+                    ))
+                })
+                .collect::<Result<Vec<_>, String>>()?,
+            billing_mode: description
+                .billing_mode_summary()
+                .and_then(|summary| summary.billing_mode())
+                .map(|billing_mode| parse_enum(Some(billing_mode.as_str())))
+                .transpose()?,
+            provisioned_throughput: description
+                .provisioned_throughput()
+                .map(provisioned_throughput),
+            global_secondary_indexes,
+            stream_specification: description
+                .stream_specification()
+                .map(|stream_specification| {
+                    Ok::<_, String>(StreamSpecification::new(
+                        stream_specification.stream_enabled().unwrap_or_default(),
+                        stream_specification
+                            .stream_view_type()
+                            .map(|value| parse_enum(Some(value.as_str())))
+                            .transpose()?,
+                    ))
+                })
+                .transpose()?,
+            latest_stream_arn: description
+                .latest_stream_arn()
+                .map(|latest_stream_arn| latest_stream_arn.to_string()),
+            item_count: description.item_count(),
+            table_size_bytes: description.table_size_bytes(),
+        })
+    }
+}
+
+fn parse_enum<T: serde::de::DeserializeOwned>(value: Option<&str>) -> Result<T, String> {
+    let value = value.ok_or("Enum value is missing from the table description.")?;
+
+    parse_aws_enum(value).map_err(|error| error.to_string())
+}
+
+fn key_schemas(key_schemas: Option<&[model::KeySchemaElement]>) -> Result<Vec<KeySchema>, String> {
+    key_schemas
+        .unwrap_or_default()
+        .iter()
+        .map(|key_schema| {
+            Ok(KeySchema::new(
+                key_schema.attribute_name().unwrap_or_default(),
+                parse_enum(key_schema.key_type().map(|value| value.as_str()))?,
+            ))
+        })
+        .collect()
+}
+
+fn provisioned_throughput(
+    provisioned_throughput: &model::ProvisionedThroughputDescription,
+) -> ProvisionedThroughput {
+    ProvisionedThroughput::new(
+        provisioned_throughput.read_capacity_units().unwrap_or_default(),
+        provisioned_throughput.write_capacity_units().unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model;
+    use pretty_assertions::assert_eq;
+
+    use crate::query::describe_table::{TableDescription, TableStatus};
+    use crate::query::dynamodb_query::{KeySchema, KeyType, StreamSpecification, StreamViewType};
+
+    #[test]
+    fn test_try_from_aws_description() {
+        let aws_description = model::TableDescription::builder()
+            .table_name("Messages")
+            .table_status(model::TableStatus::Creating)
+            .key_schema(
+                model::KeySchemaElement::builder()
+                    .attribute_name("message_id")
+                    .key_type(model::KeyType::Hash)
+                    .build(),
+            )
+            .global_secondary_indexes(
+                model::GlobalSecondaryIndexDescription::builder()
+                    .index_name("channel_id-index")
+                    .index_status(model::IndexStatus::Active)
+                    .build(),
+            )
+            .stream_specification(
+                model::StreamSpecification::builder()
+                    .stream_enabled(true)
+                    .stream_view_type(model::StreamViewType::NewAndOldImages)
+                    .build(),
+            )
+            .build();
+
+        let description = TableDescription::try_from(&aws_description).unwrap();
+
+        assert_eq!("Messages", description.table_name());
+        assert_eq!(&TableStatus::Creating, description.table_status());
+        assert_eq!(
+            &vec![KeySchema::new("message_id", KeyType::Hash)],
+            description.key_schemas()
+        );
+        assert_eq!(
+            &Some(StreamSpecification::new(true, Some(StreamViewType::NewAndOldImages))),
+            description.stream_specification()
+        );
+        assert!(!description.is_active());
+    }
+}
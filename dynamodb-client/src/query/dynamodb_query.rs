@@ -1,7 +1,129 @@
-use aws_sdk_dynamodb::model::{KeyType, ScalarAttributeType, StreamViewType};
+use aws_sdk_dynamodb::model;
+use aws_sdk_dynamodb::model::AttributeValue;
+use serde::de::value::{Error as ValueError, StrDeserializer};
+use serde::de::{DeserializeOwned, IntoDeserializer};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Serialize, Deserialize, Debug, Default)]
+pub type Keys = HashMap<String, AttributeValue>;
+
+pub type ExpressionAttributeNames = HashMap<String, String>;
+
+pub type ExpressionAttributeValues = HashMap<String, AttributeValue>;
+
+/// Enums below accept the spellings of the AWS API (e.g. `NEW_AND_OLD_IMAGES`) and nothing else,
+/// and serialize back to them. They convert into the matching `aws_sdk_dynamodb::model` types.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum KeyType {
+    Hash,
+    Range,
+}
+
+impl From<KeyType> for model::KeyType {
+    fn from(key_type: KeyType) -> Self {
+        match key_type {
+            KeyType::Hash => model::KeyType::Hash,
+            KeyType::Range => model::KeyType::Range,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarAttributeType {
+    S,
+    N,
+    B,
+}
+
+impl From<ScalarAttributeType> for model::ScalarAttributeType {
+    fn from(attribute_type: ScalarAttributeType) -> Self {
+        match attribute_type {
+            ScalarAttributeType::S => model::ScalarAttributeType::S,
+            ScalarAttributeType::N => model::ScalarAttributeType::N,
+            ScalarAttributeType::B => model::ScalarAttributeType::B,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StreamViewType {
+    KeysOnly,
+    NewImage,
+    OldImage,
+    NewAndOldImages,
+}
+
+impl From<StreamViewType> for model::StreamViewType {
+    fn from(stream_view_type: StreamViewType) -> Self {
+        match stream_view_type {
+            StreamViewType::KeysOnly => model::StreamViewType::KeysOnly,
+            StreamViewType::NewImage => model::StreamViewType::NewImage,
+            StreamViewType::OldImage => model::StreamViewType::OldImage,
+            StreamViewType::NewAndOldImages => model::StreamViewType::NewAndOldImages,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ProjectionType {
+    All,
+    KeysOnly,
+    Include,
+}
+
+impl From<ProjectionType> for model::ProjectionType {
+    fn from(projection_type: ProjectionType) -> Self {
+        match projection_type {
+            ProjectionType::All => model::ProjectionType::All,
+            ProjectionType::KeysOnly => model::ProjectionType::KeysOnly,
+            ProjectionType::Include => model::ProjectionType::Include,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum BillingMode {
+    Provisioned,
+    PayPerRequest,
+}
+
+impl From<BillingMode> for model::BillingMode {
+    fn from(billing_mode: BillingMode) -> Self {
+        match billing_mode {
+            BillingMode::Provisioned => model::BillingMode::Provisioned,
+            BillingMode::PayPerRequest => model::BillingMode::PayPerRequest,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum SseType {
+    Aes256,
+    Kms,
+}
+
+impl From<SseType> for model::SseType {
+    fn from(sse_type: SseType) -> Self {
+        match sse_type {
+            SseType::Aes256 => model::SseType::Aes256,
+            SseType::Kms => model::SseType::Kms,
+        }
+    }
+}
+
+/// Parses an enum value returned by DynamoDB, e.g. `model::KeyType::as_str()`, into the matching enum here.
+pub fn parse_aws_enum<T: DeserializeOwned>(value: &str) -> Result<T, ValueError> {
+    let deserializer: StrDeserializer<ValueError> = value.into_deserializer();
+
+    T::deserialize(deserializer)
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
 pub struct ProvisionedThroughput {
     #[serde(rename = "ReadCapacityUnits")]
     read_capacity_units: i64,
@@ -9,60 +131,64 @@ pub struct ProvisionedThroughput {
     write_capacity_units: i64,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct KeySchema {
     #[serde(rename = "AttributeName")]
     attribute_name: String,
     #[serde(rename = "KeyType")]
-    key_type: String,
+    key_type: KeyType,
 }
 
-#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct AttributeDefinition {
     #[serde(rename = "AttributeName")]
     attribute_name: String,
     #[serde(rename = "AttributeType")]
-    attribute_type: String,
+    attribute_type: ScalarAttributeType,
 }
 
 impl KeySchema {
+    pub fn new(attribute_name: impl Into<String>, key_type: KeyType) -> Self {
+        Self {
+            attribute_name: attribute_name.into(),
+            key_type,
+        }
+    }
+
     pub fn attribute_name(&self) -> &str {
         &self.attribute_name
     }
 
     pub fn key_type(&self) -> KeyType {
-        match &self.key_type {
-            _ if &self.key_type.to_string() == "HASH" => KeyType::Hash,
-            _ if &self.key_type.to_string() == "RANGE" => KeyType::Range,
-            _ => {
-                let name = &self.key_type.to_string();
-
-                KeyType::Unknown(name.clone())
-            }
-        }
+        self.key_type
     }
 }
 
 impl AttributeDefinition {
+    pub fn new(attribute_name: impl Into<String>, attribute_type: ScalarAttributeType) -> Self {
+        Self {
+            attribute_name: attribute_name.into(),
+            attribute_type,
+        }
+    }
+
     pub fn attribute_name(&self) -> &str {
         &self.attribute_name
     }
 
     pub fn attribute_type(&self) -> ScalarAttributeType {
-        match &self.attribute_type {
-            _ if &self.attribute_type.to_string() == "S" => ScalarAttributeType::S,
-            _ if &self.attribute_type.to_string() == "N" => ScalarAttributeType::N,
-            _ if &self.attribute_type.to_string() == "B" => ScalarAttributeType::B,
-            _ => {
-                let name = &self.attribute_type.to_string();
-
-                ScalarAttributeType::Unknown(name.clone())
-            }
-        }
+        self.attribute_type
     }
 }
 
 impl ProvisionedThroughput {
+    pub fn new(read_capacity_units: i64, write_capacity_units: i64) -> Self {
+        Self {
+            read_capacity_units,
+            write_capacity_units,
+        }
+    }
+
     pub fn read_capacity_units(&self) -> &i64 {
         &self.read_capacity_units
     }
@@ -72,30 +198,220 @@ impl ProvisionedThroughput {
     }
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
 pub struct StreamSpecification {
     #[serde(rename = "StreamEnabled")]
     stream_enabled: Option<bool>,
     #[serde(rename = "StreamViewType")]
-    stream_view_type: Option<String>,
+    #[serde(default)]
+    stream_view_type: Option<StreamViewType>,
 }
 
 impl StreamSpecification {
+    pub fn new(stream_enabled: bool, stream_view_type: Option<StreamViewType>) -> Self {
+        Self {
+            stream_enabled: Some(stream_enabled),
+            stream_view_type,
+        }
+    }
+
     pub fn stream_enabled(&self) -> bool {
         self.stream_enabled == Some(true)
     }
 
     pub fn stream_view_type(&self) -> Option<StreamViewType> {
-        let option = &self.stream_view_type;
-
-        option
-            .as_ref()
-            .map(|x| match x.as_str() {
-                "KeysOnly"          => StreamViewType::KeysOnly,
-                "NewAndOldImages"   => StreamViewType::NewAndOldImages,
-                "NewImage"          => StreamViewType::NewImage,
-                "OldImage"          => StreamViewType::OldImage,
-                unknown_value=> StreamViewType::Unknown(unknown_value.to_string())
-            })
+        self.stream_view_type
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Projection {
+    #[serde(rename = "ProjectionType")]
+    projection_type: ProjectionType,
+    #[serde(rename = "NonKeyAttributes")]
+    #[serde(default)]
+    non_key_attributes: Option<Vec<String>>,
+}
+
+impl Projection {
+    pub fn new(projection_type: ProjectionType, non_key_attributes: Option<Vec<String>>) -> Self {
+        Self {
+            projection_type,
+            non_key_attributes,
+        }
+    }
+
+    pub fn projection_type(&self) -> ProjectionType {
+        self.projection_type
+    }
+
+    /// Only used with the `INCLUDE` projection type.
+    pub fn non_key_attributes(&self) -> &Option<Vec<String>> {
+        &self.non_key_attributes
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct GlobalSecondaryIndex {
+    #[serde(rename = "IndexName")]
+    index_name: String,
+    #[serde(rename = "KeySchema")]
+    key_schemas: Vec<KeySchema>,
+    #[serde(rename = "Projection")]
+    projection: Projection,
+    #[serde(rename = "ProvisionedThroughput")]
+    #[serde(default)]
+    provisioned_throughput: Option<ProvisionedThroughput>,
+}
+
+impl GlobalSecondaryIndex {
+    pub fn new(index_name: impl Into<String>, key_schemas: Vec<KeySchema>, projection: Projection) -> Self {
+        Self {
+            index_name: index_name.into(),
+            key_schemas,
+            projection,
+            provisioned_throughput: None,
+        }
+    }
+
+    pub fn with_provisioned_throughput(mut self, provisioned_throughput: ProvisionedThroughput) -> Self {
+        self.provisioned_throughput = Some(provisioned_throughput);
+        self
+    }
+
+    pub fn index_name(&self) -> &str {
+        &self.index_name
+    }
+
+    pub fn key_schemas(&self) -> &Vec<KeySchema> {
+        &self.key_schemas
+    }
+
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
+
+    /// Omitted when the table uses `PAY_PER_REQUEST` billing.
+    pub fn provisioned_throughput(&self) -> &Option<ProvisionedThroughput> {
+        &self.provisioned_throughput
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct LocalSecondaryIndex {
+    #[serde(rename = "IndexName")]
+    index_name: String,
+    #[serde(rename = "KeySchema")]
+    key_schemas: Vec<KeySchema>,
+    #[serde(rename = "Projection")]
+    projection: Projection,
+}
+
+impl LocalSecondaryIndex {
+    pub fn index_name(&self) -> &str {
+        &self.index_name
+    }
+
+    pub fn key_schemas(&self) -> &Vec<KeySchema> {
+        &self.key_schemas
+    }
+
+    pub fn projection(&self) -> &Projection {
+        &self.projection
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct SseSpecification {
+    #[serde(rename = "Enabled")]
+    #[serde(default)]
+    enabled: Option<bool>,
+    #[serde(rename = "SSEType")]
+    #[serde(default)]
+    sse_type: Option<SseType>,
+    #[serde(rename = "KMSMasterKeyId")]
+    #[serde(default)]
+    kms_master_key_id: Option<String>,
+}
+
+impl SseSpecification {
+    pub fn enabled(&self) -> bool {
+        self.enabled == Some(true)
+    }
+
+    pub fn sse_type(&self) -> Option<SseType> {
+        self.sse_type
+    }
+
+    pub fn kms_master_key_id(&self) -> &Option<String> {
+        &self.kms_master_key_id
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct Tag {
+    #[serde(rename = "Key")]
+    key: String,
+    #[serde(rename = "Value")]
+    value: String,
+}
+
+impl Tag {
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::query::dynamodb_query::{
+        BillingMode, KeySchema, SseType, StreamSpecification, StreamViewType,
+    };
+
+    #[test]
+    fn test_deserialize_aws_spellings() {
+        let stream_specification = serde_json::from_str::<StreamSpecification>(
+            r#"{"StreamEnabled": true, "StreamViewType": "NEW_AND_OLD_IMAGES"}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            Some(StreamViewType::NewAndOldImages),
+            stream_specification.stream_view_type()
+        );
+        assert_eq!(
+            BillingMode::PayPerRequest,
+            serde_json::from_str::<BillingMode>(r#""PAY_PER_REQUEST""#).unwrap()
+        );
+        assert_eq!(SseType::Aes256, serde_json::from_str::<SseType>(r#""AES256""#).unwrap());
+    }
+
+    #[test]
+    fn test_deserialize_rejects_unknown_values() {
+        let error = serde_json::from_str::<StreamSpecification>(
+            r#"{"StreamEnabled": true, "StreamViewType": "NewAndOldImages"}"#,
+        )
+        .unwrap_err();
+
+        assert!(error.to_string().contains("unknown variant `NewAndOldImages`"));
+        assert!(serde_json::from_str::<KeySchema>(
+            r#"{"AttributeName": "id", "KeyType": "hash"}"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_serialize_canonical_form() {
+        assert_eq!(
+            r#""NEW_AND_OLD_IMAGES""#,
+            serde_json::to_string(&StreamViewType::NewAndOldImages).unwrap()
+        );
+        assert_eq!(r#""KEYS_ONLY""#, serde_json::to_string(&StreamViewType::KeysOnly).unwrap());
     }
 }
@@ -0,0 +1,64 @@
+use aws_sdk_dynamodb::model::AttributeValue;
+
+/// A PartiQL statement, e.g. `SELECT * FROM Messages WHERE message_id = ?`, with one
+/// parameter per `?` in order.
+#[derive(Debug, Clone, Default)]
+pub struct ExecuteStatementQuery {
+    statement: String,
+    parameters: Vec<AttributeValue>,
+    consistent_read: bool,
+    limit: Option<i32>,
+    next_token: Option<String>,
+}
+
+impl ExecuteStatementQuery {
+    pub fn new(statement: impl Into<String>) -> Self {
+        Self {
+            statement: statement.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Binds the next `?` of the statement.
+    pub fn with_parameter(mut self, parameter: AttributeValue) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    pub fn with_consistent_read(mut self, consistent_read: bool) -> Self {
+        self.consistent_read = consistent_read;
+        self
+    }
+
+    /// Items evaluated per page, before any filter of the statement is applied.
+    pub fn with_limit(mut self, limit: i32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Continues a SELECT from the `NextToken` of the previous page.
+    pub fn with_next_token(mut self, next_token: impl Into<String>) -> Self {
+        self.next_token = Some(next_token.into());
+        self
+    }
+
+    pub fn statement(&self) -> &str {
+        &self.statement
+    }
+
+    pub fn parameters(&self) -> &Vec<AttributeValue> {
+        &self.parameters
+    }
+
+    pub fn consistent_read(&self) -> &bool {
+        &self.consistent_read
+    }
+
+    pub fn limit(&self) -> &Option<i32> {
+        &self.limit
+    }
+
+    pub fn next_token(&self) -> &Option<String> {
+        &self.next_token
+    }
+}
@@ -0,0 +1,409 @@
+use aws_sdk_dynamodb::model::AttributeValue;
+
+use crate::query::dynamodb_query::{ExpressionAttributeNames, ExpressionAttributeValues};
+
+/// A rendered expression together with the placeholders it refers to.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Expression {
+    expression: String,
+    names: ExpressionAttributeNames,
+    values: ExpressionAttributeValues,
+}
+
+impl Expression {
+    pub fn new(
+        expression: impl Into<String>,
+        names: ExpressionAttributeNames,
+        values: ExpressionAttributeValues,
+    ) -> Self {
+        Self {
+            expression: expression.into(),
+            names,
+            values,
+        }
+    }
+
+    pub fn expression(&self) -> &str {
+        &self.expression
+    }
+
+    pub fn names(&self) -> &ExpressionAttributeNames {
+        &self.names
+    }
+
+    pub fn values(&self) -> &ExpressionAttributeValues {
+        &self.values
+    }
+}
+
+/// Raw expressions keep working for callers who manage their own placeholders.
+impl From<&str> for Expression {
+    fn from(expression: &str) -> Self {
+        Self::new(expression, ExpressionAttributeNames::new(), ExpressionAttributeValues::new())
+    }
+}
+
+impl From<String> for Expression {
+    fn from(expression: String) -> Self {
+        Self::new(expression, ExpressionAttributeNames::new(), ExpressionAttributeValues::new())
+    }
+}
+
+/// Hands out `#{prefix}N` / `:{prefix}N` placeholders. Update and condition expressions use
+/// different prefixes so both can be merged into the same request.
+#[derive(Debug)]
+struct Placeholders {
+    prefix: &'static str,
+    names: ExpressionAttributeNames,
+    values: ExpressionAttributeValues,
+}
+
+impl Placeholders {
+    fn new(prefix: &'static str) -> Self {
+        Self {
+            prefix,
+            names: ExpressionAttributeNames::new(),
+            values: ExpressionAttributeValues::new(),
+        }
+    }
+
+    fn name(&mut self, attribute_name: &str) -> String {
+        if let Some((placeholder, _)) = self
+            .names
+            .iter()
+            .find(|(_, name)| name.as_str() == attribute_name)
+        {
+            return placeholder.clone();
+        }
+
+        let placeholder = format!("#{}{}", self.prefix, self.names.len());
+        self.names
+            .insert(placeholder.clone(), attribute_name.to_string());
+
+        placeholder
+    }
+
+    fn value(&mut self, value: AttributeValue) -> String {
+        let placeholder = format!(":{}{}", self.prefix, self.values.len());
+        self.values.insert(placeholder.clone(), value);
+
+        placeholder
+    }
+
+    fn finish(self, expression: String) -> Expression {
+        Expression::new(expression, self.names, self.values)
+    }
+}
+
+#[derive(Debug, Clone)]
+enum SetAction {
+    Assign(String, AttributeValue),
+    IfNotExists(String, AttributeValue),
+}
+
+/// Builds a DynamoDB update expression out of SET, REMOVE, ADD and DELETE actions.
+#[derive(Debug, Clone, Default)]
+pub struct UpdateExpression {
+    set: Vec<SetAction>,
+    remove: Vec<String>,
+    add: Vec<(String, AttributeValue)>,
+    delete: Vec<(String, AttributeValue)>,
+}
+
+impl UpdateExpression {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set(mut self, name: impl Into<String>, value: AttributeValue) -> Self {
+        self.set.push(SetAction::Assign(name.into(), value));
+        self
+    }
+
+    /// `SET name = if_not_exists(name, value)`, keeps the stored value when there is one.
+    pub fn set_if_not_exists(mut self, name: impl Into<String>, value: AttributeValue) -> Self {
+        self.set.push(SetAction::IfNotExists(name.into(), value));
+        self
+    }
+
+    pub fn remove(mut self, name: impl Into<String>) -> Self {
+        self.remove.push(name.into());
+        self
+    }
+
+    /// Adds to a number or adds elements to a set.
+    pub fn add(mut self, name: impl Into<String>, value: AttributeValue) -> Self {
+        self.add.push((name.into(), value));
+        self
+    }
+
+    /// Removes elements from a set.
+    pub fn delete(mut self, name: impl Into<String>, value: AttributeValue) -> Self {
+        self.delete.push((name.into(), value));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.set.is_empty() && self.remove.is_empty() && self.add.is_empty() && self.delete.is_empty()
+    }
+
+    /// Whether any action of the expression writes or removes the attribute `name`.
+    pub fn touches(&self, name: &str) -> bool {
+        self.set.iter().any(|action| match action {
+            SetAction::Assign(set_name, _) | SetAction::IfNotExists(set_name, _) => set_name == name,
+        }) || self.remove.iter().any(|remove_name| remove_name == name)
+            || self.add.iter().chain(&self.delete).any(|(action_name, _)| action_name == name)
+    }
+
+    pub fn build(self) -> Expression {
+        let mut placeholders = Placeholders::new("u");
+        let mut clauses = vec![];
+
+        if !self.set.is_empty() {
+            let actions = self
+                .set
+                .into_iter()
+                .map(|action| match action {
+                    SetAction::Assign(name, value) => {
+                        format!("{} = {}", placeholders.name(&name), placeholders.value(value))
+                    }
+                    SetAction::IfNotExists(name, value) => {
+                        let name = placeholders.name(&name);
+
+                        format!("{} = if_not_exists({}, {})", name, name, placeholders.value(value))
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            clauses.push(format!("SET {}", actions.join(", ")));
+        }
+
+        if !self.remove.is_empty() {
+            let actions = self
+                .remove
+                .iter()
+                .map(|name| placeholders.name(name))
+                .collect::<Vec<_>>();
+
+            clauses.push(format!("REMOVE {}", actions.join(", ")));
+        }
+
+        for (keyword, actions) in [("ADD", self.add), ("DELETE", self.delete)] {
+            if actions.is_empty() {
+                continue;
+            }
+
+            let actions = actions
+                .into_iter()
+                .map(|(name, value)| format!("{} {}", placeholders.name(&name), placeholders.value(value)))
+                .collect::<Vec<_>>();
+
+            clauses.push(format!("{} {}", keyword, actions.join(", ")));
+        }
+
+        placeholders.finish(clauses.join(" "))
+    }
+}
+
+impl From<UpdateExpression> for Expression {
+    fn from(update: UpdateExpression) -> Self {
+        update.build()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparator {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl Comparator {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Comparator::Eq => "=",
+            Comparator::Ne => "<>",
+            Comparator::Lt => "<",
+            Comparator::Le => "<=",
+            Comparator::Gt => ">",
+            Comparator::Ge => ">=",
+        }
+    }
+}
+
+/// Condition expressions for put, update and delete items and transaction condition checks.
+/// Query and scan take their key condition and filter expressions as raw strings.
+#[derive(Debug, Clone)]
+pub enum Condition {
+    AttributeExists(String),
+    AttributeNotExists(String),
+    Compare(String, Comparator, AttributeValue),
+    Between(String, AttributeValue, AttributeValue),
+    BeginsWith(String, AttributeValue),
+    Contains(String, AttributeValue),
+    And(Box<Condition>, Box<Condition>),
+    Or(Box<Condition>, Box<Condition>),
+    Not(Box<Condition>),
+}
+
+impl Condition {
+    pub fn attribute_exists(name: impl Into<String>) -> Self {
+        Condition::AttributeExists(name.into())
+    }
+
+    pub fn attribute_not_exists(name: impl Into<String>) -> Self {
+        Condition::AttributeNotExists(name.into())
+    }
+
+    pub fn eq(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Eq, value)
+    }
+
+    pub fn ne(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Ne, value)
+    }
+
+    pub fn lt(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Lt, value)
+    }
+
+    pub fn le(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Le, value)
+    }
+
+    pub fn gt(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Gt, value)
+    }
+
+    pub fn ge(name: impl Into<String>, value: AttributeValue) -> Self {
+        Condition::Compare(name.into(), Comparator::Ge, value)
+    }
+
+    pub fn between(name: impl Into<String>, low: AttributeValue, high: AttributeValue) -> Self {
+        Condition::Between(name.into(), low, high)
+    }
+
+    pub fn begins_with(name: impl Into<String>, prefix: AttributeValue) -> Self {
+        Condition::BeginsWith(name.into(), prefix)
+    }
+
+    pub fn contains(name: impl Into<String>, operand: AttributeValue) -> Self {
+        Condition::Contains(name.into(), operand)
+    }
+
+    pub fn and(self, other: Condition) -> Self {
+        Condition::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Condition) -> Self {
+        Condition::Or(Box::new(self), Box::new(other))
+    }
+
+    pub fn build(self) -> Expression {
+        let mut placeholders = Placeholders::new("c");
+        let expression = self.render(&mut placeholders);
+
+        placeholders.finish(expression)
+    }
+
+    fn render(self, placeholders: &mut Placeholders) -> String {
+        match self {
+            Condition::AttributeExists(name) => {
+                format!("attribute_exists({})", placeholders.name(&name))
+            }
+            Condition::AttributeNotExists(name) => {
+                format!("attribute_not_exists({})", placeholders.name(&name))
+            }
+            Condition::Compare(name, comparator, value) => format!(
+                "{} {} {}",
+                placeholders.name(&name),
+                comparator.as_str(),
+                placeholders.value(value)
+            ),
+            Condition::Between(name, low, high) => format!(
+                "{} BETWEEN {} AND {}",
+                placeholders.name(&name),
+                placeholders.value(low),
+                placeholders.value(high)
+            ),
+            Condition::BeginsWith(name, prefix) => format!(
+                "begins_with({}, {})",
+                placeholders.name(&name),
+                placeholders.value(prefix)
+            ),
+            Condition::Contains(name, operand) => format!(
+                "contains({}, {})",
+                placeholders.name(&name),
+                placeholders.value(operand)
+            ),
+            Condition::And(left, right) => format!(
+                "({}) AND ({})",
+                left.render(placeholders),
+                right.render(placeholders)
+            ),
+            Condition::Or(left, right) => format!(
+                "({}) OR ({})",
+                left.render(placeholders),
+                right.render(placeholders)
+            ),
+            Condition::Not(condition) => format!("NOT ({})", condition.render(placeholders)),
+        }
+    }
+}
+
+impl std::ops::Not for Condition {
+    type Output = Condition;
+
+    fn not(self) -> Self::Output {
+        Condition::Not(Box::new(self))
+    }
+}
+
+impl From<Condition> for Expression {
+    fn from(condition: Condition) -> Self {
+        condition.build()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use pretty_assertions::assert_eq;
+
+    use crate::query::expression::{Condition, UpdateExpression};
+
+    #[test]
+    fn test_update_expression() {
+        let expression = UpdateExpression::new()
+            .set("message", AttributeValue::S("edited".to_string()))
+            .set_if_not_exists("edited_at", AttributeValue::S("2023-03-01".to_string()))
+            .remove("draft")
+            .add("edit_count", AttributeValue::N("1".to_string()))
+            .delete("tags", AttributeValue::Ss(vec!["old".to_string()]))
+            .build();
+
+        assert_eq!(
+            "SET #u0 = :u0, #u1 = if_not_exists(#u1, :u1) REMOVE #u2 ADD #u3 :u2 DELETE #u4 :u3",
+            expression.expression()
+        );
+        assert_eq!(Some(&"edited_at".to_string()), expression.names().get("#u1"));
+        assert_eq!(Some(&AttributeValue::N("1".to_string())), expression.values().get(":u2"));
+    }
+
+    #[test]
+    fn test_condition_reuses_name_placeholders() {
+        let expression = Condition::attribute_not_exists("message_id")
+            .or(!Condition::eq("message_id", AttributeValue::S("1".to_string())))
+            .build();
+
+        assert_eq!(
+            "(attribute_not_exists(#c0)) OR (NOT (#c0 = :c0))",
+            expression.expression()
+        );
+        assert_eq!(1, expression.names().len());
+        assert_eq!(1, expression.values().len());
+    }
+}
@@ -1,5 +1,7 @@
 use aws_sdk_dynamodb::model::AttributeValue;
 
+use crate::query::dynamodb_query::Keys;
+
 #[derive(Debug)]
 pub struct GetItemQuery {
     table_name: String,
@@ -48,3 +50,9 @@ impl Key {
         Self { name: name.into(), value }
     }
 }
+
+impl From<Key> for Keys {
+    fn from(key: Key) -> Self {
+        Keys::from([(key.name, key.value)])
+    }
+}
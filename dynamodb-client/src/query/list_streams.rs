@@ -0,0 +1,42 @@
+#[derive(Debug, Default, Clone)]
+pub struct ListStreamsQuery {
+    table_name: Option<String>,
+    limit: Option<i32>,
+    exclusive_start_stream_arn: Option<String>,
+}
+
+impl ListStreamsQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only streams of this table. Every stream of the account otherwise.
+    pub fn with_table_name(mut self, table_name: impl Into<String>) -> Self {
+        self.table_name = Some(table_name.into());
+        self
+    }
+
+    /// Streams per page, at most 100.
+    pub fn with_limit(mut self, limit: i32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Starts after this stream, i.e. the `LastEvaluatedStreamArn` of the previous page.
+    pub fn with_exclusive_start_stream_arn(mut self, stream_arn: impl Into<String>) -> Self {
+        self.exclusive_start_stream_arn = Some(stream_arn.into());
+        self
+    }
+
+    pub fn table_name(&self) -> &Option<String> {
+        &self.table_name
+    }
+
+    pub fn limit(&self) -> &Option<i32> {
+        &self.limit
+    }
+
+    pub fn exclusive_start_stream_arn(&self) -> &Option<String> {
+        &self.exclusive_start_stream_arn
+    }
+}
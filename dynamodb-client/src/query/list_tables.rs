@@ -1,2 +1,31 @@
-#[derive(Debug, Default)]
-pub struct ListTablesQuery {}
+#[derive(Debug, Default, Clone)]
+pub struct ListTablesQuery {
+    limit: Option<i32>,
+    exclusive_start_table_name: Option<String>,
+}
+
+impl ListTablesQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Table names per page, at most 100.
+    pub fn with_limit(mut self, limit: i32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Starts after this table, i.e. the `LastEvaluatedTableName` of the previous page.
+    pub fn with_exclusive_start_table_name(mut self, table_name: impl Into<String>) -> Self {
+        self.exclusive_start_table_name = Some(table_name.into());
+        self
+    }
+
+    pub fn limit(&self) -> &Option<i32> {
+        &self.limit
+    }
+
+    pub fn exclusive_start_table_name(&self) -> &Option<String> {
+        &self.exclusive_start_table_name
+    }
+}
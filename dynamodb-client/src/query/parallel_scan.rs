@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::query::attribute_value::items;
+use crate::query::dynamodb_query::Keys;
+use crate::query::put_item::Items;
+use crate::query::scan::ScanQuery;
+
+/// Progress of one segment. Persist it with `serde` to resume a `ParallelScanQuery` later.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SegmentCheckpoint {
+    /// The LastEvaluatedKey of the last page that was read.
+    InProgress(#[serde(with = "items")] Keys),
+    Completed,
+}
+
+/// Checkpoint per segment. Segments without one are scanned from the beginning.
+pub type ScanCheckpoints = HashMap<i32, SegmentCheckpoint>;
+
+#[derive(Debug, Clone)]
+pub struct ParallelScanQuery {
+    scan: ScanQuery,
+    total_segments: i32,
+    checkpoints: ScanCheckpoints,
+    read_capacity_per_second: Option<f64>,
+}
+
+impl ParallelScanQuery {
+    /// Scans with `total_segments` workers. Any segment set on `scan` is ignored.
+    pub fn new(scan: ScanQuery, total_segments: i32) -> Self {
+        Self {
+            scan,
+            total_segments: total_segments.max(1),
+            checkpoints: ScanCheckpoints::new(),
+            read_capacity_per_second: None,
+        }
+    }
+
+    /// Resumes from checkpoints taken with the same `total_segments`.
+    pub fn with_checkpoints(mut self, checkpoints: ScanCheckpoints) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    /// Caps the read capacity consumed by all segments together.
+    /// A rate that is not finite and positive leaves the scan unlimited.
+    pub fn with_read_capacity_per_second(mut self, read_capacity_per_second: f64) -> Self {
+        self.read_capacity_per_second = (read_capacity_per_second.is_finite() && read_capacity_per_second > 0.0)
+            .then_some(read_capacity_per_second);
+        self
+    }
+
+    pub fn scan(&self) -> &ScanQuery {
+        &self.scan
+    }
+
+    pub fn total_segments(&self) -> i32 {
+        self.total_segments
+    }
+
+    pub fn checkpoints(&self) -> &ScanCheckpoints {
+        &self.checkpoints
+    }
+
+    pub fn read_capacity_per_second(&self) -> &Option<f64> {
+        &self.read_capacity_per_second
+    }
+
+    /// The query of every segment that is not completed yet, with the key to start after.
+    pub fn segments(&self) -> Vec<(ScanQuery, Option<Keys>)> {
+        (0..self.total_segments)
+            .filter_map(|segment| {
+                let exclusive_start_key = match self.checkpoints.get(&segment) {
+                    Some(SegmentCheckpoint::Completed) => return None,
+                    Some(SegmentCheckpoint::InProgress(keys)) => Some(keys.clone()),
+                    None => None,
+                };
+
+                Some((
+                    self.scan.clone().with_segment(segment, self.total_segments),
+                    exclusive_start_key,
+                ))
+            })
+            .collect()
+    }
+}
+
+/// One page of a segment. Store `checkpoint` once `items` have been processed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScanPage {
+    segment: i32,
+    items: Vec<Items>,
+    checkpoint: SegmentCheckpoint,
+}
+
+impl ScanPage {
+    pub fn new(segment: i32, items: Vec<Items>, checkpoint: SegmentCheckpoint) -> Self {
+        Self {
+            segment,
+            items,
+            checkpoint,
+        }
+    }
+
+    pub fn segment(&self) -> i32 {
+        self.segment
+    }
+
+    pub fn items(&self) -> &Vec<Items> {
+        &self.items
+    }
+
+    pub fn checkpoint(&self) -> &SegmentCheckpoint {
+        &self.checkpoint
+    }
+
+    pub fn into_items(self) -> Vec<Items> {
+        self.items
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use pretty_assertions::assert_eq;
+
+    use crate::query::parallel_scan::{ParallelScanQuery, ScanCheckpoints, SegmentCheckpoint};
+    use crate::query::scan::ScanQuery;
+
+    #[test]
+    fn test_segments_skip_completed_and_resume_in_progress() {
+        let keys = HashMap::from([("message_id".to_string(), AttributeValue::S("1".to_string()))]);
+        let checkpoints = ScanCheckpoints::from([
+            (0, SegmentCheckpoint::Completed),
+            (1, SegmentCheckpoint::InProgress(keys.clone())),
+        ]);
+
+        let segments = ParallelScanQuery::new(ScanQuery::new("Messages"), 3)
+            .with_checkpoints(checkpoints.clone())
+            .segments();
+
+        assert_eq!(
+            vec![(Some(1), Some(keys)), (Some(2), None)],
+            segments
+                .into_iter()
+                .map(|(scan, exclusive_start_key)| (*scan.segment(), exclusive_start_key))
+                .collect::<Vec<_>>()
+        );
+
+        let json = serde_json::to_string(&checkpoints).unwrap();
+        assert_eq!(checkpoints, serde_json::from_str::<ScanCheckpoints>(&json).unwrap());
+    }
+
+    #[test]
+    fn test_invalid_read_capacity_is_unlimited() {
+        for rate in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            let query =
+                ParallelScanQuery::new(ScanQuery::new("Messages"), 2).with_read_capacity_per_second(rate);
+
+            assert_eq!(&None, query.read_capacity_per_second());
+        }
+
+        let query = ParallelScanQuery::new(ScanQuery::new("Messages"), 2).with_read_capacity_per_second(10.0);
+        assert_eq!(&Some(10.0), query.read_capacity_per_second());
+    }
+}
@@ -1,22 +1,20 @@
 use aws_sdk_dynamodb::model::{AttributeValue, ReturnValue};
-use serde::{Deserialize, Deserializer};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 
-/*
-#[derive(Deserialize, Debug)]
-pub struct PutItemQuery {
-    #[serde(rename = "TableName")]
-    table_name: String,
-    #[serde(rename = "Items")]
-    items: Items
-}*/
+use crate::query::attribute_value::items;
+use crate::query::dynamodb_query::{ExpressionAttributeNames, ExpressionAttributeValues};
+use crate::query::expression::Expression;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct PutItemQuery {
     table_name: String,
     items: Items,
     return_values: Option<ReturnValue>,
     condition_expression: Option<String>,
+    expression_attribute_names: ExpressionAttributeNames,
+    expression_attribute_values: ExpressionAttributeValues,
 }
 
 impl PutItemQuery {
@@ -31,9 +29,43 @@ impl PutItemQuery {
             items,
             return_values,
             condition_expression: condition_expression.map(|value| value.into()),
+            expression_attribute_names: ExpressionAttributeNames::new(),
+            expression_attribute_values: ExpressionAttributeValues::new(),
         }
     }
 
+    /// `condition` is either a `Condition` or a raw expression string.
+    pub fn with_condition(mut self, condition: impl Into<Expression>) -> Self {
+        let condition = condition.into();
+
+        self.condition_expression = Some(condition.expression().to_string());
+        self.expression_attribute_names
+            .extend(condition.names().clone());
+        self.expression_attribute_values
+            .extend(condition.values().clone());
+        self
+    }
+
+    pub fn with_expression_attribute_name(
+        mut self,
+        placeholder: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.expression_attribute_names
+            .insert(placeholder.into(), name.into());
+        self
+    }
+
+    pub fn with_expression_attribute_value(
+        mut self,
+        placeholder: impl Into<String>,
+        value: AttributeValue,
+    ) -> Self {
+        self.expression_attribute_values
+            .insert(placeholder.into(), value);
+        self
+    }
+
     pub fn table_name(&self) -> &str {
         &self.table_name
     }
@@ -59,52 +91,136 @@ impl PutItemQuery {
     pub fn condition_expression(&self) -> &Option<String> {
         &self.condition_expression
     }
+
+    pub fn expression_attribute_names(&self) -> &ExpressionAttributeNames {
+        &self.expression_attribute_names
+    }
+
+    pub fn expression_attribute_values(&self) -> &ExpressionAttributeValues {
+        &self.expression_attribute_values
+    }
 }
 
 pub type Items = HashMap<String, AttributeValue>;
 
-/*
- * @Todo Fix deserialize.
- */
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct PutItemQueryJson {
+    table_name: String,
+    #[serde(alias = "Items", with = "items")]
+    item: Items,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    condition_expression: Option<String>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    expression_attribute_names: ExpressionAttributeNames,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty", with = "items")]
+    expression_attribute_values: ExpressionAttributeValues,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    return_values: Option<String>,
+}
+
+/// Reads the PutItem request format of the AWS CLI, with the item in DynamoDB JSON.
 impl<'de> Deserialize<'de> for PutItemQuery {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
     where
         D: Deserializer<'de>,
     {
-        #[derive(Deserialize)]
-        struct PutItemQueryHelper {
-            #[serde(rename = "TableName")]
-            table_name: String,
-        }
-
-        let helper = PutItemQueryHelper::deserialize(deserializer)?;
+        let json = PutItemQueryJson::deserialize(deserializer)?;
 
-        let mut hash: HashMap<String, AttributeValue> = HashMap::new();
-
-        hash.insert("temp".to_string(), AttributeValue::S("test!!!".to_string()));
+        let return_values = match json.return_values.as_deref() {
+            None => None,
+            Some(value @ ("NONE" | "ALL_OLD")) => Some(ReturnValue::from(value)),
+            Some(value) => return Err(D::Error::unknown_variant(value, &["NONE", "ALL_OLD"])),
+        };
 
         Ok(PutItemQuery {
-            table_name: helper.table_name,
-            items: hash,
-            return_values: None,
-            condition_expression: None,
+            table_name: json.table_name,
+            items: json.item,
+            return_values,
+            condition_expression: json.condition_expression,
+            expression_attribute_names: json.expression_attribute_names,
+            expression_attribute_values: json.expression_attribute_values,
         })
     }
 }
 
-/*
-#[derive(Deserialize, Debug)]
-pub enum AttributeValue {
-    S(String),
-    Unknown
+impl Serialize for PutItemQuery {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        PutItemQueryJson {
+            table_name: self.table_name.clone(),
+            item: self.items.clone(),
+            condition_expression: self.condition_expression.clone(),
+            expression_attribute_names: self.expression_attribute_names.clone(),
+            expression_attribute_values: self.expression_attribute_values.clone(),
+            return_values: self
+                .return_values
+                .as_ref()
+                .map(|value| value.as_str().to_string()),
+        }
+        .serialize(serializer)
+    }
 }
 
-impl From<AwsAttributeValue> for AttributeValue {
-    fn from(aws_value: AwsAttributeValue) -> Self {
-        if let AwsAttributeValue::S(value) = aws_value {
-            return AttributeValue::S(value)
-        }
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::{AttributeValue, ReturnValue};
+    use pretty_assertions::assert_eq;
+
+    use crate::query::put_item::PutItemQuery;
+
+    const JSON: &str = r##"{
+        "TableName": "Messages",
+        "Item": {
+            "message_id": {"S": "1"},
+            "account_id": {"N": "111"},
+            "tags": {"SS": ["a", "b"]},
+            "metadata": {"M": {"client": {"S": "ios"}}}
+        },
+        "ConditionExpression": "attribute_not_exists(#id)",
+        "ExpressionAttributeNames": {"#id": "message_id"},
+        "ReturnValues": "ALL_OLD"
+    }"##;
+
+    #[test]
+    fn test_deserialize() {
+        let query = serde_json::from_str::<PutItemQuery>(JSON).unwrap();
+
+        assert_eq!("Messages", query.table_name());
+        assert_eq!(Some(&AttributeValue::N("111".to_string())), query.items().get("account_id"));
+        assert_eq!(
+            Some(&AttributeValue::Ss(vec!["a".to_string(), "b".to_string()])),
+            query.items().get("tags")
+        );
+        assert_eq!(
+            &Some("attribute_not_exists(#id)".to_string()),
+            query.condition_expression()
+        );
+        assert_eq!(
+            Some(&"message_id".to_string()),
+            query.expression_attribute_names().get("#id")
+        );
+        assert_eq!(ReturnValue::AllOld, query.return_values());
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let query = serde_json::from_str::<PutItemQuery>(JSON).unwrap();
+
+        let round_tripped =
+            serde_json::from_str::<PutItemQuery>(&serde_json::to_string(&query).unwrap()).unwrap();
 
-        AttributeValue::Unknown
+        assert_eq!(query.items(), round_tripped.items());
+        assert_eq!(query.condition_expression(), round_tripped.condition_expression());
+        assert_eq!(query.return_values(), round_tripped.return_values());
     }
-}*/
+
+    #[test]
+    fn test_deserialize_rejects_invalid_return_values() {
+        let json = r#"{"TableName": "Messages", "Item": {}, "ReturnValues": "ALL_NEW"}"#;
+
+        assert!(serde_json::from_str::<PutItemQuery>(json).is_err());
+    }
+}
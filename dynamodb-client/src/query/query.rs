@@ -0,0 +1,114 @@
+use aws_sdk_dynamodb::model::AttributeValue;
+
+use crate::query::dynamodb_query::{ExpressionAttributeNames, ExpressionAttributeValues};
+
+#[derive(Debug, Clone)]
+pub struct QueryQuery {
+    table_name: String,
+    key_condition_expression: String,
+    filter_expression: Option<String>,
+    index_name: Option<String>,
+    expression_attribute_names: ExpressionAttributeNames,
+    expression_attribute_values: ExpressionAttributeValues,
+    limit: Option<i32>,
+    consistent_read: bool,
+    scan_index_forward: bool,
+}
+
+impl QueryQuery {
+    pub fn new(table_name: impl Into<String>, key_condition_expression: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            key_condition_expression: key_condition_expression.into(),
+            filter_expression: None,
+            index_name: None,
+            expression_attribute_names: ExpressionAttributeNames::new(),
+            expression_attribute_values: ExpressionAttributeValues::new(),
+            limit: None,
+            consistent_read: false,
+            scan_index_forward: true,
+        }
+    }
+
+    pub fn with_filter_expression(mut self, filter_expression: impl Into<String>) -> Self {
+        self.filter_expression = Some(filter_expression.into());
+        self
+    }
+
+    pub fn with_index_name(mut self, index_name: impl Into<String>) -> Self {
+        self.index_name = Some(index_name.into());
+        self
+    }
+
+    pub fn with_expression_attribute_name(
+        mut self,
+        placeholder: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.expression_attribute_names
+            .insert(placeholder.into(), name.into());
+        self
+    }
+
+    pub fn with_expression_attribute_value(
+        mut self,
+        placeholder: impl Into<String>,
+        value: AttributeValue,
+    ) -> Self {
+        self.expression_attribute_values
+            .insert(placeholder.into(), value);
+        self
+    }
+
+    /// Maximum number of items evaluated per page, not in total.
+    pub fn with_limit(mut self, limit: i32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn with_consistent_read(mut self, consistent_read: bool) -> Self {
+        self.consistent_read = consistent_read;
+        self
+    }
+
+    pub fn with_scan_index_forward(mut self, scan_index_forward: bool) -> Self {
+        self.scan_index_forward = scan_index_forward;
+        self
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn key_condition_expression(&self) -> &str {
+        &self.key_condition_expression
+    }
+
+    pub fn filter_expression(&self) -> &Option<String> {
+        &self.filter_expression
+    }
+
+    pub fn index_name(&self) -> &Option<String> {
+        &self.index_name
+    }
+
+    pub fn expression_attribute_names(&self) -> &ExpressionAttributeNames {
+        &self.expression_attribute_names
+    }
+
+    pub fn expression_attribute_values(&self) -> &ExpressionAttributeValues {
+        &self.expression_attribute_values
+    }
+
+    pub fn limit(&self) -> &Option<i32> {
+        &self.limit
+    }
+
+    pub fn consistent_read(&self) -> &bool {
+        &self.consistent_read
+    }
+
+    pub fn scan_index_forward(&self) -> &bool {
+        &self.scan_index_forward
+    }
+}
@@ -0,0 +1,75 @@
+use std::time::Duration;
+
+use crate::stream::Checkpoints;
+
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Where reading starts in shards that have no checkpoint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StartingPosition {
+    /// The oldest record still retained, at most 24 hours old.
+    TrimHorizon,
+    /// Only records written after the read started.
+    Latest,
+    /// The record with this sequence number and everything after it.
+    AtSequenceNumber(String),
+}
+
+#[derive(Debug, Clone)]
+pub struct ReadStreamQuery {
+    stream_arn: String,
+    starting_position: StartingPosition,
+    checkpoints: Checkpoints,
+    limit: Option<i32>,
+    poll_interval: Duration,
+}
+
+impl ReadStreamQuery {
+    pub fn new(stream_arn: impl Into<String>, starting_position: StartingPosition) -> Self {
+        Self {
+            stream_arn: stream_arn.into(),
+            starting_position,
+            checkpoints: Checkpoints::new(),
+            limit: None,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Shards with a checkpoint resume right after it, whatever the starting position.
+    pub fn with_checkpoints(mut self, checkpoints: Checkpoints) -> Self {
+        self.checkpoints = checkpoints;
+        self
+    }
+
+    /// Records per GetRecords call, at most 1000.
+    pub fn with_limit(mut self, limit: i32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// How long to wait before polling again after a round without records. Defaults to 1 second.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    pub fn stream_arn(&self) -> &str {
+        &self.stream_arn
+    }
+
+    pub fn starting_position(&self) -> &StartingPosition {
+        &self.starting_position
+    }
+
+    pub fn checkpoints(&self) -> &Checkpoints {
+        &self.checkpoints
+    }
+
+    pub fn limit(&self) -> &Option<i32> {
+        &self.limit
+    }
+
+    pub fn poll_interval(&self) -> Duration {
+        self.poll_interval
+    }
+}
@@ -0,0 +1,236 @@
+use aws_sdk_dynamodb::model::{AttributeValue, CancellationReason as AwsCancellationReason};
+
+use crate::query::delete_item::DeleteItemQuery;
+use crate::query::dynamodb_query::{ExpressionAttributeNames, ExpressionAttributeValues, Keys};
+use crate::query::expression::Expression;
+use crate::query::put_item::{Items, PutItemQuery};
+use crate::query::update_item::UpdateItemQuery;
+
+#[derive(Debug, Clone)]
+pub enum TransactWriteItem {
+    Put(PutItemQuery),
+    Update(UpdateItemQuery),
+    Delete(DeleteItemQuery),
+    ConditionCheck(ConditionCheck),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct TransactWriteQuery {
+    items: Vec<TransactWriteItem>,
+    client_request_token: Option<String>,
+    return_old_items_on_condition_failure: bool,
+}
+
+impl TransactWriteQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn put(mut self, query: PutItemQuery) -> Self {
+        self.items.push(TransactWriteItem::Put(query));
+        self
+    }
+
+    pub fn update(mut self, query: UpdateItemQuery) -> Self {
+        self.items.push(TransactWriteItem::Update(query));
+        self
+    }
+
+    pub fn delete(mut self, query: DeleteItemQuery) -> Self {
+        self.items.push(TransactWriteItem::Delete(query));
+        self
+    }
+
+    pub fn condition_check(mut self, check: ConditionCheck) -> Self {
+        self.items.push(TransactWriteItem::ConditionCheck(check));
+        self
+    }
+
+    /// Makes retries of the same transaction idempotent for 10 minutes.
+    pub fn with_client_request_token(mut self, token: impl Into<String>) -> Self {
+        self.client_request_token = Some(token.into());
+        self
+    }
+
+    /// Asks every action for `ALL_OLD`, so a `ConditionalCheckFailed` reason carries the current item.
+    pub fn with_old_items_on_condition_failure(mut self) -> Self {
+        self.return_old_items_on_condition_failure = true;
+        self
+    }
+
+    pub fn items(&self) -> &Vec<TransactWriteItem> {
+        &self.items
+    }
+
+    pub fn client_request_token(&self) -> &Option<String> {
+        &self.client_request_token
+    }
+
+    pub fn return_old_items_on_condition_failure(&self) -> bool {
+        self.return_old_items_on_condition_failure
+    }
+}
+
+impl TransactWriteItem {
+    pub fn table_name(&self) -> &str {
+        match self {
+            TransactWriteItem::Put(query) => query.table_name(),
+            TransactWriteItem::Update(query) => query.table_name(),
+            TransactWriteItem::Delete(query) => query.table_name(),
+            TransactWriteItem::ConditionCheck(condition_check) => condition_check.table_name(),
+        }
+    }
+}
+
+/// Asserts a condition on an item without writing it.
+#[derive(Debug, Clone)]
+pub struct ConditionCheck {
+    table_name: String,
+    keys: Keys,
+    condition_expression: String,
+    expression_attribute_names: ExpressionAttributeNames,
+    expression_attribute_values: ExpressionAttributeValues,
+}
+
+impl ConditionCheck {
+    /// `condition` is either a `Condition` or a raw expression string.
+    pub fn new(table_name: impl Into<String>, keys: impl Into<Keys>, condition: impl Into<Expression>) -> Self {
+        let condition = condition.into();
+
+        Self {
+            table_name: table_name.into(),
+            keys: keys.into(),
+            condition_expression: condition.expression().to_string(),
+            expression_attribute_names: condition.names().clone(),
+            expression_attribute_values: condition.values().clone(),
+        }
+    }
+
+    pub fn with_expression_attribute_name(
+        mut self,
+        placeholder: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.expression_attribute_names
+            .insert(placeholder.into(), name.into());
+        self
+    }
+
+    pub fn with_expression_attribute_value(
+        mut self,
+        placeholder: impl Into<String>,
+        value: AttributeValue,
+    ) -> Self {
+        self.expression_attribute_values
+            .insert(placeholder.into(), value);
+        self
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn keys(&self) -> &Keys {
+        &self.keys
+    }
+
+    pub fn condition_expression(&self) -> &str {
+        &self.condition_expression
+    }
+
+    pub fn expression_attribute_names(&self) -> &ExpressionAttributeNames {
+        &self.expression_attribute_names
+    }
+
+    pub fn expression_attribute_values(&self) -> &ExpressionAttributeValues {
+        &self.expression_attribute_values
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CancellationReasonCode {
+    /// This action did not fail, another one in the transaction did.
+    None,
+    ConditionalCheckFailed,
+    ItemCollectionSizeLimitExceeded,
+    TransactionConflict,
+    ProvisionedThroughputExceeded,
+    ThrottlingError,
+    ValidationError,
+    Unknown(String),
+}
+
+impl From<&str> for CancellationReasonCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "None" => CancellationReasonCode::None,
+            "ConditionalCheckFailed" => CancellationReasonCode::ConditionalCheckFailed,
+            "ItemCollectionSizeLimitExceeded" => CancellationReasonCode::ItemCollectionSizeLimitExceeded,
+            "TransactionConflict" => CancellationReasonCode::TransactionConflict,
+            "ProvisionedThroughputExceeded" => CancellationReasonCode::ProvisionedThroughputExceeded,
+            "ThrottlingError" => CancellationReasonCode::ThrottlingError,
+            "ValidationError" => CancellationReasonCode::ValidationError,
+            unknown_value => CancellationReasonCode::Unknown(unknown_value.to_string()),
+        }
+    }
+}
+
+/// Why the action at `index` of the transaction was canceled.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CancellationReason {
+    index: usize,
+    code: CancellationReasonCode,
+    message: Option<String>,
+    item: Option<Items>,
+}
+
+impl CancellationReason {
+    pub fn new(
+        index: usize,
+        code: CancellationReasonCode,
+        message: Option<String>,
+        item: Option<Items>,
+    ) -> Self {
+        Self {
+            index,
+            code,
+            message,
+            item,
+        }
+    }
+
+    pub fn from_aws(index: usize, reason: &AwsCancellationReason) -> Self {
+        Self {
+            index,
+            code: reason
+                .code()
+                .map(CancellationReasonCode::from)
+                .unwrap_or(CancellationReasonCode::None),
+            message: reason.message().map(|message| message.to_string()),
+            item: reason.item().cloned(),
+        }
+    }
+
+    /// Position of the failed action in `TransactWriteQuery::items`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn code(&self) -> &CancellationReasonCode {
+        &self.code
+    }
+
+    pub fn message(&self) -> &Option<String> {
+        &self.message
+    }
+
+    /// The current item, only present when the query was built with
+    /// `with_old_items_on_condition_failure` and the condition failed.
+    pub fn item(&self) -> &Option<Items> {
+        &self.item
+    }
+
+    pub fn is_failure(&self) -> bool {
+        self.code != CancellationReasonCode::None
+    }
+}
@@ -0,0 +1,190 @@
+use aws_sdk_dynamodb::model::{AttributeValue, ReturnValue};
+use serde::de::Error;
+use serde::{Deserialize, Deserializer};
+
+use crate::query::attribute_value::items;
+use crate::query::dynamodb_query::{ExpressionAttributeNames, ExpressionAttributeValues, Keys};
+use crate::query::expression::Expression;
+
+#[derive(Debug, Clone)]
+pub struct UpdateItemQuery {
+    table_name: String,
+    keys: Keys,
+    update_expression: String,
+    condition_expression: Option<String>,
+    expression_attribute_names: ExpressionAttributeNames,
+    expression_attribute_values: ExpressionAttributeValues,
+    return_values: Option<ReturnValue>,
+}
+
+impl UpdateItemQuery {
+    /// `update` is either an `UpdateExpression` or a raw expression string.
+    pub fn new(table_name: impl Into<String>, keys: impl Into<Keys>, update: impl Into<Expression>) -> Self {
+        let update = update.into();
+
+        Self {
+            table_name: table_name.into(),
+            keys: keys.into(),
+            update_expression: update.expression().to_string(),
+            condition_expression: None,
+            expression_attribute_names: update.names().clone(),
+            expression_attribute_values: update.values().clone(),
+            return_values: None,
+        }
+    }
+
+    /// `condition` is either a `Condition` or a raw expression string.
+    pub fn with_condition(mut self, condition: impl Into<Expression>) -> Self {
+        let condition = condition.into();
+
+        self.condition_expression = Some(condition.expression().to_string());
+        self.expression_attribute_names
+            .extend(condition.names().clone());
+        self.expression_attribute_values
+            .extend(condition.values().clone());
+        self
+    }
+
+    pub fn with_expression_attribute_name(
+        mut self,
+        placeholder: impl Into<String>,
+        name: impl Into<String>,
+    ) -> Self {
+        self.expression_attribute_names
+            .insert(placeholder.into(), name.into());
+        self
+    }
+
+    pub fn with_expression_attribute_value(
+        mut self,
+        placeholder: impl Into<String>,
+        value: AttributeValue,
+    ) -> Self {
+        self.expression_attribute_values
+            .insert(placeholder.into(), value);
+        self
+    }
+
+    pub fn with_return_values(mut self, return_values: ReturnValue) -> Self {
+        self.return_values = Some(return_values);
+        self
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn keys(&self) -> &Keys {
+        &self.keys
+    }
+
+    pub fn update_expression(&self) -> &str {
+        &self.update_expression
+    }
+
+    pub fn condition_expression(&self) -> &Option<String> {
+        &self.condition_expression
+    }
+
+    pub fn expression_attribute_names(&self) -> &ExpressionAttributeNames {
+        &self.expression_attribute_names
+    }
+
+    pub fn expression_attribute_values(&self) -> &ExpressionAttributeValues {
+        &self.expression_attribute_values
+    }
+
+    pub fn return_values(&self) -> ReturnValue {
+        match &self.return_values {
+            Some(value) => value.clone(),
+            None => ReturnValue::None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct UpdateItemQueryJson {
+    table_name: String,
+    #[serde(with = "items")]
+    key: Keys,
+    update_expression: String,
+    #[serde(default)]
+    condition_expression: Option<String>,
+    #[serde(default)]
+    expression_attribute_names: ExpressionAttributeNames,
+    #[serde(default, with = "items")]
+    expression_attribute_values: ExpressionAttributeValues,
+    #[serde(default)]
+    return_values: Option<String>,
+}
+
+/// Reads the UpdateItem request format of the AWS CLI, with the key and values in DynamoDB JSON.
+impl<'de> Deserialize<'de> for UpdateItemQuery {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        const RETURN_VALUES: &[&str] = &["NONE", "ALL_OLD", "UPDATED_OLD", "ALL_NEW", "UPDATED_NEW"];
+
+        let json = UpdateItemQueryJson::deserialize(deserializer)?;
+
+        let return_values = match json.return_values.as_deref() {
+            None => None,
+            Some(value) if RETURN_VALUES.contains(&value) => Some(ReturnValue::from(value)),
+            Some(value) => return Err(D::Error::unknown_variant(value, RETURN_VALUES)),
+        };
+
+        Ok(UpdateItemQuery {
+            table_name: json.table_name,
+            keys: json.key,
+            update_expression: json.update_expression,
+            condition_expression: json.condition_expression,
+            expression_attribute_names: json.expression_attribute_names,
+            expression_attribute_values: json.expression_attribute_values,
+            return_values,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::{AttributeValue, ReturnValue};
+    use pretty_assertions::assert_eq;
+
+    use crate::query::update_item::UpdateItemQuery;
+
+    #[test]
+    fn test_deserialize() {
+        let json = r##"{
+            "TableName": "Channels",
+            "Key": {"channel_id": {"S": "general"}},
+            "UpdateExpression": "SET #topic = :topic",
+            "ConditionExpression": "attribute_exists(channel_id)",
+            "ExpressionAttributeNames": {"#topic": "topic"},
+            "ExpressionAttributeValues": {":topic": {"S": "Announcements"}},
+            "ReturnValues": "UPDATED_NEW"
+        }"##;
+
+        let query = serde_json::from_str::<UpdateItemQuery>(json).unwrap();
+
+        assert_eq!("Channels", query.table_name());
+        assert_eq!(
+            Some(&AttributeValue::S("general".to_string())),
+            query.keys().get("channel_id")
+        );
+        assert_eq!("SET #topic = :topic", query.update_expression());
+        assert_eq!(&Some("attribute_exists(channel_id)".to_string()), query.condition_expression());
+        assert_eq!(Some(&"topic".to_string()), query.expression_attribute_names().get("#topic"));
+        assert_eq!(
+            Some(&AttributeValue::S("Announcements".to_string())),
+            query.expression_attribute_values().get(":topic")
+        );
+        assert_eq!(ReturnValue::UpdatedNew, query.return_values());
+
+        assert!(serde_json::from_str::<UpdateItemQuery>(
+            r#"{"TableName": "Channels", "Key": {}, "UpdateExpression": "REMOVE a", "ReturnValues": "ALL"}"#
+        )
+        .is_err());
+    }
+}
@@ -0,0 +1,365 @@
+use serde::{Deserialize, Serialize};
+
+use crate::query::describe_table::TableDescription;
+use crate::query::dynamodb_query::{
+    AttributeDefinition, BillingMode, GlobalSecondaryIndex, ProvisionedThroughput,
+    SseSpecification, StreamSpecification,
+};
+
+/// One entry of `GlobalSecondaryIndexUpdates`. DynamoDB accepts a single create or delete per call.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum GlobalSecondaryIndexUpdate {
+    Create(GlobalSecondaryIndex),
+    Update {
+        #[serde(rename = "IndexName")]
+        index_name: String,
+        #[serde(rename = "ProvisionedThroughput")]
+        provisioned_throughput: ProvisionedThroughput,
+    },
+    Delete {
+        #[serde(rename = "IndexName")]
+        index_name: String,
+    },
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone)]
+pub struct UpdateTableQuery {
+    #[serde(rename = "TableName")]
+    table_name: String,
+    /// Only needed for attributes used by a new index.
+    #[serde(rename = "AttributeDefinitions")]
+    #[serde(default)]
+    attribute_definitions: Vec<AttributeDefinition>,
+    #[serde(rename = "BillingMode")]
+    #[serde(default)]
+    billing_mode: Option<BillingMode>,
+    #[serde(rename = "ProvisionedThroughput")]
+    #[serde(default)]
+    provisioned_throughput: Option<ProvisionedThroughput>,
+    #[serde(rename = "GlobalSecondaryIndexUpdates")]
+    #[serde(default)]
+    global_secondary_index_updates: Vec<GlobalSecondaryIndexUpdate>,
+    #[serde(rename = "StreamSpecification")]
+    #[serde(default)]
+    stream_specification: Option<StreamSpecification>,
+    #[serde(rename = "SSESpecification")]
+    #[serde(default)]
+    sse_specification: Option<SseSpecification>,
+}
+
+impl UpdateTableQuery {
+    pub fn new(table_name: impl Into<String>) -> Self {
+        Self {
+            table_name: table_name.into(),
+            ..Self::default()
+        }
+    }
+
+    pub fn with_attribute_definition(mut self, attribute_definition: AttributeDefinition) -> Self {
+        self.attribute_definitions.push(attribute_definition);
+        self
+    }
+
+    pub fn with_billing_mode(mut self, billing_mode: BillingMode) -> Self {
+        self.billing_mode = Some(billing_mode);
+        self
+    }
+
+    pub fn with_provisioned_throughput(mut self, provisioned_throughput: ProvisionedThroughput) -> Self {
+        self.provisioned_throughput = Some(provisioned_throughput);
+        self
+    }
+
+    pub fn with_global_secondary_index_update(mut self, update: GlobalSecondaryIndexUpdate) -> Self {
+        self.global_secondary_index_updates.push(update);
+        self
+    }
+
+    pub fn with_stream_specification(mut self, stream_specification: StreamSpecification) -> Self {
+        self.stream_specification = Some(stream_specification);
+        self
+    }
+
+    pub fn with_sse_specification(mut self, sse_specification: SseSpecification) -> Self {
+        self.sse_specification = Some(sse_specification);
+        self
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn attribute_definitions(&self) -> &Vec<AttributeDefinition> {
+        &self.attribute_definitions
+    }
+
+    pub fn billing_mode(&self) -> Option<BillingMode> {
+        self.billing_mode
+    }
+
+    pub fn provisioned_throughput(&self) -> &Option<ProvisionedThroughput> {
+        &self.provisioned_throughput
+    }
+
+    pub fn global_secondary_index_updates(&self) -> &Vec<GlobalSecondaryIndexUpdate> {
+        &self.global_secondary_index_updates
+    }
+
+    pub fn stream_specification(&self) -> &Option<StreamSpecification> {
+        &self.stream_specification
+    }
+
+    pub fn sse_specification(&self) -> &Option<SseSpecification> {
+        &self.sse_specification
+    }
+
+    /// Splits into requests DynamoDB accepts one at a time: throughput and billing together with
+    /// index throughput, then the stream, then each index creation or deletion.
+    pub fn into_steps(self) -> Vec<UpdateTableQuery> {
+        let (index_throughputs, index_changes): (Vec<_>, Vec<_>) = self
+            .global_secondary_index_updates
+            .into_iter()
+            .partition(|update| matches!(update, GlobalSecondaryIndexUpdate::Update { .. }));
+
+        let mut steps = vec![];
+
+        let settings = UpdateTableQuery {
+            table_name: self.table_name.clone(),
+            billing_mode: self.billing_mode,
+            provisioned_throughput: self.provisioned_throughput,
+            global_secondary_index_updates: index_throughputs,
+            sse_specification: self.sse_specification,
+            ..UpdateTableQuery::default()
+        };
+        if settings.billing_mode.is_some()
+            || settings.provisioned_throughput.is_some()
+            || !settings.global_secondary_index_updates.is_empty()
+            || settings.sse_specification.is_some()
+        {
+            steps.push(settings);
+        }
+
+        if let Some(stream_specification) = self.stream_specification {
+            steps.push(UpdateTableQuery::new(&self.table_name).with_stream_specification(stream_specification));
+        }
+
+        for index_change in index_changes {
+            let mut step = UpdateTableQuery::new(&self.table_name);
+            if matches!(index_change, GlobalSecondaryIndexUpdate::Create(_)) {
+                step.attribute_definitions = self.attribute_definitions.clone();
+            }
+
+            steps.push(step.with_global_secondary_index_update(index_change));
+        }
+
+        steps
+    }
+
+    /// Drops the changes `description` shows already in effect, so an interrupted sequence of
+    /// steps can be sent again. `None` when nothing is left. Encryption changes are always kept.
+    pub fn pending_against(self, description: &TableDescription) -> Option<UpdateTableQuery> {
+        let index = |index_name: &str| {
+            description
+                .global_secondary_indexes()
+                .iter()
+                .find(|index| index.index_name() == index_name)
+        };
+
+        // DynamoDB leaves the billing mode out for tables that have always been provisioned.
+        let current_billing_mode = description.billing_mode().unwrap_or(BillingMode::Provisioned);
+        let billing_mode = self
+            .billing_mode
+            .filter(|billing_mode| *billing_mode != current_billing_mode);
+        let provisioned_throughput = self
+            .provisioned_throughput
+            .filter(|throughput| Some(throughput) != description.provisioned_throughput().as_ref());
+        let global_secondary_index_updates = self
+            .global_secondary_index_updates
+            .into_iter()
+            .filter(|update| match update {
+                GlobalSecondaryIndexUpdate::Create(created) => index(created.index_name()).is_none(),
+                GlobalSecondaryIndexUpdate::Update {
+                    index_name,
+                    provisioned_throughput,
+                } => index(index_name).is_none_or(|index| {
+                    index.provisioned_throughput().as_ref() != Some(provisioned_throughput)
+                }),
+                GlobalSecondaryIndexUpdate::Delete { index_name } => index(index_name).is_some(),
+            })
+            .collect::<Vec<_>>();
+        let stream_specification = self.stream_specification.filter(|stream_specification| {
+            let current = description.stream_specification().as_ref();
+            let enabled = current.is_some_and(StreamSpecification::stream_enabled);
+
+            let view_type = current.and_then(StreamSpecification::stream_view_type);
+
+            stream_specification.stream_enabled() != enabled
+                || (enabled && view_type != stream_specification.stream_view_type())
+        });
+
+        if billing_mode.is_none()
+            && provisioned_throughput.is_none()
+            && global_secondary_index_updates.is_empty()
+            && stream_specification.is_none()
+            && self.sse_specification.is_none()
+        {
+            return None;
+        }
+
+        let creates_index = global_secondary_index_updates
+            .iter()
+            .any(|update| matches!(update, GlobalSecondaryIndexUpdate::Create(_)));
+
+        Some(UpdateTableQuery {
+            table_name: self.table_name,
+            attribute_definitions: if creates_index { self.attribute_definitions } else { vec![] },
+            billing_mode,
+            provisioned_throughput,
+            global_secondary_index_updates,
+            stream_specification,
+            sse_specification: self.sse_specification,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model;
+    use pretty_assertions::assert_eq;
+
+    use crate::query::describe_table::TableDescription;
+    use crate::query::dynamodb_query::{BillingMode, ProvisionedThroughput, StreamSpecification};
+    use crate::query::update_table::{GlobalSecondaryIndexUpdate, UpdateTableQuery};
+
+    #[test]
+    fn test_deserialize_index_updates() {
+        let json = r#"{
+            "TableName": "Messages",
+            "AttributeDefinitions": [{"AttributeName": "channel_id", "AttributeType": "S"}],
+            "GlobalSecondaryIndexUpdates": [
+                {"Create": {
+                    "IndexName": "channel_id-index",
+                    "KeySchema": [{"AttributeName": "channel_id", "KeyType": "HASH"}],
+                    "Projection": {"ProjectionType": "ALL"},
+                    "ProvisionedThroughput": {"ReadCapacityUnits": 1, "WriteCapacityUnits": 1}
+                }},
+                {"Update": {
+                    "IndexName": "account_id-index",
+                    "ProvisionedThroughput": {"ReadCapacityUnits": 5, "WriteCapacityUnits": 5}
+                }},
+                {"Delete": {"IndexName": "old-index"}}
+            ]
+        }"#;
+
+        let query = serde_json::from_str::<UpdateTableQuery>(json).unwrap();
+
+        assert_eq!("Messages", query.table_name());
+        assert_eq!(3, query.global_secondary_index_updates().len());
+        assert!(matches!(
+            &query.global_secondary_index_updates()[0],
+            GlobalSecondaryIndexUpdate::Create(index) if index.index_name() == "channel_id-index"
+        ));
+        assert_eq!(
+            GlobalSecondaryIndexUpdate::Update {
+                index_name: "account_id-index".to_string(),
+                provisioned_throughput: ProvisionedThroughput::new(5, 5),
+            },
+            query.global_secondary_index_updates()[1]
+        );
+        assert_eq!(
+            GlobalSecondaryIndexUpdate::Delete {
+                index_name: "old-index".to_string()
+            },
+            query.global_secondary_index_updates()[2]
+        );
+    }
+
+    #[test]
+    fn test_into_steps() {
+        let json = r#"{
+            "TableName": "Messages",
+            "AttributeDefinitions": [{"AttributeName": "channel_id", "AttributeType": "S"}],
+            "ProvisionedThroughput": {"ReadCapacityUnits": 10, "WriteCapacityUnits": 10},
+            "StreamSpecification": {"StreamEnabled": false},
+            "GlobalSecondaryIndexUpdates": [
+                {"Create": {
+                    "IndexName": "channel_id-index",
+                    "KeySchema": [{"AttributeName": "channel_id", "KeyType": "HASH"}],
+                    "Projection": {"ProjectionType": "ALL"}
+                }},
+                {"Update": {
+                    "IndexName": "account_id-index",
+                    "ProvisionedThroughput": {"ReadCapacityUnits": 5, "WriteCapacityUnits": 5}
+                }},
+                {"Delete": {"IndexName": "old-index"}}
+            ]
+        }"#;
+
+        let steps = serde_json::from_str::<UpdateTableQuery>(json).unwrap().into_steps();
+
+        assert_eq!(4, steps.len());
+        assert_eq!(&Some(ProvisionedThroughput::new(10, 10)), steps[0].provisioned_throughput());
+        assert_eq!(1, steps[0].global_secondary_index_updates().len());
+        assert!(steps[0].stream_specification().is_none());
+        assert_eq!(&Some(StreamSpecification::new(false, None)), steps[1].stream_specification());
+        assert!(steps[1].global_secondary_index_updates().is_empty());
+        assert!(matches!(
+            &steps[2].global_secondary_index_updates()[..],
+            [GlobalSecondaryIndexUpdate::Create(index)] if index.index_name() == "channel_id-index"
+        ));
+        assert_eq!(1, steps[2].attribute_definitions().len());
+        assert_eq!(
+            vec![GlobalSecondaryIndexUpdate::Delete {
+                index_name: "old-index".to_string()
+            }],
+            *steps[3].global_secondary_index_updates()
+        );
+        assert!(steps[3].attribute_definitions().is_empty());
+        assert!(steps.iter().all(|step| step.table_name() == "Messages"));
+    }
+
+    #[test]
+    fn test_pending_against_drops_changes_in_effect() {
+        let description = TableDescription::try_from(
+            &model::TableDescription::builder()
+                .table_name("Messages")
+                .provisioned_throughput(
+                    model::ProvisionedThroughputDescription::builder()
+                        .read_capacity_units(5)
+                        .write_capacity_units(5)
+                        .build(),
+                )
+                .global_secondary_indexes(
+                    model::GlobalSecondaryIndexDescription::builder()
+                        .index_name("channel_id-index")
+                        .build(),
+                )
+                .build(),
+        )
+        .unwrap();
+
+        let query = UpdateTableQuery::new("Messages")
+            .with_billing_mode(BillingMode::Provisioned)
+            .with_provisioned_throughput(ProvisionedThroughput::new(5, 5))
+            .with_stream_specification(StreamSpecification::new(false, None));
+        assert!(query.pending_against(&description).is_none());
+
+        let pending = UpdateTableQuery::new("Messages")
+            .with_provisioned_throughput(ProvisionedThroughput::new(10, 10))
+            .with_global_secondary_index_update(GlobalSecondaryIndexUpdate::Delete {
+                index_name: "old-index".to_string(),
+            })
+            .with_global_secondary_index_update(GlobalSecondaryIndexUpdate::Delete {
+                index_name: "channel_id-index".to_string(),
+            })
+            .pending_against(&description)
+            .unwrap();
+        assert_eq!(&Some(ProvisionedThroughput::new(10, 10)), pending.provisioned_throughput());
+        assert_eq!(
+            vec![GlobalSecondaryIndexUpdate::Delete {
+                index_name: "channel_id-index".to_string()
+            }],
+            *pending.global_secondary_index_updates()
+        );
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TimeToLiveSpecification {
+    #[serde(rename = "AttributeName")]
+    attribute_name: String,
+    #[serde(rename = "Enabled")]
+    enabled: bool,
+}
+
+impl TimeToLiveSpecification {
+    pub fn attribute_name(&self) -> &str {
+        &self.attribute_name
+    }
+
+    pub fn enabled(&self) -> bool {
+        self.enabled
+    }
+}
+
+/// Items expire once the epoch seconds stored in `attribute_name` are in the past.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct UpdateTimeToLiveQuery {
+    #[serde(rename = "TableName")]
+    table_name: String,
+    #[serde(rename = "TimeToLiveSpecification")]
+    time_to_live_specification: TimeToLiveSpecification,
+}
+
+impl UpdateTimeToLiveQuery {
+    pub fn new(table_name: impl Into<String>, attribute_name: impl Into<String>, enabled: bool) -> Self {
+        Self {
+            table_name: table_name.into(),
+            time_to_live_specification: TimeToLiveSpecification {
+                attribute_name: attribute_name.into(),
+                enabled,
+            },
+        }
+    }
+
+    pub fn table_name(&self) -> &str {
+        &self.table_name
+    }
+
+    pub fn time_to_live_specification(&self) -> &TimeToLiveSpecification {
+        &self.time_to_live_specification
+    }
+}
@@ -0,0 +1,86 @@
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+const MIN_WAIT: Duration = Duration::from_millis(1);
+
+/// A budget of capacity units refilled at `units_per_second`, shared by concurrent requests.
+/// Consumed capacity is only known after a request, so the budget may go negative and
+/// `acquire` then waits until it has been paid back.
+#[derive(Debug)]
+pub(crate) struct CapacityRateLimiter {
+    units_per_second: f64,
+    budget: Mutex<Budget>,
+}
+
+#[derive(Debug)]
+struct Budget {
+    available: f64,
+    refilled_at: Instant,
+}
+
+impl CapacityRateLimiter {
+    /// Starts with one second worth of units.
+    pub(crate) fn new(units_per_second: f64) -> Self {
+        Self {
+            units_per_second,
+            budget: Mutex::new(Budget {
+                available: units_per_second,
+                refilled_at: Instant::now(),
+            }),
+        }
+    }
+
+    /// Waits until the budget is positive.
+    pub(crate) async fn acquire(&self) {
+        loop {
+            let deficit = {
+                let budget = self.refilled();
+                if budget.available > 0.0 {
+                    return;
+                }
+
+                -budget.available
+            };
+
+            tokio::time::sleep(Duration::from_secs_f64(deficit / self.units_per_second).max(MIN_WAIT)).await;
+        }
+    }
+
+    pub(crate) fn consume(&self, units: f64) {
+        self.refilled().available -= units;
+    }
+
+    fn refilled(&self) -> std::sync::MutexGuard<'_, Budget> {
+        let mut budget = self.budget.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+        let now = Instant::now();
+
+        budget.available = (budget.available
+            + now.duration_since(budget.refilled_at).as_secs_f64() * self.units_per_second)
+            .min(self.units_per_second);
+        budget.refilled_at = now;
+
+        budget
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::rate_limiter::CapacityRateLimiter;
+
+    #[tokio::test]
+    async fn test_acquire_waits_for_deficit() {
+        let limiter = CapacityRateLimiter::new(1000.0);
+
+        let started_at = Instant::now();
+        limiter.acquire().await;
+        assert!(started_at.elapsed() < Duration::from_millis(50));
+
+        limiter.consume(1100.0);
+
+        let started_at = Instant::now();
+        limiter.acquire().await;
+        assert!(started_at.elapsed() >= Duration::from_millis(90));
+    }
+}
@@ -0,0 +1,259 @@
+use std::fmt::Debug;
+use std::future::Future;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use tracing::{field, Instrument};
+
+use crate::error::DynamoDbError;
+use crate::metrics::{ConsumedCapacityUnits, Operation};
+use crate::query::transact_write::CancellationReasonCode;
+
+const DEFAULT_MAX_ATTEMPTS: u32 = 5;
+const DEFAULT_BASE_DELAY: Duration = Duration::from_millis(50);
+const DEFAULT_MAX_DELAY: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RetryableErrorClass {
+    /// ProvisionedThroughputExceeded, RequestLimitExceeded and ThrottlingException.
+    Throttling,
+    /// Timeouts, dispatch failures and unreadable responses.
+    Transport,
+    /// A transaction canceled because it conflicted with another one.
+    TransactionConflict,
+    /// InternalServerError and ServiceUnavailable.
+    ServerError,
+}
+
+impl RetryableErrorClass {
+    pub fn of(error: &DynamoDbError) -> Option<RetryableErrorClass> {
+        match error {
+            DynamoDbError::Throttled(_) => Some(RetryableErrorClass::Throttling),
+            DynamoDbError::Transport(_) => Some(RetryableErrorClass::Transport),
+            DynamoDbError::TransactionCanceled(reasons)
+                if reasons
+                    .iter()
+                    .any(|reason| reason.code() == &CancellationReasonCode::TransactionConflict) =>
+            {
+                Some(RetryableErrorClass::TransactionConflict)
+            }
+            DynamoDbError::Service { code, .. }
+                if code == "InternalServerError" || code == "ServiceUnavailable" =>
+            {
+                Some(RetryableErrorClass::ServerError)
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Decides whether and when a failed operation is attempted again.
+pub trait RetryPolicy: Debug + Send + Sync {
+    /// `attempt` is the number of attempts made so far, starting at 1.
+    /// Returns the delay before the next attempt, or `None` to give up.
+    fn next_delay(&self, attempt: u32, error: &DynamoDbError) -> Option<Duration>;
+}
+
+/// Exponential backoff with full jitter: the n-th retry waits a random duration between zero
+/// and `min(max_delay, base_delay * 2^(n - 1))`.
+#[derive(Debug, Clone)]
+pub struct ExponentialBackoff {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retryable: Vec<RetryableErrorClass>,
+}
+
+impl Default for ExponentialBackoff {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            base_delay: DEFAULT_BASE_DELAY,
+            max_delay: DEFAULT_MAX_DELAY,
+            retryable: vec![
+                RetryableErrorClass::Throttling,
+                RetryableErrorClass::Transport,
+                RetryableErrorClass::ServerError,
+            ],
+        }
+    }
+}
+
+impl ExponentialBackoff {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Attempts including the first one. `1` disables retries.
+    pub fn with_max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    pub fn with_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn with_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn with_retryable(mut self, retryable: Vec<RetryableErrorClass>) -> Self {
+        self.retryable = retryable;
+        self
+    }
+
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub fn retryable(&self) -> &Vec<RetryableErrorClass> {
+        &self.retryable
+    }
+
+    fn delay_ceiling(&self, attempt: u32) -> Duration {
+        self.base_delay
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+            .min(self.max_delay)
+    }
+}
+
+impl RetryPolicy for ExponentialBackoff {
+    fn next_delay(&self, attempt: u32, error: &DynamoDbError) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+
+        let class = RetryableErrorClass::of(error)?;
+        if !self.retryable.contains(&class) {
+            return None;
+        }
+
+        let ceiling = self.delay_ceiling(attempt);
+
+        Some(rand::thread_rng().gen_range(Duration::ZERO..=ceiling))
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoRetry;
+
+impl RetryPolicy for NoRetry {
+    fn next_delay(&self, _attempt: u32, _error: &DynamoDbError) -> Option<Duration> {
+        None
+    }
+}
+
+/// Runs `attempt` until it succeeds or the retry policy of `operation` gives up, inside a span
+/// recording table, duration, retries and consumed capacity, which are also sent to the metrics hook.
+pub(crate) async fn with_retry<T, F, Fut>(operation: &Operation<'_>, attempt: F) -> Result<T, DynamoDbError>
+where
+    T: ConsumedCapacityUnits,
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, DynamoDbError>>,
+{
+    let span = tracing::info_span!(
+        "dynamodb",
+        operation = operation.name(),
+        table = operation.table_name(),
+        duration_ms = field::Empty,
+        retries = field::Empty,
+        consumed_capacity = field::Empty,
+    );
+    let started_at = Instant::now();
+
+    let (result, attempts) = retry(operation.retry_policy(), operation.name(), attempt)
+        .instrument(span.clone())
+        .await;
+
+    operation.record(&span, started_at.elapsed(), attempts, &result);
+
+    result
+}
+
+/// Returns the outcome of the last attempt and the number of attempts made, logging every attempt.
+async fn retry<T, F, Fut>(
+    policy: &dyn RetryPolicy,
+    operation_name: &'static str,
+    operation: F,
+) -> (Result<T, DynamoDbError>, u32)
+where
+    F: Fn() -> Fut,
+    Fut: Future<Output = Result<T, DynamoDbError>>,
+{
+    let mut attempt = 1;
+
+    loop {
+        tracing::debug!(operation = operation_name, attempt, "Sending DynamoDB request.");
+
+        let error = match operation().await {
+            Ok(output) => return (Ok(output), attempt),
+            Err(error) => error,
+        };
+
+        match policy.next_delay(attempt, &error) {
+            Some(delay) => {
+                tracing::warn!(
+                    operation = operation_name,
+                    attempt,
+                    delay_ms = delay.as_millis() as u64,
+                    error = %error,
+                    "DynamoDB request failed, retrying."
+                );
+
+                tokio::time::sleep(delay).await;
+                attempt += 1;
+            }
+            None => {
+                tracing::error!(
+                    operation = operation_name,
+                    attempt,
+                    error = %error,
+                    "DynamoDB request failed, giving up."
+                );
+
+                return (Err(error), attempt);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::error::DynamoDbError;
+    use crate::retry::{ExponentialBackoff, RetryPolicy, RetryableErrorClass};
+
+    #[test]
+    fn test_exponential_backoff_stays_under_ceiling() {
+        let policy = ExponentialBackoff::new()
+            .with_base_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(300));
+        let error = DynamoDbError::Throttled("slow down".to_string());
+
+        assert!(policy.next_delay(1, &error).unwrap() <= Duration::from_millis(100));
+        assert!(policy.next_delay(2, &error).unwrap() <= Duration::from_millis(200));
+        assert!(policy.next_delay(4, &error).unwrap() <= Duration::from_millis(300));
+        assert_eq!(None, policy.next_delay(5, &error));
+    }
+
+    #[test]
+    fn test_exponential_backoff_only_retries_configured_classes() {
+        let policy = ExponentialBackoff::new().with_retryable(vec![RetryableErrorClass::Transport]);
+
+        assert_eq!(
+            None,
+            policy.next_delay(1, &DynamoDbError::Throttled("slow down".to_string()))
+        );
+        assert_eq!(
+            None,
+            policy.next_delay(1, &DynamoDbError::ConditionalCheckFailed("failed".to_string()))
+        );
+        assert!(policy
+            .next_delay(1, &DynamoDbError::Transport("timeout".to_string()))
+            .is_some());
+    }
+}
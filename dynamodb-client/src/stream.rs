@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use aws_sdk_dynamodb::model::AttributeValue;
+use aws_sdk_dynamodbstreams::model as streams_model;
+
+use crate::query::dynamodb_query::Keys;
+use crate::query::put_item::Items;
+
+/// Last processed sequence number per shard id. Persist it to resume a `ReadStreamQuery` later.
+pub type Checkpoints = HashMap<String, String>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamEventName {
+    Insert,
+    Modify,
+    Remove,
+}
+
+impl TryFrom<&streams_model::OperationType> for StreamEventName {
+    type Error = String;
+
+    fn try_from(operation_type: &streams_model::OperationType) -> Result<Self, Self::Error> {
+        match operation_type {
+            streams_model::OperationType::Insert => Ok(StreamEventName::Insert),
+            streams_model::OperationType::Modify => Ok(StreamEventName::Modify),
+            streams_model::OperationType::Remove => Ok(StreamEventName::Remove),
+            operation_type => Err(format!("Unknown stream event name {}.", operation_type.as_str())),
+        }
+    }
+}
+
+/// One change to an item, as delivered by DynamoDB Streams. Which images are present
+/// depends on the `StreamViewType` of the table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamRecord {
+    event_name: StreamEventName,
+    sequence_number: String,
+    keys: Keys,
+    new_image: Option<Items>,
+    old_image: Option<Items>,
+}
+
+impl StreamRecord {
+    pub fn new(
+        event_name: StreamEventName,
+        sequence_number: impl Into<String>,
+        keys: Keys,
+        new_image: Option<Items>,
+        old_image: Option<Items>,
+    ) -> Self {
+        Self {
+            event_name,
+            sequence_number: sequence_number.into(),
+            keys,
+            new_image,
+            old_image,
+        }
+    }
+
+    pub fn event_name(&self) -> StreamEventName {
+        self.event_name
+    }
+
+    pub fn sequence_number(&self) -> &str {
+        &self.sequence_number
+    }
+
+    pub fn keys(&self) -> &Keys {
+        &self.keys
+    }
+
+    pub fn new_image(&self) -> &Option<Items> {
+        &self.new_image
+    }
+
+    pub fn old_image(&self) -> &Option<Items> {
+        &self.old_image
+    }
+}
+
+impl TryFrom<&streams_model::Record> for StreamRecord {
+    type Error = String;
+
+    fn try_from(record: &streams_model::Record) -> Result<Self, Self::Error> {
+        let event_name = record
+            .event_name()
+            .ok_or("eventName is missing from the stream record.")?;
+        let change = record
+            .dynamodb()
+            .ok_or("dynamodb is missing from the stream record.")?;
+        let sequence_number = change
+            .sequence_number()
+            .ok_or("SequenceNumber is missing from the stream record.")?;
+
+        Ok(Self {
+            event_name: StreamEventName::try_from(event_name)?,
+            sequence_number: sequence_number.to_string(),
+            keys: change.keys().map(items).unwrap_or_default(),
+            new_image: change.new_image().map(items),
+            old_image: change.old_image().map(items),
+        })
+    }
+}
+
+/// The DynamoDB Streams SDK has its own copy of `AttributeValue`.
+fn attribute_value(value: &streams_model::AttributeValue) -> AttributeValue {
+    match value {
+        streams_model::AttributeValue::S(value) => AttributeValue::S(value.clone()),
+        streams_model::AttributeValue::N(value) => AttributeValue::N(value.clone()),
+        streams_model::AttributeValue::B(value) => AttributeValue::B(value.clone()),
+        streams_model::AttributeValue::Bool(value) => AttributeValue::Bool(*value),
+        streams_model::AttributeValue::Null(value) => AttributeValue::Null(*value),
+        streams_model::AttributeValue::Ss(values) => AttributeValue::Ss(values.clone()),
+        streams_model::AttributeValue::Ns(values) => AttributeValue::Ns(values.clone()),
+        streams_model::AttributeValue::Bs(values) => AttributeValue::Bs(values.clone()),
+        streams_model::AttributeValue::L(values) => AttributeValue::L(values.iter().map(attribute_value).collect()),
+        streams_model::AttributeValue::M(values) => AttributeValue::M(items(values)),
+        _ => AttributeValue::Null(true),
+    }
+}
+
+fn items(values: &HashMap<String, streams_model::AttributeValue>) -> Items {
+    values
+        .iter()
+        .map(|(name, value)| (name.clone(), attribute_value(value)))
+        .collect()
+}
+
+/// A stream record together with the shard it was read from, which is what a checkpoint refers to.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ShardRecord {
+    shard_id: String,
+    record: StreamRecord,
+}
+
+impl ShardRecord {
+    pub fn new(shard_id: impl Into<String>, record: StreamRecord) -> Self {
+        Self {
+            shard_id: shard_id.into(),
+            record,
+        }
+    }
+
+    pub fn shard_id(&self) -> &str {
+        &self.shard_id
+    }
+
+    pub fn record(&self) -> &StreamRecord {
+        &self.record
+    }
+
+    pub fn into_record(self) -> StreamRecord {
+        self.record
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ShardIteratorType {
+    TrimHorizon,
+    Latest,
+    AtSequenceNumber(String),
+    AfterSequenceNumber(String),
+}
+
+/// A shard of a stream. Shards are closed after about four hours and replaced by children
+/// naming them as parent, so children must only be read once their parent has been read.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shard {
+    shard_id: String,
+    parent_shard_id: Option<String>,
+    starting_sequence_number: Option<String>,
+    ending_sequence_number: Option<String>,
+}
+
+impl Shard {
+    pub fn new(
+        shard_id: impl Into<String>,
+        parent_shard_id: Option<String>,
+        starting_sequence_number: Option<String>,
+        ending_sequence_number: Option<String>,
+    ) -> Self {
+        Self {
+            shard_id: shard_id.into(),
+            parent_shard_id,
+            starting_sequence_number,
+            ending_sequence_number,
+        }
+    }
+
+    pub fn shard_id(&self) -> &str {
+        &self.shard_id
+    }
+
+    pub fn parent_shard_id(&self) -> &Option<String> {
+        &self.parent_shard_id
+    }
+
+    pub fn starting_sequence_number(&self) -> &Option<String> {
+        &self.starting_sequence_number
+    }
+
+    pub fn ending_sequence_number(&self) -> &Option<String> {
+        &self.ending_sequence_number
+    }
+
+    /// A closed shard receives no new records.
+    pub fn is_closed(&self) -> bool {
+        self.ending_sequence_number.is_some()
+    }
+}
+
+impl TryFrom<&streams_model::Shard> for Shard {
+    type Error = String;
+
+    fn try_from(shard: &streams_model::Shard) -> Result<Self, Self::Error> {
+        let shard_id = shard.shard_id().ok_or("ShardId is missing from the shard.")?;
+        let range = shard.sequence_number_range();
+
+        Ok(Self {
+            shard_id: shard_id.to_string(),
+            parent_shard_id: shard.parent_shard_id().map(|parent_shard_id| parent_shard_id.to_string()),
+            starting_sequence_number: range
+                .and_then(|range| range.starting_sequence_number())
+                .map(|sequence_number| sequence_number.to_string()),
+            ending_sequence_number: range
+                .and_then(|range| range.ending_sequence_number())
+                .map(|sequence_number| sequence_number.to_string()),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct StreamSummary {
+    stream_arn: String,
+    table_name: Option<String>,
+    stream_label: Option<String>,
+}
+
+impl StreamSummary {
+    pub fn stream_arn(&self) -> &str {
+        &self.stream_arn
+    }
+
+    pub fn table_name(&self) -> &Option<String> {
+        &self.table_name
+    }
+
+    pub fn stream_label(&self) -> &Option<String> {
+        &self.stream_label
+    }
+}
+
+impl TryFrom<&streams_model::Stream> for StreamSummary {
+    type Error = String;
+
+    fn try_from(stream: &streams_model::Stream) -> Result<Self, Self::Error> {
+        Ok(Self {
+            stream_arn: stream.stream_arn().ok_or("StreamArn is missing from the stream.")?.to_string(),
+            table_name: stream.table_name().map(|table_name| table_name.to_string()),
+            stream_label: stream.stream_label().map(|stream_label| stream_label.to_string()),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use aws_sdk_dynamodbstreams::model as streams_model;
+    use pretty_assertions::assert_eq;
+
+    use crate::stream::{StreamEventName, StreamRecord};
+
+    #[test]
+    fn test_try_from_aws_record() {
+        let record = streams_model::Record::builder()
+            .event_name(streams_model::OperationType::Modify)
+            .dynamodb(
+                streams_model::StreamRecord::builder()
+                    .sequence_number("100")
+                    .keys("message_id", streams_model::AttributeValue::S("1".to_string()))
+                    .new_image(
+                        "tags",
+                        streams_model::AttributeValue::L(vec![streams_model::AttributeValue::N("2".to_string())]),
+                    )
+                    .build(),
+            )
+            .build();
+
+        let record = StreamRecord::try_from(&record).unwrap();
+
+        assert_eq!(StreamEventName::Modify, record.event_name());
+        assert_eq!("100", record.sequence_number());
+        assert_eq!(
+            &HashMap::from([("message_id".to_string(), AttributeValue::S("1".to_string()))]),
+            record.keys()
+        );
+        assert_eq!(
+            &Some(HashMap::from([(
+                "tags".to_string(),
+                AttributeValue::L(vec![AttributeValue::N("2".to_string())])
+            )])),
+            record.new_image()
+        );
+        assert_eq!(&None, record.old_image());
+    }
+}
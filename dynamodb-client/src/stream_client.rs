@@ -0,0 +1,533 @@
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_stream::try_stream;
+use aws_sdk_dynamodbstreams::model as streams_model;
+use aws_sdk_dynamodbstreams::output::ListStreamsOutput;
+use futures_util::stream::BoxStream;
+use http::Uri;
+
+use crate::config::ClientConfig;
+use crate::error::DynamoDbError;
+use crate::query::list_streams::ListStreamsQuery;
+use crate::query::read_stream::{ReadStreamQuery, StartingPosition};
+use crate::metrics::{MetricsHook, Operation};
+use crate::retry::{with_retry, RetryPolicy};
+use crate::stream::{Checkpoints, Shard, ShardIteratorType, ShardRecord, StreamRecord, StreamSummary};
+
+pub type ShardRecordStream = BoxStream<'static, Result<ShardRecord, DynamoDbError>>;
+
+pub type StreamSummaryStream = BoxStream<'static, Result<StreamSummary, DynamoDbError>>;
+
+/// One page of GetRecords. `next_shard_iterator` is `None` once a closed shard has been read to its end.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecordsPage {
+    records: Vec<StreamRecord>,
+    next_shard_iterator: Option<String>,
+}
+
+impl RecordsPage {
+    pub fn records(&self) -> &Vec<StreamRecord> {
+        &self.records
+    }
+
+    pub fn next_shard_iterator(&self) -> &Option<String> {
+        &self.next_shard_iterator
+    }
+}
+
+/// Reads change events from DynamoDB Streams, e.g. to update read models without a Lambda.
+#[derive(Debug, Clone)]
+pub struct StreamClient {
+    client: aws_sdk_dynamodbstreams::Client,
+    retry_policy: Arc<dyn RetryPolicy>,
+    metrics: Arc<dyn MetricsHook>,
+}
+
+impl StreamClient {
+    /// Shortcut for DynamoDB Local / LocalStack listening on `uri`.
+    pub fn new(uri: Uri) -> StreamClient {
+        StreamClient::from_config(&ClientConfig::local(uri))
+    }
+
+    pub fn from_config(config: &ClientConfig) -> StreamClient {
+        Self {
+            client: aws_sdk_dynamodbstreams::Client::from_conf(config.to_streams_sdk_config()),
+            retry_policy: config.retry_policy(),
+            metrics: config.metrics_hook(),
+        }
+    }
+
+    fn operation(&self, name: &'static str, table_name: impl Into<String>) -> Operation<'_> {
+        Operation::new(name, table_name, self.retry_policy.as_ref(), self.metrics.as_ref())
+    }
+
+    /// A single page. See `list_all_streams` to read every stream.
+    pub async fn list_streams(&self, query: &ListStreamsQuery) -> Result<ListStreamsOutput, DynamoDbError> {
+        let client = &self.client;
+
+        with_retry(&self.operation("list_streams", query.table_name().clone().unwrap_or_default()), move || async move {
+            client
+                .list_streams()
+                .set_table_name(query.table_name().clone())
+                .set_limit(*query.limit())
+                .set_exclusive_start_stream_arn(query.exclusive_start_stream_arn().clone())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await
+    }
+
+    /// Follows LastEvaluatedStreamArn until every stream has been read.
+    pub fn list_all_streams(&self, query: ListStreamsQuery) -> StreamSummaryStream {
+        let client = self.clone();
+
+        Box::pin(try_stream! {
+            let mut query = query;
+
+            loop {
+                let output = client.list_streams(&query).await?;
+
+                for stream in output.streams.unwrap_or_default() {
+                    yield StreamSummary::try_from(&stream).map_err(DynamoDbError::UnexpectedResponse)?;
+                }
+
+                match output.last_evaluated_stream_arn {
+                    Some(stream_arn) => query = query.with_exclusive_start_stream_arn(stream_arn),
+                    None => break,
+                }
+            }
+        })
+    }
+
+    /// Follows LastEvaluatedShardId and returns every shard of the stream.
+    pub async fn describe_stream(&self, stream_arn: &str) -> Result<Vec<Shard>, DynamoDbError> {
+        let mut shards = vec![];
+        let mut exclusive_start_shard_id = None;
+
+        loop {
+            let client = &self.client;
+            let start_shard_id = &exclusive_start_shard_id;
+
+            let output = with_retry(&self.operation("describe_stream", table_name_of(stream_arn)), move || async move {
+                client
+                    .describe_stream()
+                    .stream_arn(stream_arn)
+                    .set_exclusive_start_shard_id(start_shard_id.clone())
+                    .send()
+                    .await
+                    .map_err(DynamoDbError::from)
+            })
+            .await?;
+
+            let description = output
+                .stream_description
+                .ok_or_else(|| DynamoDbError::UnexpectedResponse("StreamDescription is missing.".to_string()))?;
+
+            for shard in description.shards.unwrap_or_default() {
+                shards.push(Shard::try_from(&shard).map_err(DynamoDbError::UnexpectedResponse)?);
+            }
+
+            match description.last_evaluated_shard_id {
+                Some(shard_id) => exclusive_start_shard_id = Some(shard_id),
+                None => break,
+            }
+        }
+
+        Ok(shards)
+    }
+
+    /// `None` when the shard has nothing left to read at that position.
+    pub async fn get_shard_iterator(
+        &self,
+        stream_arn: &str,
+        shard_id: &str,
+        iterator_type: &ShardIteratorType,
+    ) -> Result<Option<String>, DynamoDbError> {
+        let client = &self.client;
+
+        let (aws_iterator_type, sequence_number) = match iterator_type {
+            ShardIteratorType::TrimHorizon => (streams_model::ShardIteratorType::TrimHorizon, None),
+            ShardIteratorType::Latest => (streams_model::ShardIteratorType::Latest, None),
+            ShardIteratorType::AtSequenceNumber(sequence_number) => {
+                (streams_model::ShardIteratorType::AtSequenceNumber, Some(sequence_number.clone()))
+            }
+            ShardIteratorType::AfterSequenceNumber(sequence_number) => {
+                (streams_model::ShardIteratorType::AfterSequenceNumber, Some(sequence_number.clone()))
+            }
+        };
+        let aws_iterator_type = &aws_iterator_type;
+        let sequence_number = &sequence_number;
+
+        let output = with_retry(&self.operation("get_shard_iterator", table_name_of(stream_arn)), move || async move {
+            client
+                .get_shard_iterator()
+                .stream_arn(stream_arn)
+                .shard_id(shard_id)
+                .shard_iterator_type(aws_iterator_type.clone())
+                .set_sequence_number(sequence_number.clone())
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await?;
+
+        Ok(output.shard_iterator)
+    }
+
+    pub async fn get_records(
+        &self,
+        shard_iterator: &str,
+        limit: Option<i32>,
+    ) -> Result<RecordsPage, DynamoDbError> {
+        let client = &self.client;
+
+        let output = with_retry(&self.operation("get_records", ""), move || async move {
+            client
+                .get_records()
+                .shard_iterator(shard_iterator)
+                .set_limit(limit)
+                .send()
+                .await
+                .map_err(DynamoDbError::from)
+        })
+        .await?;
+
+        let records = output
+            .records
+            .unwrap_or_default()
+            .iter()
+            .map(StreamRecord::try_from)
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(DynamoDbError::UnexpectedResponse)?;
+
+        Ok(RecordsPage {
+            records,
+            next_shard_iterator: output.next_shard_iterator,
+        })
+    }
+
+    /// Reads every shard of the stream, parents before their children, and keeps polling open
+    /// shards until the stream is dropped. Ends once every shard is closed and has been read,
+    /// i.e. after the stream was disabled.
+    ///
+    /// Persist `shard_id` and `sequence_number` of the records handled so far and pass them back
+    /// with `ReadStreamQuery::with_checkpoints` to resume where the previous reader stopped.
+    pub fn read(&self, query: ReadStreamQuery) -> ShardRecordStream {
+        let client = self.clone();
+
+        Box::pin(try_stream! {
+            let stream_arn = query.stream_arn();
+            let mut lineage = ShardLineage::new(
+                client.describe_stream(stream_arn).await?,
+                query.starting_position(),
+                query.checkpoints(),
+            );
+            let mut readers: Vec<ShardReader> = vec![];
+
+            loop {
+                for (shard_id, iterator_type) in lineage.take_ready() {
+                    match client.shard_iterator(stream_arn, &shard_id, iterator_type.clone()).await? {
+                        Some(shard_iterator) => readers.push(ShardReader {
+                            shard_id,
+                            shard_iterator,
+                            iterator_type,
+                        }),
+                        None => lineage.complete(&shard_id),
+                    }
+                }
+
+                if readers.is_empty() && lineage.is_finished() {
+                    break;
+                }
+
+                let mut received = 0;
+                let mut lineage_changed = readers.is_empty();
+                let mut index = 0;
+
+                while index < readers.len() {
+                    let page = match client.get_records(&readers[index].shard_iterator, *query.limit()).await {
+                        Err(DynamoDbError::ExpiredIterator(message)) => {
+                            tracing::info!(shard_id = %readers[index].shard_id, message, "Shard iterator expired, renewing it.");
+
+                            let reader = &readers[index];
+                            match client.shard_iterator(stream_arn, &reader.shard_id, reader.iterator_type.clone()).await? {
+                                Some(shard_iterator) => readers[index].shard_iterator = shard_iterator,
+                                None => {
+                                    lineage.complete(&readers.remove(index).shard_id);
+                                    lineage_changed = true;
+                                }
+                            }
+                            continue;
+                        }
+                        result => result?,
+                    };
+
+                    let shard_id = readers[index].shard_id.clone();
+                    for record in page.records {
+                        readers[index].iterator_type = ShardIteratorType::AfterSequenceNumber(record.sequence_number().to_string());
+                        received += 1;
+
+                        yield ShardRecord::new(shard_id.clone(), record);
+                    }
+
+                    match page.next_shard_iterator {
+                        Some(shard_iterator) => {
+                            readers[index].shard_iterator = shard_iterator;
+                            index += 1;
+                        }
+                        None => {
+                            readers.remove(index);
+                            lineage.complete(&shard_id);
+                            lineage_changed = true;
+                        }
+                    }
+                }
+
+                if lineage_changed {
+                    lineage.refresh(client.describe_stream(stream_arn).await?);
+                }
+
+                if received == 0 {
+                    tokio::time::sleep(query.poll_interval()).await;
+                }
+            }
+        })
+    }
+
+    /// Falls back to TRIM_HORIZON when the records at the requested position were already trimmed.
+    async fn shard_iterator(
+        &self,
+        stream_arn: &str,
+        shard_id: &str,
+        iterator_type: ShardIteratorType,
+    ) -> Result<Option<String>, DynamoDbError> {
+        match self.get_shard_iterator(stream_arn, shard_id, &iterator_type).await {
+            Err(DynamoDbError::TrimmedDataAccess(message)) if iterator_type != ShardIteratorType::TrimHorizon => {
+                tracing::warn!(shard_id, message, "Records were trimmed, reading from TRIM_HORIZON.");
+
+                self.get_shard_iterator(stream_arn, shard_id, &ShardIteratorType::TrimHorizon)
+                    .await
+            }
+            result => result,
+        }
+    }
+}
+
+/// `iterator_type` is where to get a new iterator from if the current one expires.
+struct ShardReader {
+    shard_id: String,
+    shard_iterator: String,
+    iterator_type: ShardIteratorType,
+}
+
+/// Decides which shards may be read and from where, so that a child shard is only read
+/// after its parent.
+#[derive(Debug)]
+struct ShardLineage {
+    shards: Vec<Shard>,
+    initial_shard_ids: HashSet<String>,
+    started: HashSet<String>,
+    completed: HashSet<String>,
+    starting_position: StartingPosition,
+    checkpoints: Checkpoints,
+}
+
+impl ShardLineage {
+    fn new(shards: Vec<Shard>, starting_position: &StartingPosition, checkpoints: &Checkpoints) -> Self {
+        let mut lineage = Self {
+            initial_shard_ids: shards.iter().map(|shard| shard.shard_id().to_string()).collect(),
+            shards,
+            started: HashSet::new(),
+            completed: HashSet::new(),
+            starting_position: starting_position.clone(),
+            checkpoints: checkpoints.clone(),
+        };
+
+        // Ancestors of a checkpointed shard were read by the previous reader.
+        for shard_id in checkpoints.keys() {
+            let mut parent_shard_id = lineage.parent_of(shard_id);
+            while let Some(shard_id) = parent_shard_id {
+                parent_shard_id = lineage.parent_of(&shard_id);
+                lineage.completed.insert(shard_id);
+            }
+        }
+
+        // Closed shards only hold records older than the starting position.
+        for shard in &lineage.shards {
+            let skipped = match (&lineage.starting_position, shard.ending_sequence_number()) {
+                (StartingPosition::Latest, Some(_)) => true,
+                (StartingPosition::AtSequenceNumber(sequence_number), Some(ending_sequence_number)) => {
+                    compare_sequence_numbers(ending_sequence_number, sequence_number) == Ordering::Less
+                }
+                _ => false,
+            };
+
+            if skipped && !lineage.checkpoints.contains_key(shard.shard_id()) {
+                lineage.completed.insert(shard.shard_id().to_string());
+            }
+        }
+
+        lineage
+    }
+
+    fn parent_of(&self, shard_id: &str) -> Option<String> {
+        self.shards
+            .iter()
+            .find(|shard| shard.shard_id() == shard_id)
+            .and_then(|shard| shard.parent_shard_id().clone())
+    }
+
+    /// Adds shards created since the last describe. Progress of known shards is kept.
+    fn refresh(&mut self, shards: Vec<Shard>) {
+        self.shards = shards;
+    }
+
+    fn complete(&mut self, shard_id: &str) {
+        self.completed.insert(shard_id.to_string());
+    }
+
+    /// Shards whose parent is done (or was trimmed away), with the position to start reading at.
+    fn take_ready(&mut self) -> Vec<(String, ShardIteratorType)> {
+        let known = self
+            .shards
+            .iter()
+            .map(|shard| shard.shard_id().to_string())
+            .collect::<HashSet<_>>();
+
+        let ready = self
+            .shards
+            .iter()
+            .filter(|shard| !self.started.contains(shard.shard_id()) && !self.completed.contains(shard.shard_id()))
+            .filter(|shard| match shard.parent_shard_id() {
+                Some(parent_shard_id) => !known.contains(parent_shard_id) || self.completed.contains(parent_shard_id),
+                None => true,
+            })
+            .map(|shard| (shard.shard_id().to_string(), self.iterator_type(shard)))
+            .collect::<Vec<_>>();
+
+        for (shard_id, _) in &ready {
+            self.started.insert(shard_id.clone());
+        }
+
+        ready
+    }
+
+    fn iterator_type(&self, shard: &Shard) -> ShardIteratorType {
+        if let Some(sequence_number) = self.checkpoints.get(shard.shard_id()) {
+            return ShardIteratorType::AfterSequenceNumber(sequence_number.clone());
+        }
+
+        // Shards created while reading are read from their beginning.
+        if !self.initial_shard_ids.contains(shard.shard_id()) {
+            return ShardIteratorType::TrimHorizon;
+        }
+
+        match &self.starting_position {
+            StartingPosition::TrimHorizon => ShardIteratorType::TrimHorizon,
+            StartingPosition::Latest => ShardIteratorType::Latest,
+            StartingPosition::AtSequenceNumber(sequence_number) => {
+                let started = shard
+                    .starting_sequence_number()
+                    .as_ref()
+                    .map(|starting| compare_sequence_numbers(starting, sequence_number) != Ordering::Greater)
+                    .unwrap_or(false);
+
+                if started {
+                    ShardIteratorType::AtSequenceNumber(sequence_number.clone())
+                } else {
+                    ShardIteratorType::TrimHorizon
+                }
+            }
+        }
+    }
+
+    fn is_finished(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| shard.is_closed() && self.completed.contains(shard.shard_id()))
+    }
+}
+
+/// Sequence numbers are decimal strings of up to 40 digits.
+fn compare_sequence_numbers(left: &str, right: &str) -> Ordering {
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+/// Stream ARNs look like `arn:aws:dynamodb:<region>:<account>:table/<table name>/stream/<label>`.
+fn table_name_of(stream_arn: &str) -> &str {
+    stream_arn.split('/').nth(1).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use pretty_assertions::assert_eq;
+
+    use crate::query::read_stream::StartingPosition;
+    use crate::stream::{Checkpoints, Shard, ShardIteratorType};
+    use crate::stream_client::ShardLineage;
+
+    fn shards() -> Vec<Shard> {
+        vec![
+            Shard::new("parent", None, Some("100".to_string()), Some("199".to_string())),
+            Shard::new("child", Some("parent".to_string()), Some("200".to_string()), None),
+            Shard::new("orphan", Some("trimmed".to_string()), Some("150".to_string()), None),
+        ]
+    }
+
+    #[test]
+    fn test_children_wait_for_their_parent() {
+        let mut lineage = ShardLineage::new(shards(), &StartingPosition::TrimHorizon, &Checkpoints::new());
+
+        assert_eq!(
+            vec![
+                ("parent".to_string(), ShardIteratorType::TrimHorizon),
+                ("orphan".to_string(), ShardIteratorType::TrimHorizon),
+            ],
+            lineage.take_ready()
+        );
+        assert!(lineage.take_ready().is_empty());
+
+        lineage.complete("parent");
+
+        assert_eq!(
+            vec![("child".to_string(), ShardIteratorType::TrimHorizon)],
+            lineage.take_ready()
+        );
+        assert!(!lineage.is_finished());
+    }
+
+    #[test]
+    fn test_latest_skips_closed_shards() {
+        let mut lineage = ShardLineage::new(shards(), &StartingPosition::Latest, &Checkpoints::new());
+
+        assert_eq!(
+            vec![
+                ("child".to_string(), ShardIteratorType::Latest),
+                ("orphan".to_string(), ShardIteratorType::Latest),
+            ],
+            lineage.take_ready()
+        );
+    }
+
+    #[test]
+    fn test_checkpoints_resume_after_sequence_number() {
+        let checkpoints = Checkpoints::from([("child".to_string(), "250".to_string())]);
+
+        let mut lineage = ShardLineage::new(
+            shards(),
+            &StartingPosition::AtSequenceNumber("160".to_string()),
+            &checkpoints,
+        );
+
+        assert_eq!(
+            vec![
+                ("child".to_string(), ShardIteratorType::AfterSequenceNumber("250".to_string())),
+                ("orphan".to_string(), ShardIteratorType::AtSequenceNumber("160".to_string())),
+            ],
+            lineage.take_ready()
+        );
+    }
+}
@@ -0,0 +1,191 @@
+use futures_util::stream::{self, TryStreamExt};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tokio::io::{AsyncBufRead, AsyncBufReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::api::DynamoDbApi;
+use crate::error::DynamoDbError;
+use crate::query::attribute_value::items;
+use crate::query::batch_write::{BatchWriteQuery, BATCH_WRITE_CHUNK_SIZE};
+use crate::query::put_item::Items;
+use crate::query::scan::ScanQuery;
+
+#[derive(Error, Debug)]
+pub enum TableDumpError {
+    #[error("Could not read or write the dump. Error: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("Line {line} of the dump could not be converted. Error: {message}")]
+    Json { line: usize, message: String },
+    #[error("{0} items could not be written.")]
+    Unprocessed(usize),
+    #[error(transparent)]
+    DynamoDb(#[from] DynamoDbError),
+}
+
+/// One line of a dump, in the format of DynamoDB's own S3 export, e.g. `{"Item": {"id": {"S": "1"}}}`.
+#[derive(Debug, Serialize, Deserialize)]
+struct DumpLine {
+    #[serde(rename = "Item", with = "items")]
+    item: Items,
+}
+
+/// Writes every item of `table_name` to `writer` as JSON Lines, scanning `total_segments` in parallel.
+/// Returns the number of items written.
+pub async fn export_table<D, W>(
+    dynamodb: &D,
+    table_name: &str,
+    total_segments: i32,
+    writer: &mut W,
+) -> Result<usize, TableDumpError>
+where
+    D: DynamoDbApi,
+    W: AsyncWrite + Unpin,
+{
+    let total_segments = total_segments.max(1);
+    let mut items = stream::select_all((0..total_segments).map(|segment| {
+        dynamodb.scan(ScanQuery::new(table_name).with_segment(segment, total_segments))
+    }));
+
+    let mut count = 0;
+    while let Some(item) = items.try_next().await? {
+        count += 1;
+
+        let mut line = serde_json::to_vec(&DumpLine { item }).map_err(|error| TableDumpError::Json {
+            line: count,
+            message: error.to_string(),
+        })?;
+        line.push(b'\n');
+
+        writer.write_all(&line).await?;
+    }
+
+    writer.flush().await?;
+
+    Ok(count)
+}
+
+/// Puts every item of a dump written by `export_table` into `table_name`. Returns the number of items written.
+pub async fn import_table<D, R>(dynamodb: &D, table_name: &str, reader: R) -> Result<usize, TableDumpError>
+where
+    D: DynamoDbApi,
+    R: AsyncBufRead + Unpin,
+{
+    let mut lines = reader.lines();
+    let mut line_number = 0;
+    let mut query = BatchWriteQuery::new();
+    let mut count = 0;
+
+    while let Some(line) = lines.next_line().await? {
+        line_number += 1;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let dump_line = serde_json::from_str::<DumpLine>(&line).map_err(|error| TableDumpError::Json {
+            line: line_number,
+            message: error.to_string(),
+        })?;
+
+        query = query.put(table_name, dump_line.item);
+        if query.len() == BATCH_WRITE_CHUNK_SIZE {
+            count += write(dynamodb, std::mem::take(&mut query)).await?;
+        }
+    }
+
+    if !query.is_empty() {
+        count += write(dynamodb, query).await?;
+    }
+
+    Ok(count)
+}
+
+async fn write<D: DynamoDbApi>(dynamodb: &D, query: BatchWriteQuery) -> Result<usize, TableDumpError> {
+    let output = dynamodb.batch_write(query).await?;
+
+    if !output.is_complete() {
+        return Err(TableDumpError::Unprocessed(output.unprocessed().len()));
+    }
+
+    Ok(output.processed_count())
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use pretty_assertions::assert_eq;
+
+    use crate::api::DynamoDbApi;
+    use crate::in_memory::InMemoryDynamoDb;
+    use crate::query::create_table::CreateTableQuery;
+    use crate::query::put_item::{Items, PutItemQuery};
+    use crate::table_dump::{export_table, import_table, TableDumpError};
+
+    fn create_table_query(table_name: &str) -> CreateTableQuery {
+        serde_json::from_str::<CreateTableQuery>(&format!(
+            r#"{{
+                "TableName": "{}",
+                "KeySchema": [{{"AttributeName": "id", "KeyType": "HASH"}}],
+                "AttributeDefinitions": [{{"AttributeName": "id", "AttributeType": "S"}}],
+                "BillingMode": "PAY_PER_REQUEST"
+            }}"#,
+            table_name
+        ))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_export_and_import_table() {
+        let dynamodb = InMemoryDynamoDb::new();
+        for table_name in ["Source", "Target"] {
+            dynamodb
+                .create_table(table_name, &create_table_query(table_name))
+                .await
+                .unwrap();
+        }
+
+        for id in 0..30 {
+            let item = Items::from([
+                ("id".to_string(), AttributeValue::S(id.to_string())),
+                ("count".to_string(), AttributeValue::N(id.to_string())),
+                (
+                    "tags".to_string(),
+                    AttributeValue::L(vec![AttributeValue::Bool(id % 2 == 0)]),
+                ),
+            ]);
+            dynamodb
+                .put_item(PutItemQuery::new("Source", item, None, None::<String>))
+                .await
+                .unwrap();
+        }
+
+        let mut dump = vec![];
+        assert_eq!(30, export_table(&dynamodb, "Source", 4, &mut dump).await.unwrap());
+        assert_eq!(30, dump.iter().filter(|byte| **byte == b'\n').count());
+        assert!(String::from_utf8(dump.clone()).unwrap().starts_with(r#"{"Item":{"#));
+
+        assert_eq!(30, import_table(&dynamodb, "Target", dump.as_slice()).await.unwrap());
+
+        let sort = |mut items: Vec<Items>| {
+            items.sort_by_key(|item| format!("{:?}", item.get("id")));
+            items
+        };
+        assert_eq!(sort(dynamodb.items("Source")), sort(dynamodb.items("Target")));
+    }
+
+    #[tokio::test]
+    async fn test_import_table_rejects_invalid_line() {
+        let dynamodb = InMemoryDynamoDb::new();
+        dynamodb
+            .create_table("Target", &create_table_query("Target"))
+            .await
+            .unwrap();
+
+        let dump = "{\"Item\":{\"id\":{\"S\":\"1\"}}}\n\n{\"Item\":{\"id\":\"2\"}}\n";
+
+        assert!(matches!(
+            import_table(&dynamodb, "Target", dump.as_bytes()).await,
+            Err(TableDumpError::Json { line: 3, .. })
+        ));
+    }
+}
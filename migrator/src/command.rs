@@ -1,8 +1,13 @@
+pub mod create;
 pub mod list;
 pub mod migrate;
 pub mod migrate_operation_type;
 pub mod migrate_type;
+#[cfg(test)]
+mod migration_dir;
 pub mod reset;
+pub mod rollback_target;
+pub mod status;
 
 #[derive(Debug, Clone)]
 pub struct Output {
@@ -30,6 +35,5 @@ impl Output {
 #[derive(Debug, Copy, Clone)]
 pub enum ExitCode {
     Succeed = 0,
-    #[allow(dead_code)]
     Failed = 1,
 }
@@ -0,0 +1,160 @@
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use chrono::Utc;
+use clap::ValueEnum;
+
+use crate::command::migrate::DEFAULT_MIGRATION_FILE_PATH;
+use crate::command::{ExitCode, Output};
+
+/// Versions are UTC, like `ExecutedAt`, so files created in different time zones stay in order.
+pub(crate) const VERSION_FORMAT: &str = "%Y%m%d%H%M";
+
+/// The operation of a new migration file, which decides its skeleton.
+#[derive(PartialEq, Debug, Clone, Copy, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum MigrationTemplate {
+    CreateTable,
+    DeleteTable,
+    UpdateTable,
+    PutItem,
+    UpdateItem,
+    BatchWrite,
+    DeleteItem,
+}
+
+impl MigrationTemplate {
+    /// The `{command}` part of the file name.
+    pub fn operation(&self) -> &'static str {
+        match self {
+            MigrationTemplate::CreateTable => "create_table",
+            MigrationTemplate::DeleteTable => "delete_table",
+            MigrationTemplate::UpdateTable => "update_table",
+            MigrationTemplate::PutItem => "put_item",
+            MigrationTemplate::UpdateItem => "update_item",
+            MigrationTemplate::BatchWrite => "batch_write",
+            MigrationTemplate::DeleteItem => "delete_item",
+        }
+    }
+
+    pub fn skeleton(&self) -> &'static str {
+        match self {
+            MigrationTemplate::CreateTable => include_str!("../../templates/create_table.json"),
+            MigrationTemplate::DeleteTable => include_str!("../../templates/delete_table.json"),
+            MigrationTemplate::UpdateTable => include_str!("../../templates/update_table.json"),
+            MigrationTemplate::PutItem => include_str!("../../templates/put_item.json"),
+            MigrationTemplate::UpdateItem => include_str!("../../templates/update_item.json"),
+            MigrationTemplate::BatchWrite => include_str!("../../templates/batch_write.json"),
+            MigrationTemplate::DeleteItem => include_str!("../../templates/delete_item.json"),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+pub struct Create {}
+
+impl Create {
+    pub fn new() -> Self {
+        Self {}
+    }
+
+    pub fn execute(
+        &self,
+        name: &str,
+        template: &MigrationTemplate,
+        migrate_path: Option<&PathBuf>,
+    ) -> Result<Output> {
+        let path = migrate_path
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_MIGRATION_FILE_PATH));
+        let version = Utc::now().format(VERSION_FORMAT).to_string();
+
+        let file = self.write(&path, &version, name, template)?;
+
+        Ok(Output::new(
+            ExitCode::Succeed,
+            format!("Created migration file {}.", file.display()),
+        ))
+    }
+
+    /// Writes `{version}_{name}.{operation}.json` into `dir`, failing if the file already exists.
+    pub fn write(
+        &self,
+        dir: &Path,
+        version: &str,
+        name: &str,
+        template: &MigrationTemplate,
+    ) -> Result<PathBuf> {
+        if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+            return Err(anyhow!(format!(
+                "Migration name must consist of letters, digits and underscores. Name: {}",
+                name
+            )));
+        }
+
+        let file = dir.join(format!("{}_{}.{}.json", version, name, template.operation()));
+
+        let mut data = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&file)
+            .context(format!("Cannot create migration file. File: {:?}", file))?;
+
+        data.write_all(template.skeleton().as_bytes())
+            .context(format!("Cannot write migration file. File: {:?}", file))?;
+
+        Ok(file)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use dynamodb_client::query::create_table::CreateTableQuery;
+    use dynamodb_client::query::delete_item::DeleteItemQuery;
+    use dynamodb_client::query::put_item::PutItemQuery;
+    use dynamodb_client::query::update_item::UpdateItemQuery;
+    use dynamodb_client::query::update_table::UpdateTableQuery;
+    use pretty_assertions::assert_eq;
+
+    use crate::command::create::{Create, MigrationTemplate};
+    use crate::command::migrate_operation_type::MigrateOperationType;
+    use crate::command::migration_dir::MigrationDir;
+
+    #[test]
+    fn test_templates_parse() {
+        serde_json::from_str::<CreateTableQuery>(MigrationTemplate::CreateTable.skeleton()).unwrap();
+        serde_json::from_str::<UpdateTableQuery>(MigrationTemplate::UpdateTable.skeleton()).unwrap();
+        serde_json::from_str::<PutItemQuery>(MigrationTemplate::PutItem.skeleton()).unwrap();
+        serde_json::from_str::<UpdateItemQuery>(MigrationTemplate::UpdateItem.skeleton()).unwrap();
+        serde_json::from_str::<Vec<PutItemQuery>>(MigrationTemplate::BatchWrite.skeleton()).unwrap();
+        serde_json::from_str::<DeleteItemQuery>(MigrationTemplate::DeleteItem.skeleton()).unwrap();
+    }
+
+    #[test]
+    fn test_write_refuses_to_clobber() {
+        let dir = MigrationDir::new();
+
+        let create = Create::new();
+        let file = create
+            .write(&dir.path(), "202303010000", "add_users_table", &MigrationTemplate::CreateTable)
+            .unwrap();
+
+        assert_eq!(
+            "202303010000_add_users_table.create_table.json",
+            file.file_name().unwrap().to_string_lossy()
+        );
+        assert_eq!(MigrateOperationType::CreateTable, MigrateOperationType::resolve(&file).unwrap());
+        assert_eq!(MigrationTemplate::CreateTable.skeleton(), fs::read_to_string(&file).unwrap());
+
+        assert!(create
+            .write(&dir.path(), "202303010000", "add_users_table", &MigrationTemplate::CreateTable)
+            .is_err());
+        assert!(create
+            .write(&dir.path(), "202303010000", "add.users", &MigrationTemplate::CreateTable)
+            .is_err());
+    }
+}
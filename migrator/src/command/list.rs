@@ -11,8 +11,10 @@ impl List {
     pub async fn execute(self) -> Output {
         let message = "Usage:  migrator [Command] [Option] \n
         Options:
+            create  Create a migration file from a template.
             list    Display command list.
             migrate Execute migration..
+            status  Display applied, pending and orphaned migrations.
         ";
 
         Output::new(ExitCode::Succeed, message)
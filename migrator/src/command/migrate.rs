@@ -2,31 +2,45 @@ use anyhow::{anyhow, Context};
 use aws_sdk_dynamodb::model::AttributeValue;
 use aws_sdk_dynamodb::output::PutItemOutput;
 use chrono::Utc;
-use dynamodb_client::client::{Client, ExistsTableResultType};
+use dynamodb_client::api::DynamoDbApi;
+use dynamodb_client::client::ExistsTableResultType;
+use dynamodb_client::error::DynamoDbError;
+use dynamodb_client::query::batch_write::BatchWriteQuery;
 use dynamodb_client::query::create_table::CreateTableQuery;
+use dynamodb_client::query::delete_item::DeleteItemQuery;
 use dynamodb_client::query::delete_table::DeleteTableQuery;
+use dynamodb_client::query::describe_table::DescribeTableQuery;
 use dynamodb_client::query::get_item::{GetItemQuery, Key};
 use dynamodb_client::query::put_item::{Items, PutItemQuery};
+use dynamodb_client::query::scan::ScanQuery;
+use dynamodb_client::query::update_item::UpdateItemQuery;
+use dynamodb_client::query::update_table::UpdateTableQuery;
+use std::collections::HashMap;
 use std::fmt::Debug;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use std::{env, fs};
 use thiserror::__private::PathAsDisplay;
+use tokio_stream::StreamExt;
 
 use crate::command::migrate_operation_type::MigrateOperationType;
 use crate::command::migrate_type::MigrateType;
+use crate::command::rollback_target::RollbackTarget;
 use crate::command::{ExitCode, Output};
 use crate::parser::Parser;
 
 const RESOURCE_FILE_DIR: &str = "resource";
-const DEFAULT_MIGRATION_FILE_PATH: &str = "migrations";
+pub(crate) const DEFAULT_MIGRATION_FILE_PATH: &str = "migrations";
+const TABLE_WAIT_TIMEOUT: Duration = Duration::from_secs(300);
+const DOWN_FILE_SUFFIX: &str = ".down.json";
 
 #[derive(Debug, Clone)]
-pub struct Migrate {
-    client: Client
+pub struct Migrate<D: DynamoDbApi> {
+    client: D
 }
 
-impl Migrate {
-    pub fn new(client: Client) -> Self {
+impl<D: DynamoDbApi> Migrate<D> {
+    pub fn new(client: D) -> Self {
         Self { client }
     }
 
@@ -55,11 +69,30 @@ impl Migrate {
 
                 Ok(Output::new(ExitCode::Succeed, "All migrate succeed."))
             },
-            MigrateType::Down => Ok(Output::new(ExitCode::Succeed, "Migrate down succeed."))
+            MigrateType::Down => self.rollback(migrate_path, &RollbackTarget::default()).await,
         }
     }
 
-    fn read_migration_files(&self, current_path: PathBuf) -> anyhow::Result<Vec<PathBuf>> {
+    /// `migrate down` rolling back the migrations selected by `rollback_target`.
+    pub async fn rollback(
+        &self,
+        migrate_path: Option<&PathBuf>,
+        rollback_target: &RollbackTarget,
+    ) -> anyhow::Result<Output> {
+        let path =
+            self.migrate_path_resolver()(migrate_path, PathBuf::from(DEFAULT_MIGRATION_FILE_PATH));
+
+        let rolled_back = self.rollback_migrations(path, rollback_target).await.map_err(|error| {
+            anyhow!(format!("Failed rollback migration. Error: {}", error))
+        })?;
+
+        Ok(Output::new(
+            ExitCode::Succeed,
+            format!("Migrate down succeed. {} migrations were rolled back.", rolled_back),
+        ))
+    }
+
+    pub(crate) fn read_migration_files(current_path: PathBuf) -> anyhow::Result<Vec<PathBuf>> {
         let directories = fs::read_dir(&current_path).context(format!(
             "Cannot resolve path. File: {} ",
             current_path.as_display()
@@ -74,8 +107,7 @@ impl Migrate {
     }
 
     async fn create_migration_table_for_dynamodb(&self) -> anyhow::Result<()> {
-        for migration_file in self
-            .read_migration_files(self.migration_dir()?)
+        for migration_file in Self::read_migration_files(self.migration_dir()?)
             .context("")?
         {
             let data =
@@ -94,26 +126,27 @@ impl Migrate {
                     .await
                     .context("Cannot create table.")?;
             }
+
+            self.client
+                .wait_until_active(query.table_name(), TABLE_WAIT_TIMEOUT)
+                .await
+                .context("Table did not become active.")?;
         }
 
         Ok(())
     }
 
     async fn migrate(&self, target_path: PathBuf) -> anyhow::Result<()> {
-        let mut files = self
-            .read_migration_files(target_path)
+        let mut files = Self::read_migration_files(target_path)
             .context("Cannot read migration file.")?;
 
+        files.retain(|file| !is_down_file(file));
         files.sort();
 
         for file in files {
             let operation_type = MigrateOperationType::resolve(&file)?;
 
-            let file_name = file
-                .file_name()
-                .context(format!("Cannot get filename from PathBuf. {:?}", file))?
-                .to_string_lossy()
-                .to_string();
+            let file_name = file_name_of(&file)?;
 
             let query = GetItemQuery::new(
                 "migrations".to_string(),
@@ -130,42 +163,217 @@ impl Migrate {
                         file_name
                     )
                 }
-                (None, MigrateOperationType::CreateTable) => {
-                    let data = std::fs::File::open(&file).context(format!(
-                        "Cannot open migration file. FileName: {}",
+                (None, MigrateOperationType::UndefinedOperation(_)) => {
+                    println!("File name {} was skipped. Unsupported command.", file_name)
+                }
+                (None, operation_type) => {
+                    self.apply(&file, &operation_type).await?;
+                    self.add_migration_record(&file).await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the down files of the applied migrations selected by `rollback_target`, newest first,
+    /// and deletes their records. Returns the number of migrations rolled back.
+    async fn rollback_migrations(
+        &self,
+        target_path: PathBuf,
+        rollback_target: &RollbackTarget,
+    ) -> anyhow::Result<usize> {
+        let mut down_files = HashMap::new();
+        for file in Self::read_migration_files(target_path)
+            .context("Cannot read migration file.")?
+        {
+            if is_down_file(&file) {
+                let file_name = file_name_of(&file)?;
+
+                if let Some(duplicate) = down_files.insert(migration_id(&file_name).to_string(), file) {
+                    return Err(anyhow!(format!(
+                        "Several down files share {}. FileName: {}, {}",
+                        migration_id(&file_name),
+                        file_name_of(&duplicate)?,
                         file_name
-                    ))?;
+                    )));
+                }
+            }
+        }
 
-                    let query = Parser::from_json_file::<CreateTableQuery>(&data)?;
+        let mut applied = self
+            .client
+            .scan(ScanQuery::new("migrations"))
+            .collect::<Result<Vec<_>, _>>()
+            .await?
+            .iter()
+            .filter_map(|item| match item.get("FileName") {
+                Some(AttributeValue::S(file_name)) => Some(file_name.clone()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        applied.sort();
+        applied.reverse();
+
+        if let RollbackTarget::Version(version) = rollback_target {
+            RollbackTarget::version(version).map_err(|error| anyhow!(error))?;
+
+            if !applied.iter().any(|file_name| migration_version(file_name) == version) {
+                return Err(anyhow!(format!(
+                    "No applied migration has this version. Version: {}",
+                    version
+                )));
+            }
+        }
 
-                    self.client
-                        .create_table(query.table_name(), &query)
-                        .await?;
-                    self.add_migration_record(&file).await?;
+        let targets = match rollback_target {
+            RollbackTarget::Steps(steps) => applied.into_iter().take(*steps).collect::<Vec<_>>(),
+            RollbackTarget::Version(version) => applied
+                .into_iter()
+                .filter(|file_name| migration_version(file_name) > version.as_str())
+                .collect::<Vec<_>>(),
+        };
+
+        let missing = targets
+            .iter()
+            .filter(|file_name| !down_files.contains_key(migration_id(file_name)))
+            .cloned()
+            .collect::<Vec<_>>();
+        if !missing.is_empty() {
+            return Err(anyhow!(format!(
+                "Down file was not found. FileName: {}",
+                missing.join(", ")
+            )));
+        }
+
+        for file_name in &targets {
+            let down_file = &down_files[migration_id(file_name)];
+
+            println!("Rolling back file name {}", file_name);
+
+            match MigrateOperationType::resolve(down_file)? {
+                MigrateOperationType::UndefinedOperation(name) => {
+                    return Err(anyhow!(format!("Unsupported down file. FileName: {}", name)));
                 }
-                (None, MigrateOperationType::DeleteTable) => {
-                    let data = std::fs::File::open(&file).context(format!(
-                        "Cannot open migration file. FileName: {}",
-                        file_name
-                    ))?;
+                operation_type => self.apply(down_file, &operation_type).await?,
+            }
+
+            self.delete_migration_record(file_name).await?;
+        }
 
-                    let query = Parser::from_json_file::<DeleteTableQuery>(&data)?;
+        Ok(targets.len())
+    }
 
-                    let delete_result = self.client
-                        .delete_table(&query)
-                        .await
-                        .context("Cannot delete table. {}");
+    async fn apply(&self, file: &Path, operation_type: &MigrateOperationType) -> anyhow::Result<()> {
+        let data = std::fs::File::open(file).context(format!(
+            "Cannot open migration file. FileName: {:?}",
+            file
+        ))?;
 
-                    dbg!(&delete_result);
+        match operation_type {
+            MigrateOperationType::CreateTable => {
+                let query = Parser::from_json_file::<CreateTableQuery>(&data)?;
 
-                    delete_result?;
+                self.client
+                    .create_table(query.table_name(), &query)
+                    .await?;
+                self.client
+                    .wait_until_active(query.table_name(), TABLE_WAIT_TIMEOUT)
+                    .await?;
+            }
+            MigrateOperationType::DeleteTable => {
+                let query = Parser::from_json_file::<DeleteTableQuery>(&data)?;
 
-                    self.add_migration_record(&file).await?;
+                self.client
+                    .delete_table(&query)
+                    .await
+                    .context("Cannot delete table.")?;
+                self.client
+                    .wait_until_deleted(query.table_name(), TABLE_WAIT_TIMEOUT)
+                    .await?;
+            }
+            MigrateOperationType::UpdateTable => {
+                let query = Parser::from_json_file::<UpdateTableQuery>(&data)?;
+
+                // DynamoDB rejects a second change while the table or an index is still updating,
+                // and rejects changes already in effect, e.g. steps applied before a failed re-run.
+                for step in query.into_steps() {
+                    let description = self
+                        .client
+                        .describe_table(&DescribeTableQuery::new(step.table_name()))
+                        .await?;
+
+                    let Some(step) = step.pending_against(&description) else {
+                        println!("A step of {:?} is already in effect. It was skipped.", file);
+                        continue;
+                    };
+
+                    self.client
+                        .update_table(&step)
+                        .await
+                        .context("Cannot update table.")?;
+                    self.client
+                        .wait_until_active(step.table_name(), TABLE_WAIT_TIMEOUT)
+                        .await?;
                 }
-                (_, _) => {
-                    println!("File name {} was skipped. Unsupported command.", file_name)
+            }
+            MigrateOperationType::PutItem => {
+                let query = Parser::from_json_file::<PutItemQuery>(&data)?;
+
+                match self.client.put_item(query).await {
+                    Err(DynamoDbError::ConditionalCheckFailed(_)) => {
+                        println!("Condition of {:?} was not met. The item was left as it is.", file)
+                    }
+                    result => {
+                        result.context("Cannot put item.")?;
+                    }
                 }
             }
+            MigrateOperationType::UpdateItem => {
+                let query = Parser::from_json_file::<UpdateItemQuery>(&data)?;
+
+                match self.client.update_item(query).await {
+                    Err(DynamoDbError::ConditionalCheckFailed(_)) => {
+                        println!("Condition of {:?} was not met. The item was left as it is.", file)
+                    }
+                    result => {
+                        result.context("Cannot update item.")?;
+                    }
+                }
+            }
+            MigrateOperationType::BatchWrite => {
+                let put_items = Parser::from_json_file::<Vec<PutItemQuery>>(&data)?;
+
+                let query = BatchWriteQuery::try_from(put_items)
+                    .map_err(|error| anyhow!(format!("{} Use a put_item file instead. File: {:?}", error, file)))?;
+
+                let output = self
+                    .client
+                    .batch_write(query)
+                    .await
+                    .context("Cannot write items.")?;
+                if !output.is_complete() {
+                    return Err(anyhow!(format!(
+                        "{} items were left unprocessed. File: {:?}",
+                        output.unprocessed().len(),
+                        file
+                    )));
+                }
+            }
+            MigrateOperationType::DeleteItem => {
+                let query = Parser::from_json_file::<DeleteItemQuery>(&data)?;
+
+                match self.client.delete_item(query).await {
+                    Err(DynamoDbError::ConditionalCheckFailed(_)) => {
+                        println!("Condition of {:?} was not met. The item was left as it is.", file)
+                    }
+                    result => {
+                        result.context("Cannot delete item.")?;
+                    }
+                }
+            }
+            MigrateOperationType::UndefinedOperation(_) => {}
         }
 
         Ok(())
@@ -189,13 +397,8 @@ impl Migrate {
         }
     }
 
-    async fn add_migration_record(&self, file: &PathBuf) -> anyhow::Result<PutItemOutput> {
-        let file_name = AttributeValue::S(
-            file.file_name()
-                .context(format!("Cannot get filename from PathBuf. {:?}", file))?
-                .to_string_lossy()
-                .to_string(),
-        );
+    async fn add_migration_record(&self, file: &Path) -> anyhow::Result<PutItemOutput> {
+        let file_name = AttributeValue::S(file_name_of(file)?);
 
         let mut items = Items::new();
 
@@ -214,4 +417,324 @@ impl Migrate {
 
         Ok(response)
     }
+
+    async fn delete_migration_record(&self, file_name: &str) -> anyhow::Result<()> {
+        let keys = HashMap::from([("FileName".to_string(), AttributeValue::S(file_name.to_string()))]);
+
+        self.client
+            .delete_item(DeleteItemQuery::new("migrations", keys))
+            .await
+            .context("Failed delete item.")?;
+
+        Ok(())
+    }
+}
+
+/// `{version}_{title}.{command}.down.json` reverts the migration `{version}_{title}`.
+pub(crate) fn is_down_file(file: &Path) -> bool {
+    file.to_string_lossy().ends_with(DOWN_FILE_SUFFIX)
+}
+
+pub(crate) fn file_name_of(file: &Path) -> anyhow::Result<String> {
+    Ok(file
+        .file_name()
+        .context(format!("Cannot get filename from PathBuf. {:?}", file))?
+        .to_string_lossy()
+        .to_string())
+}
+
+/// `{version}_{title}`, shared by a migration file and its down file.
+fn migration_id(file_name: &str) -> &str {
+    file_name.split('.').next().unwrap_or(file_name)
+}
+
+fn migration_version(file_name: &str) -> &str {
+    file_name.split('_').next().unwrap_or(file_name)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+    use std::time::Duration;
+
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use dynamodb_client::api::DynamoDbApi;
+    use dynamodb_client::client::ExistsTableResultType;
+    use dynamodb_client::in_memory::InMemoryDynamoDb;
+    use pretty_assertions::assert_eq;
+
+    use crate::command::migrate::Migrate;
+    use crate::command::migrate_type::MigrateType;
+    use crate::command::migration_dir::MigrationDir;
+    use crate::command::rollback_target::RollbackTarget;
+
+    #[tokio::test]
+    async fn test_migrate_up() {
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        let path = PathBuf::from("migrations");
+
+        migrate.execute(&MigrateType::Up, Some(&path)).await.unwrap();
+
+        assert_eq!(ExistsTableResultType::Found, dynamodb.exists_table("Messages").await.unwrap());
+        assert_eq!(ExistsTableResultType::NotFound, dynamodb.exists_table("Tests").await.unwrap());
+        assert_eq!(3, dynamodb.items("migrations").len());
+
+        migrate.execute(&MigrateType::Up, Some(&path)).await.unwrap();
+
+        assert_eq!(3, dynamodb.items("migrations").len());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_down() {
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        let path = PathBuf::from("migrations");
+
+        migrate.execute(&MigrateType::Up, Some(&path)).await.unwrap();
+
+        migrate.rollback(Some(&path), &RollbackTarget::Steps(1)).await.unwrap();
+        assert_eq!(ExistsTableResultType::Found, dynamodb.exists_table("Tests").await.unwrap());
+        assert_eq!(2, dynamodb.items("migrations").len());
+
+        let target = RollbackTarget::Version("202302142330".to_string());
+        migrate.rollback(Some(&path), &target).await.unwrap();
+        assert_eq!(ExistsTableResultType::NotFound, dynamodb.exists_table("Tests").await.unwrap());
+        assert_eq!(ExistsTableResultType::Found, dynamodb.exists_table("Messages").await.unwrap());
+        assert_eq!(1, dynamodb.items("migrations").len());
+
+        migrate.rollback(Some(&path), &RollbackTarget::Steps(5)).await.unwrap();
+        assert_eq!(ExistsTableResultType::NotFound, dynamodb.exists_table("Messages").await.unwrap());
+        assert_eq!(0, dynamodb.items("migrations").len());
+
+        migrate.execute(&MigrateType::Up, Some(&path)).await.unwrap();
+        assert_eq!(ExistsTableResultType::Found, dynamodb.exists_table("Messages").await.unwrap());
+        assert_eq!(3, dynamodb.items("migrations").len());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_rejects_invalid_versions() {
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        let path = PathBuf::from("migrations");
+        migrate.execute(&MigrateType::Up, Some(&path)).await.unwrap();
+
+        assert!(RollbackTarget::version("20230214").is_err());
+        assert!(RollbackTarget::version("202302142360").is_err());
+        assert_eq!(
+            Ok(RollbackTarget::Version("202302142330".to_string())),
+            RollbackTarget::version("202302142330")
+        );
+
+        for version in ["20230214", "202302142331"] {
+            let target = RollbackTarget::Version(version.to_string());
+            assert!(migrate.rollback(Some(&path), &target).await.is_err());
+        }
+        assert_eq!(3, dynamodb.items("migrations").len());
+    }
+
+    #[tokio::test]
+    async fn test_rollback_rejects_duplicate_down_files() {
+        let dir = MigrationDir::new();
+        dir.copy("202302142330_add_message_table.create_table.json");
+        dir.copy("202302142330_add_message_table.delete_table.down.json");
+        dir.write(
+            "202302142330_add_message_table.delete_item.down.json",
+            r#"{"TableName": "Messages", "Key": {"message_id": {"S": "1"}}}"#,
+        );
+
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        migrate.execute(&MigrateType::Up, Some(&dir.path())).await.unwrap();
+
+        let error = migrate
+            .rollback(Some(&dir.path()), &RollbackTarget::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("Several down files share 202302142330_add_message_table."));
+        assert_eq!(ExistsTableResultType::Found, dynamodb.exists_table("Messages").await.unwrap());
+        assert_eq!(1, dynamodb.items("migrations").len());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_update_table() {
+        let dir = MigrationDir::new();
+        dir.copy("202302142330_add_message_table.create_table.json");
+        dir.write(
+            "202303010000_add_channel_index.update_table.json",
+            r#"{
+                "TableName": "Messages",
+                "AttributeDefinitions": [{"AttributeName": "channel_id", "AttributeType": "S"}],
+                "BillingMode": "PROVISIONED",
+                "ProvisionedThroughput": {"ReadCapacityUnits": 5, "WriteCapacityUnits": 5},
+                "StreamSpecification": {"StreamEnabled": false},
+                "GlobalSecondaryIndexUpdates": [
+                    {"Create": {
+                        "IndexName": "channel_id-index",
+                        "KeySchema": [{"AttributeName": "channel_id", "KeyType": "HASH"}],
+                        "Projection": {"ProjectionType": "KEYS_ONLY"},
+                        "ProvisionedThroughput": {"ReadCapacityUnits": 5, "WriteCapacityUnits": 5}
+                    }}
+                ]
+            }"#,
+        );
+
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        migrate.execute(&MigrateType::Up, Some(&dir.path())).await.unwrap();
+
+        let description = dynamodb
+            .wait_until_active("Messages", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(1, description.global_secondary_indexes().len());
+        assert_eq!("channel_id-index", description.global_secondary_indexes()[0].index_name());
+        assert!(!description.stream_specification().as_ref().unwrap().stream_enabled());
+        assert_eq!(2, dynamodb.items("migrations").len());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_update_table_skips_steps_in_effect() {
+        let file_name = "202303010000_add_channel_index.update_table.json";
+        let update_table = |index_updates: &str| {
+            format!(
+                r#"{{
+                    "TableName": "Messages",
+                    "AttributeDefinitions": [{{"AttributeName": "channel_id", "AttributeType": "S"}}],
+                    "ProvisionedThroughput": {{"ReadCapacityUnits": 5, "WriteCapacityUnits": 5}},
+                    "StreamSpecification": {{"StreamEnabled": false}},
+                    "GlobalSecondaryIndexUpdates": [{}]
+                }}"#,
+                index_updates
+            )
+        };
+        let create_index = r#"{"Create": {
+            "IndexName": "channel_id-index",
+            "KeySchema": [{"AttributeName": "channel_id", "KeyType": "HASH"}],
+            "Projection": {"ProjectionType": "KEYS_ONLY"}
+        }}"#;
+
+        let dir = MigrationDir::new();
+        dir.copy("202302142330_add_message_table.create_table.json");
+        dir.write(
+            file_name,
+            &update_table(&format!(
+                r#"{}, {{"Create": {{
+                    "IndexName": "broken-index",
+                    "KeySchema": [{{"AttributeName": "channel_id", "KeyType": "RANGE"}}],
+                    "Projection": {{"ProjectionType": "KEYS_ONLY"}}
+                }}}}"#,
+                create_index
+            )),
+        );
+
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        assert!(migrate.execute(&MigrateType::Up, Some(&dir.path())).await.is_err());
+        assert_eq!(1, dynamodb.items("migrations").len());
+
+        dir.write(file_name, &update_table(create_index));
+        migrate.execute(&MigrateType::Up, Some(&dir.path())).await.unwrap();
+
+        let description = dynamodb
+            .wait_until_active("Messages", Duration::from_secs(1))
+            .await
+            .unwrap();
+        assert_eq!(1, description.global_secondary_indexes().len());
+        assert!(!description.stream_specification().as_ref().unwrap().stream_enabled());
+        assert_eq!(2, dynamodb.items("migrations").len());
+    }
+
+    #[tokio::test]
+    async fn test_migrate_items() {
+        let dir = MigrationDir::new();
+        dir.copy("202302142330_add_message_table.create_table.json");
+        dir.write(
+            "202303010000_seed_messages.batch_write.json",
+            r#"[
+                {"TableName": "Messages", "Item": {"message_id": {"S": "welcome"}, "text": {"S": "Welcome!"}}},
+                {"TableName": "Messages", "Item": {"message_id": {"S": "rules"}, "text": {"S": "Be nice."}}}
+            ]"#,
+        );
+        dir.write(
+            "202303010001_keep_welcome.put_item.json",
+            r#"{
+                "TableName": "Messages",
+                "Item": {"message_id": {"S": "welcome"}, "text": {"S": "Overwritten"}},
+                "ConditionExpression": "attribute_not_exists(message_id)"
+            }"#,
+        );
+        dir.write(
+            "202303010002_remove_rules.delete_item.json",
+            r#"{"TableName": "Messages", "Key": {"message_id": {"S": "rules"}}}"#,
+        );
+        dir.write(
+            "202303010002_remove_rules.put_item.down.json",
+            r#"{"TableName": "Messages", "Item": {"message_id": {"S": "rules"}, "text": {"S": "Be nice."}}}"#,
+        );
+
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        migrate.execute(&MigrateType::Up, Some(&dir.path())).await.unwrap();
+
+        let items = dynamodb.items("Messages");
+        assert_eq!(1, items.len());
+        assert_eq!(Some(&AttributeValue::S("Welcome!".to_string())), items[0].get("text"));
+        assert_eq!(4, dynamodb.items("migrations").len());
+
+        migrate.rollback(Some(&dir.path()), &RollbackTarget::Steps(1)).await.unwrap();
+
+        assert_eq!(2, dynamodb.items("Messages").len());
+        assert_eq!(3, dynamodb.items("migrations").len());
+
+        dir.write(
+            "202303010003_guarded_seed.batch_write.json",
+            r#"[{
+                "TableName": "Messages",
+                "Item": {"message_id": {"S": "welcome"}, "text": {"S": "Overwritten"}},
+                "ConditionExpression": "attribute_not_exists(message_id)"
+            }]"#,
+        );
+
+        let result = migrate.execute(&MigrateType::Up, Some(&dir.path())).await;
+        assert!(result.unwrap_err().to_string().contains("202303010003_guarded_seed.batch_write.json"));
+        assert_eq!(
+            Some(&AttributeValue::S("Welcome!".to_string())),
+            dynamodb.items("Messages")[0].get("text")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_migrate_update_item() {
+        let dir = MigrationDir::new();
+        dir.copy("202302142330_add_message_table.create_table.json");
+        dir.write(
+            "202303010000_seed_welcome.put_item.json",
+            r#"{"TableName": "Messages", "Item": {"message_id": {"S": "welcome"}, "text": {"S": "Welcome!"}}}"#,
+        );
+        let update_item = |message_id: &str, text: &str| {
+            format!(
+                r##"{{
+                    "TableName": "Messages",
+                    "Key": {{"message_id": {{"S": "{}"}}}},
+                    "UpdateExpression": "SET #text = :text",
+                    "ConditionExpression": "attribute_exists(message_id)",
+                    "ExpressionAttributeNames": {{"#text": "text"}},
+                    "ExpressionAttributeValues": {{":text": {{"S": "{}"}}}}
+                }}"##,
+                message_id, text
+            )
+        };
+        dir.write("202303010001_edit_welcome.update_item.json", &update_item("welcome", "Hello!"));
+        dir.write("202303010002_edit_missing.update_item.json", &update_item("missing", "Nobody"));
+
+        let dynamodb = InMemoryDynamoDb::new();
+        let migrate = Migrate::new(dynamodb.clone());
+        migrate.execute(&MigrateType::Up, Some(&dir.path())).await.unwrap();
+
+        let items = dynamodb.items("Messages");
+        assert_eq!(1, items.len());
+        assert_eq!(Some(&AttributeValue::S("Hello!".to_string())), items[0].get("text"));
+        assert_eq!(4, dynamodb.items("migrations").len());
+    }
 }
@@ -5,6 +5,11 @@ use std::path::Path;
 pub enum MigrateOperationType {
     CreateTable,
     DeleteTable,
+    UpdateTable,
+    PutItem,
+    UpdateItem,
+    BatchWrite,
+    DeleteItem,
     UndefinedOperation(String),
 }
 
@@ -26,6 +31,46 @@ impl MigrateOperationType {
             return Ok(MigrateOperationType::DeleteTable);
         }
 
+        if name
+            .to_str()
+            .context("Failed to_str name.")?
+            .contains(".update_table.")
+        {
+            return Ok(MigrateOperationType::UpdateTable);
+        }
+
+        if name
+            .to_str()
+            .context("Failed to_str name.")?
+            .contains(".put_item.")
+        {
+            return Ok(MigrateOperationType::PutItem);
+        }
+
+        if name
+            .to_str()
+            .context("Failed to_str name.")?
+            .contains(".update_item.")
+        {
+            return Ok(MigrateOperationType::UpdateItem);
+        }
+
+        if name
+            .to_str()
+            .context("Failed to_str name.")?
+            .contains(".batch_write.")
+        {
+            return Ok(MigrateOperationType::BatchWrite);
+        }
+
+        if name
+            .to_str()
+            .context("Failed to_str name.")?
+            .contains(".delete_item.")
+        {
+            return Ok(MigrateOperationType::DeleteItem);
+        }
+
         Ok(MigrateOperationType::UndefinedOperation(
             name.to_str().context("Failed to_str name.")?.to_string(),
         ))
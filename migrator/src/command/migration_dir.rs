@@ -0,0 +1,31 @@
+use std::fs;
+use std::path::PathBuf;
+
+use tempfile::TempDir;
+
+/// A migrations directory for tests, removed on drop even when the test fails.
+pub(crate) struct MigrationDir {
+    dir: TempDir,
+}
+
+impl MigrationDir {
+    pub(crate) fn new() -> Self {
+        Self {
+            dir: TempDir::new().expect("Cannot create temporary migrations directory."),
+        }
+    }
+
+    /// Copies a file of the project's `migrations` directory.
+    pub(crate) fn copy(&self, file_name: &str) {
+        fs::copy(PathBuf::from("migrations").join(file_name), self.dir.path().join(file_name))
+            .expect("Cannot copy migration file.");
+    }
+
+    pub(crate) fn write(&self, file_name: &str, contents: &str) {
+        fs::write(self.dir.path().join(file_name), contents).expect("Cannot write migration file.");
+    }
+
+    pub(crate) fn path(&self) -> PathBuf {
+        self.dir.path().to_path_buf()
+    }
+}
@@ -1,5 +1,5 @@
 use anyhow::{anyhow, Result};
-use dynamodb_client::client::Client;
+use dynamodb_client::api::DynamoDbApi;
 use dynamodb_client::query::delete_table::DeleteTableQuery;
 use dynamodb_client::query::list_tables::ListTablesQuery;
 use tokio_stream::StreamExt;
@@ -7,12 +7,12 @@ use tokio_stream::StreamExt;
 use crate::command::{ExitCode, Output};
 
 #[derive(Debug, Clone)]
-pub struct Reset {
-    client: Client
+pub struct Reset<D: DynamoDbApi> {
+    client: D
 }
 
-impl Reset {
-    pub fn new(client: Client) -> Self {
+impl<D: DynamoDbApi> Reset<D> {
+    pub fn new(client: D) -> Self {
         Self { client }
     }
 
@@ -38,18 +38,11 @@ impl Reset {
     }
 
     async fn find_table_names(&self) -> Result<Vec<String>> {
-        let result = self
+        let table_names = self
             .client
-            .list_tables(&ListTablesQuery::default())
-            .await;
-
-        let table_names = result.map(|output|
-            //@todo When there are more than 100 tables.
-            output
-                .table_names()
-                .unwrap_or(&[])
-                .to_vec()
-        )?;
+            .list_all_tables(ListTablesQuery::new())
+            .collect::<Result<Vec<_>, _>>()
+            .await?;
 
         Ok(table_names)
     }
@@ -62,3 +55,34 @@ impl Reset {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use dynamodb_client::api::DynamoDbApi;
+    use dynamodb_client::in_memory::InMemoryDynamoDb;
+    use dynamodb_client::query::create_table::CreateTableQuery;
+    use dynamodb_client::query::list_tables::ListTablesQuery;
+    use tokio_stream::StreamExt;
+
+    use crate::command::reset::Reset;
+
+    #[tokio::test]
+    async fn test_reset_deletes_all_tables() {
+        let dynamodb = InMemoryDynamoDb::new();
+        let query = serde_json::from_str::<CreateTableQuery>(include_str!("../resource/migrations.json")).unwrap();
+
+        for table_name in ["migrations", "Messages"] {
+            dynamodb.create_table(table_name, &query).await.unwrap();
+        }
+
+        Reset::new(dynamodb.clone()).execute().await.unwrap();
+
+        let table_names = dynamodb
+            .list_all_tables(ListTablesQuery::new())
+            .collect::<Result<Vec<_>, _>>()
+            .await
+            .unwrap();
+
+        assert!(table_names.is_empty());
+    }
+}
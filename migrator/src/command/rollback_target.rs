@@ -0,0 +1,33 @@
+use chrono::NaiveDateTime;
+
+use crate::command::create::VERSION_FORMAT;
+
+/// How far `migrate down` rolls back.
+#[derive(PartialEq, Debug, Clone)]
+pub enum RollbackTarget {
+    /// The last N applied migrations.
+    Steps(usize),
+    /// Every migration applied after this version. The version itself stays applied.
+    Version(String),
+}
+
+impl Default for RollbackTarget {
+    fn default() -> Self {
+        RollbackTarget::Steps(1)
+    }
+}
+
+impl RollbackTarget {
+    /// Checks that `version` is a full `YYYYMMDDHHMM` migration version, as `--version` takes it.
+    pub fn version(version: &str) -> Result<Self, String> {
+        let valid = version.len() == 12
+            && version.bytes().all(|byte| byte.is_ascii_digit())
+            && NaiveDateTime::parse_from_str(version, VERSION_FORMAT).is_ok();
+
+        if valid {
+            Ok(RollbackTarget::Version(version.to_string()))
+        } else {
+            Err(format!("Version must be a YYYYMMDDHHMM migration version. Version: {}", version))
+        }
+    }
+}
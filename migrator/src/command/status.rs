@@ -0,0 +1,220 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use aws_sdk_dynamodb::model::AttributeValue;
+use dynamodb_client::api::DynamoDbApi;
+use dynamodb_client::client::ExistsTableResultType;
+use dynamodb_client::query::scan::ScanQuery;
+use tokio_stream::StreamExt;
+
+use crate::command::migrate::{file_name_of, is_down_file, Migrate, DEFAULT_MIGRATION_FILE_PATH};
+use crate::command::migrate_operation_type::MigrateOperationType;
+use crate::command::{ExitCode, Output};
+
+#[derive(PartialEq, Debug, Clone)]
+pub enum MigrationStatus {
+    Applied { executed_at: String },
+    Pending,
+    /// Recorded in the `migrations` table, but the file no longer exists.
+    Orphaned { executed_at: String },
+}
+
+#[derive(Debug, Clone)]
+pub struct Status<D: DynamoDbApi> {
+    client: D
+}
+
+impl<D: DynamoDbApi> Status<D> {
+    pub fn new(client: D) -> Self {
+        Self { client }
+    }
+
+    /// Fails with a non-zero exit code while migrations are pending.
+    pub async fn execute(&self, migrate_path: Option<&PathBuf>) -> Result<Output> {
+        let path = migrate_path
+            .cloned()
+            .unwrap_or_else(|| PathBuf::from(DEFAULT_MIGRATION_FILE_PATH));
+
+        let statuses = self
+            .statuses(path)
+            .await
+            .map_err(|error| anyhow!(format!("Status failed. : {}", error)))?;
+
+        let mut lines = vec![];
+        let mut pending_count = 0;
+        for (file_name, status) in &statuses {
+            lines.push(match status {
+                MigrationStatus::Applied { executed_at } => {
+                    format!("Applied   {} (ExecutedAt: {})", file_name, executed_at)
+                }
+                MigrationStatus::Pending => {
+                    pending_count += 1;
+
+                    format!("Pending   {}", file_name)
+                }
+                MigrationStatus::Orphaned { executed_at } => {
+                    format!("Orphaned  {} (ExecutedAt: {})", file_name, executed_at)
+                }
+            });
+        }
+
+        lines.push(format!("{} migrations are pending.", pending_count));
+
+        let exit_code = if pending_count == 0 {
+            ExitCode::Succeed
+        } else {
+            ExitCode::Failed
+        };
+
+        Ok(Output::new(exit_code, lines.join("\n")))
+    }
+
+    /// Every migration file and every record, ordered by file name.
+    pub async fn statuses(&self, target_path: PathBuf) -> Result<BTreeMap<String, MigrationStatus>> {
+        let mut statuses = BTreeMap::new();
+
+        // Only files `migrate up` would apply; it skips down files and unsupported operations.
+        for file in Migrate::<D>::read_migration_files(target_path)? {
+            if is_down_file(&file) {
+                continue;
+            }
+
+            if let MigrateOperationType::UndefinedOperation(_) = MigrateOperationType::resolve(&file)? {
+                continue;
+            }
+
+            statuses.insert(file_name_of(&file)?, MigrationStatus::Pending);
+        }
+
+        for (file_name, executed_at) in self.find_records().await? {
+            let status = match statuses.get(&file_name) {
+                Some(_) => MigrationStatus::Applied { executed_at },
+                None => MigrationStatus::Orphaned { executed_at },
+            };
+
+            statuses.insert(file_name, status);
+        }
+
+        Ok(statuses)
+    }
+
+    /// FileName and ExecutedAt of every record. None before the first `migrate up`.
+    async fn find_records(&self) -> Result<Vec<(String, String)>> {
+        if self.client.exists_table("migrations").await? == ExistsTableResultType::NotFound {
+            return Ok(vec![]);
+        }
+
+        let items = self
+            .client
+            .scan(ScanQuery::new("migrations"))
+            .collect::<Result<Vec<_>, _>>()
+            .await?;
+
+        let string_of = |value: Option<&AttributeValue>| match value {
+            Some(AttributeValue::S(value)) => value.clone(),
+            _ => String::new(),
+        };
+
+        Ok(items
+            .iter()
+            .map(|item| (string_of(item.get("FileName")), string_of(item.get("ExecutedAt"))))
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use dynamodb_client::api::DynamoDbApi;
+    use dynamodb_client::in_memory::InMemoryDynamoDb;
+    use dynamodb_client::query::delete_item::DeleteItemQuery;
+    use dynamodb_client::query::put_item::{Items, PutItemQuery};
+    use pretty_assertions::assert_eq;
+
+    use crate::command::migrate::Migrate;
+    use crate::command::migrate_type::MigrateType;
+    use crate::command::migration_dir::MigrationDir;
+    use crate::command::status::{MigrationStatus, Status};
+
+    #[tokio::test]
+    async fn test_status() {
+        let dynamodb = InMemoryDynamoDb::new();
+        let status = Status::new(dynamodb.clone());
+        let path = PathBuf::from("migrations");
+
+        let output = status.execute(Some(&path)).await.unwrap();
+        assert_eq!(1, *output.exit_code() as i32);
+        assert!(output.message().ends_with("3 migrations are pending."));
+
+        Migrate::new(dynamodb.clone())
+            .execute(&MigrateType::Up, Some(&path))
+            .await
+            .unwrap();
+
+        let output = status.execute(Some(&path)).await.unwrap();
+        assert_eq!(0, *output.exit_code() as i32);
+
+        let file_name = "202302150230_delete_test_table.delete_table.json";
+        let keys = HashMap::from([("FileName".to_string(), AttributeValue::S(file_name.to_string()))]);
+        dynamodb
+            .delete_item(DeleteItemQuery::new("migrations", keys))
+            .await
+            .unwrap();
+
+        let items = Items::from([
+            ("FileName".to_string(), AttributeValue::S("202301010000_removed.create_table.json".to_string())),
+            ("ExecutedAt".to_string(), AttributeValue::S("2023-01-01 00:00:00 UTC".to_string())),
+        ]);
+        dynamodb
+            .put_item(PutItemQuery::new("migrations", items, None, None::<String>))
+            .await
+            .unwrap();
+
+        let statuses = status.statuses(path.clone()).await.unwrap();
+        assert_eq!(4, statuses.len());
+        assert_eq!(Some(&MigrationStatus::Pending), statuses.get(file_name));
+        assert_eq!(
+            Some(&MigrationStatus::Orphaned {
+                executed_at: "2023-01-01 00:00:00 UTC".to_string()
+            }),
+            statuses.get("202301010000_removed.create_table.json")
+        );
+        assert!(matches!(
+            statuses.get("202302142330_add_message_table.create_table.json"),
+            Some(MigrationStatus::Applied { .. })
+        ));
+
+        let output = status.execute(Some(&path)).await.unwrap();
+        assert_eq!(1, *output.exit_code() as i32);
+        assert!(output.message().ends_with("1 migrations are pending."));
+    }
+
+    #[tokio::test]
+    async fn test_status_ignores_unsupported_files() {
+        let dir = MigrationDir::new();
+        dir.copy("202302142330_add_message_table.create_table.json");
+        dir.write("README.md", "Migrations of the Messages table.");
+        dir.write(".gitkeep", "");
+        dir.write("202303010000_typo.craete_table.json", "{}");
+
+        let dynamodb = InMemoryDynamoDb::new();
+        Migrate::new(dynamodb.clone())
+            .execute(&MigrateType::Up, Some(&dir.path()))
+            .await
+            .unwrap();
+
+        let status = Status::new(dynamodb);
+        let statuses = status.statuses(dir.path()).await.unwrap();
+        assert_eq!(
+            vec!["202302142330_add_message_table.create_table.json"],
+            statuses.keys().collect::<Vec<_>>()
+        );
+
+        let output = status.execute(Some(&dir.path())).await.unwrap();
+        assert_eq!(0, *output.exit_code() as i32);
+    }
+}
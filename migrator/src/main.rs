@@ -4,10 +4,13 @@ use std::path::PathBuf;
 use std::process::exit;
 use dynamodb_client::client::Client;
 
+use crate::command::create::{Create as CreateCommand, MigrationTemplate};
 use crate::command::list::List as ListCommand;
 use crate::command::migrate::Migrate as MigrateCommand;
 use crate::command::migrate_type::MigrateType;
 use crate::command::reset::Reset as ResetCommand;
+use crate::command::rollback_target::RollbackTarget;
+use crate::command::status::Status as StatusCommand;
 use crate::settings::Settings;
 
 mod command;
@@ -31,13 +34,34 @@ enum Commands {
 
         #[arg(short, long, required = false)]
         path: Option<PathBuf>,
+
+        /// Number of migrations `down` rolls back. Defaults to 1.
+        #[arg(long, conflicts_with = "version")]
+        steps: Option<usize>,
+
+        /// `down` rolls back every migration newer than this version, e.g. 202302142330.
+        #[arg(long, value_parser = RollbackTarget::version)]
+        version: Option<RollbackTarget>,
     },
     /// Display command list.
     List {},
     /// Create migrate file.
-    Create {},
+    Create {
+        name: String,
+
+        #[arg(short, long = "type", value_enum)]
+        template: MigrationTemplate,
+
+        #[arg(short, long, required = false)]
+        path: Option<PathBuf>,
+    },
     /// Reset migration.
     Reset {},
+    /// Display applied, pending and orphaned migrations. Exits with 1 while migrations are pending.
+    Status {
+        #[arg(short, long, required = false)]
+        path: Option<PathBuf>,
+    },
 }
 
 #[tokio::main]
@@ -57,10 +81,19 @@ async fn main() -> Result<()> {
         .unwrap();
 
     match &cli.command {
-        Some(Commands::Migrate { command, path }) => {
+        Some(Commands::Migrate { command, path, steps, version }) => {
             let migrate = MigrateCommand::new(client);
 
-            let result = migrate.execute(command, path.as_ref()).await;
+            let rollback_target = match (steps, version) {
+                (_, Some(version)) => version.clone(),
+                (Some(steps), None) => RollbackTarget::Steps(*steps),
+                (None, None) => RollbackTarget::default(),
+            };
+
+            let result = match command {
+                MigrateType::Up => migrate.execute(command, path.as_ref()).await,
+                MigrateType::Down => migrate.rollback(path.as_ref(), &rollback_target).await,
+            };
 
             match result {
                 Ok(output) => {
@@ -84,7 +117,22 @@ async fn main() -> Result<()> {
 
             exit(*(output.exit_code()) as i32);
         }
-        Some(Commands::Create {}) => exit(0),
+        Some(Commands::Create { name, template, path }) => {
+            let create = CreateCommand::new();
+
+            match create.execute(name, template, path.as_ref()) {
+                Ok(output) => {
+                    println!("{}", output.message());
+
+                    exit(*(output.exit_code()) as i32);
+                }
+                Err(error) => {
+                    println!("{}", error);
+
+                    exit(1);
+                }
+            }
+        }
         Some(Commands::Reset {}) => {
             let reset = ResetCommand::new(client);
 
@@ -102,6 +150,23 @@ async fn main() -> Result<()> {
                 }
             }
         }
+        Some(Commands::Status { path }) => {
+            let status = StatusCommand::new(client);
+
+            let result = status.execute(path.as_ref()).await;
+            match result {
+                Ok(output) => {
+                    println!("{}", output.message());
+
+                    exit(*(output.exit_code()) as i32);
+                }
+                Err(error) => {
+                    println!("{}", error);
+
+                    exit(1);
+                }
+            }
+        }
         None => {
             if let Some(name) = cli.name.as_deref() {
                 println!("Command {} was not found.", name);
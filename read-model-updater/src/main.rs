@@ -114,19 +114,35 @@ mod tests {
     use aws_lambda_events::dynamodb::Event;
     use aws_lambda_events::serde_json;
     use lambda_runtime::LambdaEvent;
+    use crate::message_dto::MessageDto;
     use crate::push_to_read_model;
 
-    #[tokio::test]
-    async fn test_function_push_to_read_model() {
+    fn event() -> Event {
         let data = include_bytes!("../tests/fixtures/example-dynamodb-event.json");
-        let mut event: Event = serde_json::from_slice(data).expect("Cannot parse json.");
 
+        serde_json::from_slice(data).expect("Cannot parse json.")
+    }
+
+    #[test]
+    fn test_message_dto_from_event() {
+        let mut event = event();
         let event_record = event.records.pop().unwrap();
 
-        let result = push_to_read_model(&event_record).await;
+        let dto = MessageDto::from_event(&event_record).unwrap();
+
+        assert_eq!("INSERT", event_record.event_name);
+        assert!(!dto.message_write_id().is_empty());
+        assert!(dto.created_at().is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs MySQL on localhost:3306"]
+    async fn test_function_push_to_read_model() {
+        let mut event = event();
+        let event_record = event.records.pop().unwrap();
 
-        dbg!("{}", &result);
+        let result = push_to_read_model(&event_record).await;
 
-        assert!(result.is_ok())
+        assert!(result.is_ok(), "{:?}", result)
     }
 }
@@ -1,3 +1,4 @@
+use dynamodb_client::api::DynamoDbApi;
 use message::{MessageReply, MessageRequest};
 use tonic::{Code, Request, Response, Status};
 
@@ -8,18 +9,18 @@ pub mod message {
     tonic::include_proto!("message");
 }
 
-pub struct AddMessage {
-    usecase: AddMessageUsecase,
+pub struct AddMessage<D: DynamoDbApi> {
+    usecase: AddMessageUsecase<D>,
 }
 
-impl AddMessage {
-    pub fn new(usecase: AddMessageUsecase) -> Self {
+impl<D: DynamoDbApi> AddMessage<D> {
+    pub fn new(usecase: AddMessageUsecase<D>) -> Self {
         Self { usecase }
     }
 }
 
 #[tonic::async_trait]
-impl Message for AddMessage {
+impl<D: DynamoDbApi + 'static> Message for AddMessage<D> {
     async fn add_message(
         &self,
         request: Request<MessageRequest>,
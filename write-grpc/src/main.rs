@@ -1,5 +1,6 @@
 use anyhow::{Context, Result};
-use tonic::transport::Server;
+use dynamodb_client::client::Client;
+use tonic::transport::{Server, Uri};
 
 pub mod adapter;
 pub mod usecase;
@@ -18,7 +19,9 @@ async fn main() -> Result<()> {
         .parse()
         .context("Failed start grpc server. Error: parse error.")?;
 
-    let message = AddMessage::new(AddMessageUsecase::default());
+    let message = AddMessage::new(AddMessageUsecase::new(Client::new(Uri::from_static(
+        "http://localhost:4566/",
+    ))));
 
     println!("MessageServer listening on {}", addr);
 
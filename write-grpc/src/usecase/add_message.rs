@@ -1,46 +1,78 @@
 use anyhow::Result;
-use aws_sdk_dynamodb::model::AttributeValue;
 use aws_sdk_dynamodb::output::PutItemOutput;
 use chrono::Utc;
-use dynamodb_client::client::Client;
-use dynamodb_client::query::put_item::{Items, PutItemQuery};
-use std::collections::HashMap;
-use tonic::transport::Uri;
+use dynamodb_client::api::DynamoDbApi;
+use serde::Serialize;
 use uuid::Uuid;
 
 use crate::adapter::controllers::add_message_controller::message::MessageRequest;
 
-#[derive(Debug, Default, Copy, Clone)]
-pub struct AddMessageUsecase {}
-
-impl AddMessageUsecase {
-    pub async fn run(self, request: &MessageRequest) -> Result<PutItemOutput> {
-        let mut items: Items = HashMap::new();
-
-        items.insert("message_id".to_string(), AttributeValue::S(Uuid::new_v4().to_string()));
-        items.insert(
-            String::from("account_id"),
-            AttributeValue::S(request.account_id.to_string()),
-        );
-        items.insert(
-            String::from("channel_id"),
-            AttributeValue::S(request.channel_id.to_string()),
-        );
-        items.insert(
-            String::from("posted_at"),
-            AttributeValue::S(Utc::now().to_string()),
-        );
-        items.insert(
-            String::from("message"),
-            AttributeValue::S(request.message.to_string()),
-        );
-        items.insert(
-            String::from("message_type"),
-            AttributeValue::S("post".to_string()),
-        );
-
-        let query = PutItemQuery::new("Messages".to_string(), items, None, None::<String>);
-
-        Client::new(Uri::from_static("http://localhost:4566/")).put_item(query).await
+#[derive(Debug, Serialize)]
+struct MessageItem {
+    message_id: String,
+    account_id: String,
+    channel_id: String,
+    posted_at: String,
+    message: String,
+    message_type: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct AddMessageUsecase<D: DynamoDbApi> {
+    dynamodb: D,
+}
+
+impl<D: DynamoDbApi> AddMessageUsecase<D> {
+    pub fn new(dynamodb: D) -> Self {
+        Self { dynamodb }
+    }
+
+    pub async fn run(&self, request: &MessageRequest) -> Result<PutItemOutput> {
+        let item = MessageItem {
+            message_id: Uuid::new_v4().to_string(),
+            account_id: request.account_id.to_string(),
+            channel_id: request.channel_id.to_string(),
+            posted_at: Utc::now().to_string(),
+            message: request.message.to_string(),
+            message_type: "post".to_string(),
+        };
+
+        Ok(self.dynamodb.put_typed("Messages", &item).await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aws_sdk_dynamodb::model::AttributeValue;
+    use dynamodb_client::api::DynamoDbApi;
+    use dynamodb_client::in_memory::InMemoryDynamoDb;
+    use dynamodb_client::query::create_table::CreateTableQuery;
+    use pretty_assertions::assert_eq;
+
+    use crate::adapter::controllers::add_message_controller::message::MessageRequest;
+    use crate::usecase::add_message::AddMessageUsecase;
+
+    #[tokio::test]
+    async fn test_run_puts_message() {
+        let dynamodb = InMemoryDynamoDb::new();
+        let query = serde_json::from_str::<CreateTableQuery>(include_str!(
+            "../../../migrator/migrations/202302142330_add_message_table.create_table.json"
+        ))
+        .unwrap();
+        dynamodb.create_table("Messages", &query).await.unwrap();
+
+        let request = MessageRequest {
+            channel_id: "channel".to_string(),
+            account_id: "account".to_string(),
+            message: "hello".to_string(),
+        };
+
+        AddMessageUsecase::new(dynamodb.clone()).run(&request).await.unwrap();
+
+        let items = dynamodb.items("Messages");
+        assert_eq!(1, items.len());
+        assert_eq!(Some(&AttributeValue::S("hello".to_string())), items[0].get("message"));
+        assert_eq!(Some(&AttributeValue::S("post".to_string())), items[0].get("message_type"));
+        assert_eq!(1, dynamodb.stream_records("Messages").len());
     }
 }